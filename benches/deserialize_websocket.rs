@@ -3,7 +3,8 @@
 /// This module benchmarks ALL WebSocket message types with special focus on the MOST CRITICAL
 /// hot paths for live trading: orderbook updates, trade notifications, and order status updates.
 use criterion::{BenchmarkId, Criterion, Throughput, criterion_group, criterion_main};
-use kuest_client_sdk::clob::ws::types::response::OrderBookLevel;
+use kuest_client_sdk::clob::ws::interest::MessageInterest;
+use kuest_client_sdk::clob::ws::types::response::{OrderBookLevel, parse_if_interested};
 use kuest_client_sdk::clob::ws::{
     BestBidAsk, BookUpdate, LastTradePrice, MakerOrder, MarketResolved, MidpointUpdate, NewMarket,
     OrderMessage, PriceChange, TickSizeChange, TradeMessage, WsMessage,
@@ -442,6 +443,94 @@ fn bench_orderbook_level(c: &mut Criterion) {
     group.finish();
 }
 
+/// The previous `parse_if_interested`, which built a `Value` for every frame, kept as a baseline.
+fn parse_via_value(bytes: &[u8], interest: MessageInterest) -> Vec<WsMessage> {
+    let value: serde_json::Value =
+        serde_json::from_slice(bytes).expect("Deserialization should succeed");
+
+    let interested = |elem: &serde_json::Value| {
+        elem.get("event_type")
+            .and_then(serde_json::Value::as_str)
+            .is_some_and(|event_type| interest.is_interested_in_event(event_type))
+    };
+
+    match value {
+        serde_json::Value::Array(arr) => arr
+            .iter()
+            .filter(|elem| interested(elem))
+            .filter_map(|elem| serde_json::from_value(elem.clone()).ok())
+            .collect(),
+        value if interested(&value) => vec![serde_json::from_value(value).expect("valid message")],
+        _ => vec![],
+    }
+}
+
+fn bench_parse_if_interested(c: &mut Criterion) {
+    let mut group = c.benchmark_group("websocket/parse_if_interested");
+
+    // Frames as received from the connection, including the event type
+    let levels: Vec<String> = (0..20)
+        .map(|i| {
+            format!(
+                r#"{{"price": "0.{:02}", "size": "{}.0"}}"#,
+                55 - i,
+                100 * (i + 1)
+            )
+        })
+        .collect();
+    let book = format!(
+        r#"{{
+        "event_type": "book",
+        "asset_id": "123456789",
+        "market": "0x0000000000000000000000000000000000000000000000000000000000000001",
+        "timestamp": "1234567890123",
+        "hash": "abc123",
+        "bids": [{}],
+        "asks": [{}]
+    }}"#,
+        levels.join(", "),
+        levels.join(", ")
+    );
+
+    let price_change = r#"{
+        "event_type": "price_change",
+        "market": "0x0000000000000000000000000000000000000000000000000000000000000001",
+        "timestamp": "1234567890123",
+        "price_changes": [{"asset_id": "123456789", "price": "0.65", "side": "BUY"}]
+    }"#;
+    let trade = r#"{
+        "event_type": "trade",
+        "id": "trade_123",
+        "market": "0x0000000000000000000000000000000000000000000000000000000000000001",
+        "asset_id": "123456789",
+        "side": "BUY",
+        "size": "25.0",
+        "price": "0.55",
+        "status": "MATCHED",
+        "maker_orders": []
+    }"#;
+    let batch = format!("[{book}, {}]", [price_change; 9].join(", "));
+
+    for (name, json, interest) in [
+        ("book_20_levels", book.as_str(), MessageInterest::MARKET),
+        ("batch_10", batch.as_str(), MessageInterest::MARKET),
+        ("filtered_out", trade, MessageInterest::MARKET),
+    ] {
+        group.throughput(Throughput::Bytes(json.len() as u64));
+        group.bench_with_input(BenchmarkId::new("value", name), &json, |b, json| {
+            b.iter(|| parse_via_value(std::hint::black_box(json.as_bytes()), interest));
+        });
+        group.bench_with_input(BenchmarkId::new("borrowed", name), &json, |b, json| {
+            b.iter(|| {
+                parse_if_interested(std::hint::black_box(json.as_bytes()), &interest)
+                    .expect("Deserialization should succeed")
+            });
+        });
+    }
+
+    group.finish();
+}
+
 criterion_group!(
     websocket_benches,
     bench_ws_message,
@@ -449,6 +538,7 @@ criterion_group!(
     bench_user_messages,
     bench_market_data_updates,
     bench_market_events,
-    bench_orderbook_level
+    bench_orderbook_level,
+    bench_parse_if_interested
 );
 criterion_main!(websocket_benches);
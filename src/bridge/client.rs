@@ -8,6 +8,7 @@ use super::types::{
     DepositRequest, DepositResponse, StatusRequest, StatusResponse, SupportedAssetsResponse,
 };
 use crate::error::Error;
+use crate::retry::RetryPolicy;
 use crate::Result;
 
 const DEFAULT_HOST: &str = "https://bridge.kuest.com/#disabled";
@@ -47,6 +48,7 @@ pub struct Client {
     host: Url,
     client: ReqwestClient,
     disabled: bool,
+    retry_policy: Option<RetryPolicy>,
 }
 
 impl Default for Client {
@@ -77,9 +79,20 @@ impl Client {
             host,
             client,
             disabled,
+            retry_policy: None,
         })
     }
 
+    /// Sets the [`RetryPolicy`] applied to requests made by this client.
+    ///
+    /// By default, failed requests are not retried.
+    #[cfg(feature = "retry")]
+    #[must_use]
+    pub fn with_retry_policy(mut self, retry_policy: RetryPolicy) -> Self {
+        self.retry_policy = Some(retry_policy);
+        self
+    }
+
     /// Returns the host URL for the client.
     #[must_use]
     pub fn host(&self) -> &Url {
@@ -130,7 +143,7 @@ impl Client {
             .json(request)
             .build()?;
 
-        crate::request(&self.client, request, None).await
+        crate::request(&self.client, request, None, self.retry_policy.as_ref()).await
     }
 
     /// Get all supported chains and tokens for deposits.
@@ -166,7 +179,7 @@ impl Client {
             .request(Method::GET, format!("{}supported-assets", self.host()))
             .build()?;
 
-        crate::request(&self.client, request, None).await
+        crate::request(&self.client, request, None, self.retry_policy.as_ref()).await
     }
 
     /// Get the transaction status for all deposits associated with a given deposit address.
@@ -207,6 +220,6 @@ impl Client {
             )
             .build()?;
 
-        crate::request(&self.client, request, None).await
+        crate::request(&self.client, request, None, self.retry_policy.as_ref()).await
     }
 }
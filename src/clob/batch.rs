@@ -0,0 +1,246 @@
+//! Submission of large sets of orders with an outcome for each of them.
+//!
+//! [`Client::post_batch`] splits the orders into chunks of at most [`MAX_BATCH_SIZE`], posts the
+//! chunks with [`Client::post_orders`], running up to `concurrency` requests at a time, and
+//! returns an [`OrderOutcome`] for every order, aligned index for index with the input:
+//!
+//! - [`OrderOutcome::Posted`] when the CLOB accepted the order.
+//! - [`OrderOutcome::Rejected`] when the order was refused, either by the CLOB in its own response
+//!   or by rejecting the whole chunk with a client error status, or by a client-side check such
+//!   as the risk guard or the balance check failing the whole chunk before it was sent.
+//! - [`OrderOutcome::Failed`] when the chunk did not get a definite answer: a transport error, a
+//!   server error or a rate limit, enforced by the CLOB or client-side. Every order of the chunk
+//!   shares the same error.
+//!
+//! Only [`OrderOutcome::Failed`] orders are worth sending again unchanged, see
+//! [`OrderOutcome::is_retryable`].
+//!
+//! [`Client::post_batch`]: crate::clob::Client::post_batch
+//! [`Client::post_orders`]: crate::clob::Client::post_orders
+
+use std::sync::Arc;
+
+use crate::Result;
+use crate::clob::client::MAX_BATCH_SIZE;
+use crate::clob::funds::InsufficientFunds;
+use crate::clob::types::ApiError;
+use crate::clob::types::response::PostOrderResponse;
+use crate::error::{Error, Kind, Status, StatusCode};
+
+/// What happened to one order of a [`Client::post_batch`](crate::clob::Client::post_batch).
+#[non_exhaustive]
+#[derive(Clone, Debug)]
+pub enum OrderOutcome {
+    /// The CLOB accepted the order.
+    Posted(PostOrderResponse),
+    /// The CLOB or a client-side check refused the order.
+    Rejected {
+        /// Why the order was refused, [`ApiError::Unknown`] with the error message for client-side
+        /// checks other than the balance check.
+        reason: ApiError,
+        /// The CLOB's response for this order, or `None` when the whole chunk was refused.
+        response: Option<PostOrderResponse>,
+    },
+    /// The chunk holding the order failed before the CLOB answered for it.
+    Failed(Arc<Error>),
+}
+
+impl OrderOutcome {
+    /// Returns the ID of the order if it was posted.
+    #[must_use]
+    pub fn order_id(&self) -> Option<&str> {
+        match self {
+            OrderOutcome::Posted(response) => Some(&response.order_id),
+            _ => None,
+        }
+    }
+
+    /// Returns `true` if the order was posted.
+    #[must_use]
+    pub const fn is_posted(&self) -> bool {
+        matches!(self, OrderOutcome::Posted(_))
+    }
+
+    /// Returns `true` if the CLOB gave no definite answer for the order, so that sending it again
+    /// may succeed.
+    #[must_use]
+    pub const fn is_retryable(&self) -> bool {
+        matches!(self, OrderOutcome::Failed(_))
+    }
+}
+
+/// Returns the number of orders in each chunk of `len` orders.
+pub(crate) fn chunk_sizes(len: usize) -> impl Iterator<Item = usize> {
+    (0..len)
+        .step_by(MAX_BATCH_SIZE)
+        .map(move |start| MAX_BATCH_SIZE.min(len - start))
+}
+
+/// Returns `true` if a whole chunk was refused for reasons that sending it again will not change,
+/// that is by a client-side check or by the CLOB with a client error other than
+/// `429 Too Many Requests`.
+fn is_rejection(error: &Error) -> bool {
+    error.kind() == Kind::Validation
+        || error.downcast_ref::<Status>().is_some_and(|status| {
+            status.status_code.is_client_error()
+                && status.status_code != StatusCode::TOO_MANY_REQUESTS
+        })
+}
+
+/// Returns why a whole chunk was refused with `error`.
+fn rejection_reason(error: &Error) -> ApiError {
+    if error.downcast_ref::<InsufficientFunds>().is_some() {
+        return ApiError::InsufficientBalance;
+    }
+
+    error
+        .api_error()
+        .unwrap_or_else(|| ApiError::Unknown(error.to_string()))
+}
+
+/// Maps the `result` of posting a chunk of `len` orders to the outcome of each order.
+pub(crate) fn outcomes(len: usize, result: Result<Vec<PostOrderResponse>>) -> Vec<OrderOutcome> {
+    let responses = match result {
+        Ok(responses) => responses,
+        Err(error) if is_rejection(&error) => {
+            let reason = rejection_reason(&error);
+            return vec![
+                OrderOutcome::Rejected {
+                    reason,
+                    response: None,
+                };
+                len
+            ];
+        }
+        Err(error) => return vec![OrderOutcome::Failed(Arc::new(error)); len],
+    };
+
+    let mut outcomes: Vec<_> = responses
+        .into_iter()
+        .take(len)
+        .map(|response| {
+            if response.success {
+                OrderOutcome::Posted(response)
+            } else {
+                OrderOutcome::Rejected {
+                    reason: response
+                        .api_error()
+                        .unwrap_or_else(|| ApiError::Unknown(String::new())),
+                    response: Some(response),
+                }
+            }
+        })
+        .collect();
+
+    if outcomes.len() < len {
+        let missing = Arc::new(Error::validation(format!(
+            "The CLOB answered for {} of {len} orders",
+            outcomes.len()
+        )));
+        outcomes.resize(len, OrderOutcome::Failed(missing));
+    }
+
+    outcomes
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::clob::types::OrderStatusType;
+    use crate::error::Method;
+
+    fn response(order_id: &str, success: bool, error_msg: &str) -> PostOrderResponse {
+        PostOrderResponse::builder()
+            .order_id(order_id)
+            .status(OrderStatusType::Live)
+            .success(success)
+            .error_msg(error_msg)
+            .making_amount(0.into())
+            .taking_amount(0.into())
+            .build()
+    }
+
+    #[test]
+    fn chunk_sizes_should_cover_every_order() {
+        assert_eq!(chunk_sizes(0).count(), 0);
+        assert_eq!(chunk_sizes(15).collect::<Vec<_>>(), vec![15]);
+        assert_eq!(chunk_sizes(31).collect::<Vec<_>>(), vec![15, 15, 1]);
+    }
+
+    #[test]
+    fn outcomes_should_align_with_orders() {
+        let responses = vec![
+            response("0x01", true, ""),
+            response("", false, "not enough balance / allowance"),
+        ];
+        let found = outcomes(3, Ok(responses));
+
+        assert_eq!(found.len(), 3);
+        assert_eq!(found[0].order_id(), Some("0x01"));
+        assert!(matches!(
+            found[1],
+            OrderOutcome::Rejected {
+                reason: ApiError::InsufficientBalance,
+                response: Some(_),
+            }
+        ));
+        assert!(found[2].is_retryable());
+
+        let error = Error::status(
+            StatusCode::BAD_REQUEST,
+            Method::POST,
+            "/orders".to_owned(),
+            r#"{"error":"invalid nonce"}"#,
+        );
+        let found = outcomes(2, Err(error));
+        assert!(found.iter().all(|outcome| matches!(
+            outcome,
+            OrderOutcome::Rejected {
+                reason: ApiError::InvalidNonce,
+                response: None,
+            }
+        )));
+
+        let error = Error::status(
+            StatusCode::SERVICE_UNAVAILABLE,
+            Method::POST,
+            "/orders".to_owned(),
+            "",
+        );
+        let found = outcomes(2, Err(error));
+        assert!(found.iter().all(OrderOutcome::is_retryable));
+    }
+
+    #[test]
+    fn outcomes_should_reject_chunks_failing_client_side_checks() {
+        let found = outcomes(
+            2,
+            Err(Error::validation("order exceeds the notional limit")),
+        );
+        assert!(found.iter().all(|outcome| matches!(
+            outcome,
+            OrderOutcome::Rejected {
+                reason: ApiError::Unknown(message),
+                response: None,
+            } if message.contains("notional limit")
+        )));
+        assert!(!found.iter().any(OrderOutcome::is_retryable));
+
+        let error = Error::from(InsufficientFunds {
+            shortfalls: Vec::new(),
+        });
+        let found = outcomes(1, Err(error));
+        assert!(matches!(
+            found[0],
+            OrderOutcome::Rejected {
+                reason: ApiError::InsufficientBalance,
+                response: None,
+            }
+        ));
+
+        // Client-side rate limits are worth waiting out
+        let error = Error::with_source(Kind::RateLimited, Error::validation("quota exceeded"));
+        let found = outcomes(1, Err(error));
+        assert!(found[0].is_retryable());
+    }
+}
@@ -0,0 +1,125 @@
+//! Order book hashing compatible with the hashes published by the CLOB.
+//!
+//! Every order book the server sends, over REST or the market WebSocket channel, carries a
+//! `hash` of its contents. The server computes it as the hex-encoded SHA-1 digest of a compact
+//! JSON document holding the market, asset ID, timestamp and price levels, with the `hash`
+//! field itself set to an empty string. [`canonical_payload`] rebuilds that document so a
+//! locally held book can be checked against the server's hash without fetching it again.
+//!
+//! Prices and sizes are written in their shortest decimal form (`"0.5"`, `"100"`), and levels
+//! are written in the order they are given, which should match the order the server sends:
+//! bids from the lowest price up and asks from the highest price down.
+
+use serde::Serialize;
+use sha1::{Digest as _, Sha1};
+
+use crate::types::{B256, Decimal, U256};
+
+/// Builds the canonical JSON document the server hashes for an order book.
+///
+/// `timestamp` is the book's Unix timestamp in milliseconds. `bids` and `asks` yield
+/// `(price, size)` pairs in the order the server emits them.
+#[must_use]
+pub fn canonical_payload<B, A>(
+    market: B256,
+    asset_id: U256,
+    timestamp: i64,
+    bids: B,
+    asks: A,
+) -> String
+where
+    B: IntoIterator<Item = (Decimal, Decimal)>,
+    A: IntoIterator<Item = (Decimal, Decimal)>,
+{
+    let payload = CanonicalBook {
+        market: market.to_string(),
+        asset_id: asset_id.to_string(),
+        timestamp: timestamp.to_string(),
+        hash: "",
+        bids: bids.into_iter().map(CanonicalLevel::from).collect(),
+        asks: asks.into_iter().map(CanonicalLevel::from).collect(),
+    };
+
+    // Serializing a struct of plain strings cannot fail
+    serde_json::to_string(&payload).unwrap_or_default()
+}
+
+/// Returns the hex-encoded SHA-1 digest of a payload built by [`canonical_payload`].
+#[must_use]
+pub fn digest(payload: &str) -> String {
+    format!("{:x}", Sha1::digest(payload.as_bytes()))
+}
+
+/// Compares a computed hash with one received from the server.
+///
+/// The comparison ignores ASCII case and an optional `0x` prefix on `expected`.
+#[must_use]
+pub fn matches(computed: &str, expected: &str) -> bool {
+    let expected = expected.strip_prefix("0x").unwrap_or(expected);
+    computed.eq_ignore_ascii_case(expected)
+}
+
+#[derive(Serialize)]
+struct CanonicalBook {
+    market: String,
+    asset_id: String,
+    timestamp: String,
+    hash: &'static str,
+    bids: Vec<CanonicalLevel>,
+    asks: Vec<CanonicalLevel>,
+}
+
+#[derive(Serialize)]
+struct CanonicalLevel {
+    price: String,
+    size: String,
+}
+
+impl From<(Decimal, Decimal)> for CanonicalLevel {
+    fn from((price, size): (Decimal, Decimal)) -> Self {
+        Self {
+            price: price.normalize().to_string(),
+            size: size.normalize().to_string(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rust_decimal_macros::dec;
+
+    use super::*;
+    use crate::types::b256;
+
+    #[test]
+    fn canonical_payload_should_be_compact_and_ordered() {
+        let payload = canonical_payload(
+            b256!("0000000000000000000000000000000000000000000000000000000000000001"),
+            U256::from(42),
+            1_000,
+            [(dec!(0.480), dec!(30.00)), (dec!(0.49), dec!(20))],
+            [(dec!(0.53), dec!(60))],
+        );
+
+        assert_eq!(
+            payload,
+            r#"{"market":"0x0000000000000000000000000000000000000000000000000000000000000001","asset_id":"42","timestamp":"1000","hash":"","bids":[{"price":"0.48","size":"30"},{"price":"0.49","size":"20"}],"asks":[{"price":"0.53","size":"60"}]}"#
+        );
+    }
+
+    #[test]
+    fn digest_should_be_sha1_hex() {
+        assert_eq!(digest("abc"), "a9993e364706816aba3e25717850c26c9cd0d89d");
+    }
+
+    #[test]
+    fn matches_should_ignore_prefix_and_case() {
+        let computed = "a9993e364706816aba3e25717850c26c9cd0d89d";
+
+        assert!(matches(
+            computed,
+            "0xA9993E364706816ABA3E25717850C26C9CD0D89D"
+        ));
+        assert!(!matches(computed, "0xdeadbeef"));
+    }
+}
@@ -1,21 +1,20 @@
-use std::borrow::Cow;
+use std::collections::HashMap;
 use std::marker::PhantomData;
 use std::mem;
 use std::sync::Arc;
 #[cfg(feature = "heartbeats")]
 use std::time::Duration;
 
-use alloy::dyn_abi::Eip712Domain;
 use alloy::primitives::U256;
 use alloy::signers::Signer;
-use alloy::sol_types::SolStruct as _;
 use async_stream::try_stream;
 use bon::Builder;
 use chrono::{NaiveDate, Utc};
 use dashmap::DashMap;
-use futures::Stream;
+use futures::{Stream, StreamExt as _, TryStreamExt as _};
 use reqwest::header::{HeaderMap, HeaderValue};
 use reqwest::{Client as ReqwestClient, Method, Request};
+use serde::de::DeserializeOwned;
 use serde_json::json;
 #[cfg(all(feature = "tracing", feature = "heartbeats"))]
 use tracing::{debug, error};
@@ -27,7 +26,21 @@ use {tokio::sync::oneshot::Receiver, tokio::time, tokio_util::sync::Cancellation
 use crate::auth::builder::{Builder, Config as BuilderConfig};
 use crate::auth::state::{Authenticated, State, Unauthenticated};
 use crate::auth::{Credentials, Kind, Normal};
-use crate::clob::order_builder::{Limit, Market, OrderBuilder, generate_seed};
+use crate::clob::batch::{OrderOutcome, chunk_sizes, outcomes};
+use crate::clob::funds::{
+    BalanceCheck, CommittedCollateral, InsufficientFunds, Requirement, Shortfall, balance_request,
+    committed, requirements, shortfalls,
+};
+use crate::clob::ladder::LadderBuilder;
+use crate::clob::order_builder::{
+    Limit, Market, MarketParameters, OrderBuilder, SlippageAction, sign_order,
+};
+use crate::clob::quote::{BinaryQuote, Holdings, Outcome, QuoteLeg, binary_tokens, plan};
+use crate::clob::rate_limit::{EndpointGroup, RateLimiter, RateLimits};
+#[cfg(feature = "refresh")]
+use crate::clob::refresh::{RefreshHandle, RefreshOrder, RefreshSchedule};
+use crate::clob::risk::{Reservation, RiskGuard};
+use crate::clob::salt::{SaltGenerator, UuidSalt};
 use crate::clob::types::request::{
     BalanceAllowanceRequest, CancelMarketOrderRequest, DeleteNotificationsRequest,
     LastTradePriceRequest, MidpointRequest, OrderBookSummaryRequest, OrdersRequest,
@@ -36,14 +49,15 @@ use crate::clob::types::request::{
 };
 use crate::clob::types::response::{
     ApiKeysResponse, BalanceAllowanceResponse, BanStatusResponse, BuilderApiKeyResponse,
-    BuilderTradeResponse, CancelOrdersResponse, CurrentRewardResponse, FeeRateResponse,
-    GeoblockResponse, HeartbeatResponse, LastTradePriceResponse, LastTradesPricesResponse,
-    MarketResponse, MarketRewardResponse, MidpointResponse, MidpointsResponse, NegRiskResponse,
-    NotificationResponse, OpenOrderResponse, OrderBookSummaryResponse, OrderScoringResponse,
-    OrdersScoringResponse, Page, PostOrderResponse, PriceHistoryResponse, PriceResponse,
-    PricesResponse, RewardsPercentagesResponse, SimplifiedMarketResponse, SpreadResponse,
-    SpreadsResponse, TickSizeResponse, TotalUserEarningResponse, TradeResponse,
-    UserEarningResponse, UserRewardsEarningResponse,
+    BuilderTradeResponse, CancelOrdersResponse, CancelOutcome, CurrentRewardResponse,
+    FeeRateResponse, GeoblockResponse, HeartbeatResponse, LastTradePriceResponse,
+    LastTradesPricesResponse, MarketResponse, MarketRewardResponse, MidpointResponse,
+    MidpointsResponse, NegRiskResponse, NotificationResponse, OpenOrderResponse,
+    OrderBookSummaryResponse, OrderScoringResponse, OrdersScoringResponse, Page, PostOrderResponse,
+    PriceHistoryResponse, PriceResponse, PricesResponse, ReplaceOrderResponse,
+    RewardsPercentagesResponse, SimplifiedMarketResponse, SpreadResponse, SpreadsResponse,
+    TickSizeResponse, TotalUserEarningResponse, TradeResponse, UserEarningResponse,
+    UserRewardsEarningResponse,
 };
 #[cfg(feature = "rfq")]
 use crate::clob::types::{
@@ -54,17 +68,18 @@ use crate::clob::types::{
 };
 use crate::clob::types::{SignableOrder, SignatureType, SignedOrder, TickSize};
 use crate::error::{Error, Kind as ErrorKind, Synchronization};
-use crate::types::Address;
+use crate::retry::RetryPolicy;
+use crate::types::{Address, Decimal};
 use crate::{
-    AMOY, POLYGON, Result, Timestamp, ToQueryParams as _, auth, contract_config,
-    derive_proxy_wallet, derive_safe_wallet,
+    AMOY, POLYGON, Result, Timestamp, ToQueryParams as _, auth, derive_proxy_wallet,
+    derive_safe_wallet,
 };
 
-const ORDER_NAME: Option<Cow<'static, str>> = Some(Cow::Borrowed("Kuest CTF Exchange"));
-const VERSION: Option<Cow<'static, str>> = Some(Cow::Borrowed("1"));
-
 const TERMINAL_CURSOR: &str = "LTE="; // base64("-1")
 
+/// Maximum number of orders accepted by a single [`Client::post_orders`] request.
+pub const MAX_BATCH_SIZE: usize = 15;
+
 /// The type used to build a request to authenticate the inner [`Client<Unauthorized>`]. Calling
 /// `authenticate` on this will elevate that inner `client` into an [`Client<Authenticated<K>>`].
 pub struct AuthenticationBuilder<'signer, S: Signer, K: Kind = Normal> {
@@ -87,7 +102,7 @@ pub struct AuthenticationBuilder<'signer, S: Signer, K: Kind = Normal> {
     /// The optional [`SignatureType`], see `funder` for more information.
     signature_type: Option<SignatureType>,
     /// The optional salt/seed generator for use in creating [`SignableOrder`]s
-    salt_generator: Option<fn() -> u64>,
+    salt_generator: Option<Arc<dyn SaltGenerator>>,
 }
 
 impl<S: Signer, K: Kind> AuthenticationBuilder<'_, S, K> {
@@ -115,9 +130,11 @@ impl<S: Signer, K: Kind> AuthenticationBuilder<'_, S, K> {
         self
     }
 
+    /// Sets the [`SaltGenerator`] used for the orders of the authenticated client. Defaults to
+    /// [`UuidSalt`].
     #[must_use]
-    pub fn salt_generator(mut self, salt_generator: fn() -> u64) -> Self {
-        self.salt_generator = Some(salt_generator);
+    pub fn salt_generator<G: SaltGenerator + 'static>(mut self, salt_generator: G) -> Self {
+        self.salt_generator = Some(Arc::new(salt_generator));
         self
     }
 
@@ -231,7 +248,9 @@ impl<S: Signer, K: Kind> AuthenticationBuilder<'_, S, K> {
                 fee_rate_bps: inner.fee_rate_bps,
                 funder,
                 signature_type: self.signature_type.unwrap_or(SignatureType::Eoa),
-                salt_generator: self.salt_generator.unwrap_or(generate_seed),
+                salt_generator: self.salt_generator.unwrap_or_else(|| Arc::new(UuidSalt)),
+                rate_limiter: inner.rate_limiter,
+                committed_collateral: CommittedCollateral::default(),
             }),
             #[cfg(feature = "heartbeats")]
             heartbeat_token: DroppingCancellationToken(None),
@@ -375,12 +394,42 @@ pub struct Config {
     /// This is primarily useful for testing.
     #[builder(into)]
     geoblock_host: Option<String>,
+    /// Retry policy for failed requests. When unset, failed requests are returned as errors
+    /// without being retried.
+    #[cfg(feature = "retry")]
+    retry_policy: Option<RetryPolicy>,
+    /// Client-side request quotas per endpoint group. When unset, requests are sent as soon as
+    /// they are made.
+    rate_limits: Option<RateLimits>,
+    /// Pre-trade risk checks applied to every order posted by an authenticated [`Client`]. When
+    /// unset, orders are sent without client-side checks.
+    risk_guard: Option<RiskGuard>,
+    /// Balance and allowance check applied to every order posted by an authenticated
+    /// [`Client`], see [`Client::check_funds`]. When unset, orders are sent without it.
+    balance_check: Option<BalanceCheck>,
     #[cfg(feature = "heartbeats")]
     #[builder(default = Duration::from_secs(5))]
     /// How often the [`Client`] will automatically submit heartbeats. The default is five (5) seconds.
     heartbeat_interval: Duration,
 }
 
+impl Config {
+    /// Returns the policy retrying failed requests, never set without the `retry` feature.
+    #[cfg(feature = "retry")]
+    const fn retry_policy(&self) -> Option<&RetryPolicy> {
+        self.retry_policy.as_ref()
+    }
+
+    #[cfg(not(feature = "retry"))]
+    #[expect(
+        clippy::unused_self,
+        reason = "Mirrors the accessor reading the policy set with the `retry` feature"
+    )]
+    const fn retry_policy(&self) -> Option<&RetryPolicy> {
+        None
+    }
+}
+
 /// The default geoblock API host (separate from CLOB host)
 const DEFAULT_GEOBLOCK_HOST: &str = "https://api.kuest.com";
 
@@ -408,17 +457,38 @@ struct ClientInner<S: State> {
     /// The signature type for this [`ClientInner`]. Defaults to [`SignatureType::Eoa`]
     signature_type: SignatureType,
     /// The salt/seed generator for use in creating [`SignableOrder`]s
-    salt_generator: fn() -> u64,
+    salt_generator: Arc<dyn SaltGenerator>,
+    /// Token buckets enforcing [`Config::rate_limits`], shared across authentication changes
+    rate_limiter: Option<RateLimiter>,
+    /// Collateral committed to the user's open buys, reused by [`Client::check_funds`]
+    committed_collateral: CommittedCollateral,
 }
 
 impl<S: State> ClientInner<S> {
+    /// Sends `request` with the configured [`Config::retry_policy`].
+    async fn send<Response: DeserializeOwned>(
+        &self,
+        request: Request,
+        headers: Option<HeaderMap>,
+    ) -> Result<Response> {
+        crate::request(&self.client, request, headers, self.config.retry_policy()).await
+    }
+
+    /// Waits for, or fails on, capacity in the configured rate limit for `group`.
+    async fn throttle(&self, group: EndpointGroup) -> Result<()> {
+        match &self.rate_limiter {
+            Some(rate_limiter) => rate_limiter.acquire(group).await,
+            None => Ok(()),
+        }
+    }
+
     pub async fn server_time(&self) -> Result<Timestamp> {
         let request = self
             .client
             .request(Method::GET, format!("{}time", self.host))
             .build()?;
 
-        crate::request(&self.client, request, None).await
+        self.send(request, None).await
     }
 }
 
@@ -428,13 +498,15 @@ impl ClientInner<Unauthenticated> {
         signer: &S,
         nonce: Option<u32>,
     ) -> Result<Credentials> {
+        self.throttle(EndpointGroup::Auth).await?;
+
         let request = self
             .client
             .request(Method::POST, format!("{}auth/api-key", self.host))
             .build()?;
         let headers = self.create_headers(signer, nonce).await?;
 
-        crate::request(&self.client, request, Some(headers)).await
+        self.send(request, Some(headers)).await
     }
 
     pub async fn derive_api_key<S: Signer>(
@@ -442,13 +514,15 @@ impl ClientInner<Unauthenticated> {
         signer: &S,
         nonce: Option<u32>,
     ) -> Result<Credentials> {
+        self.throttle(EndpointGroup::Auth).await?;
+
         let request = self
             .client
             .request(Method::GET, format!("{}auth/derive-api-key", self.host))
             .build()?;
         let headers = self.create_headers(signer, nonce).await?;
 
-        crate::request(&self.client, request, Some(headers)).await
+        self.send(request, Some(headers)).await
     }
 
     async fn create_or_derive_api_key<S: Signer>(
@@ -589,7 +663,7 @@ impl<S: State> Client<S> {
             .request(Method::GET, self.host().to_owned())
             .build()?;
 
-        crate::request(&self.inner.client, request, None).await
+        self.send(request, None).await
     }
 
     /// Returns the current server timestamp in milliseconds since Unix epoch.
@@ -611,13 +685,15 @@ impl<S: State> Client<S> {
     ///
     /// Returns an error if the request fails or the token ID is invalid.
     pub async fn midpoint(&self, request: &MidpointRequest) -> Result<MidpointResponse> {
+        self.inner.throttle(EndpointGroup::Book).await?;
+
         let params = request.query_params(None);
         let request = self
             .client()
             .request(Method::GET, format!("{}midpoint{params}", self.host()))
             .build()?;
 
-        crate::request(&self.inner.client, request, None).await
+        self.send(request, None).await
     }
 
     /// Retrieves midpoint prices for multiple market outcome tokens in a single request.
@@ -629,13 +705,15 @@ impl<S: State> Client<S> {
     ///
     /// Returns an error if the request fails or any token ID is invalid.
     pub async fn midpoints(&self, requests: &[MidpointRequest]) -> Result<MidpointsResponse> {
+        self.inner.throttle(EndpointGroup::Book).await?;
+
         let request = self
             .client()
             .request(Method::POST, format!("{}midpoints", self.host()))
             .json(requests)
             .build()?;
 
-        crate::request(&self.inner.client, request, None).await
+        self.send(request, None).await
     }
 
     /// Retrieves the current price for a market outcome token on a specific side.
@@ -647,13 +725,15 @@ impl<S: State> Client<S> {
     ///
     /// Returns an error if the request fails or the token ID is invalid.
     pub async fn price(&self, request: &PriceRequest) -> Result<PriceResponse> {
+        self.inner.throttle(EndpointGroup::Book).await?;
+
         let params = request.query_params(None);
         let request = self
             .client()
             .request(Method::GET, format!("{}price{params}", self.host()))
             .build()?;
 
-        crate::request(&self.inner.client, request, None).await
+        self.send(request, None).await
     }
 
     /// Retrieves prices for multiple market outcome tokens on their specific sides.
@@ -665,13 +745,15 @@ impl<S: State> Client<S> {
     ///
     /// Returns an error if the request fails or any token ID is invalid.
     pub async fn prices(&self, requests: &[PriceRequest]) -> Result<PricesResponse> {
+        self.inner.throttle(EndpointGroup::Book).await?;
+
         let request = self
             .client()
             .request(Method::POST, format!("{}prices", self.host()))
             .json(requests)
             .build()?;
 
-        crate::request(&self.inner.client, request, None).await
+        self.send(request, None).await
     }
 
     /// Retrieves prices for all available market outcome tokens.
@@ -683,12 +765,14 @@ impl<S: State> Client<S> {
     ///
     /// Returns an error if the request fails.
     pub async fn all_prices(&self) -> Result<PricesResponse> {
+        self.inner.throttle(EndpointGroup::Book).await?;
+
         let request = self
             .client()
             .request(Method::GET, format!("{}prices", self.host()))
             .build()?;
 
-        crate::request(&self.inner.client, request, None).await
+        self.send(request, None).await
     }
 
     /// Retrieves historical price data for a market.
@@ -709,7 +793,7 @@ impl<S: State> Client<S> {
             format!("{}prices-history{params}", self.host()),
         );
 
-        crate::request(&self.inner.client, req.build()?, None).await
+        self.send(req.build()?, None).await
     }
 
     /// Retrieves the bid-ask spread for a single market outcome token.
@@ -722,13 +806,15 @@ impl<S: State> Client<S> {
     ///
     /// Returns an error if the request fails or the token ID is invalid.
     pub async fn spread(&self, request: &SpreadRequest) -> Result<SpreadResponse> {
+        self.inner.throttle(EndpointGroup::Book).await?;
+
         let params = request.query_params(None);
         let request = self
             .client()
             .request(Method::GET, format!("{}spread{params}", self.host()))
             .build()?;
 
-        crate::request(&self.inner.client, request, None).await
+        self.send(request, None).await
     }
 
     /// Retrieves bid-ask spreads for multiple market outcome tokens.
@@ -740,13 +826,15 @@ impl<S: State> Client<S> {
     ///
     /// Returns an error if the request fails or any token ID is invalid.
     pub async fn spreads(&self, requests: &[SpreadRequest]) -> Result<SpreadsResponse> {
+        self.inner.throttle(EndpointGroup::Book).await?;
+
         let request = self
             .client()
             .request(Method::POST, format!("{}spreads", self.host()))
             .json(requests)
             .build()?;
 
-        crate::request(&self.inner.client, request, None).await
+        self.send(request, None).await
     }
 
     /// Retrieves the minimum tick size for a market outcome token.
@@ -776,8 +864,7 @@ impl<S: State> Client<S> {
             .query(&[("token_id", token_id.to_string())])
             .build()?;
 
-        let response =
-            crate::request::<TickSizeResponse>(&self.inner.client, request, None).await?;
+        let response = self.send::<TickSizeResponse>(request, None).await?;
 
         self.inner
             .tick_sizes
@@ -816,7 +903,7 @@ impl<S: State> Client<S> {
             .query(&[("token_id", token_id.to_string())])
             .build()?;
 
-        let response = crate::request::<NegRiskResponse>(&self.inner.client, request, None).await?;
+        let response = self.send::<NegRiskResponse>(request, None).await?;
 
         self.inner.neg_risk.insert(token_id, response.neg_risk);
 
@@ -852,7 +939,7 @@ impl<S: State> Client<S> {
             .query(&[("token_id", token_id.to_string())])
             .build()?;
 
-        let response = crate::request::<FeeRateResponse>(&self.inner.client, request, None).await?;
+        let response = self.send::<FeeRateResponse>(request, None).await?;
 
         self.inner.fee_rate_bps.insert(token_id, response.base_fee);
 
@@ -909,13 +996,10 @@ impl<S: State> Client<S> {
     pub async fn check_geoblock(&self) -> Result<GeoblockResponse> {
         let request = self
             .client()
-            .request(
-                Method::GET,
-                format!("{}geoblock", self.inner.geoblock_host),
-            )
+            .request(Method::GET, format!("{}geoblock", self.inner.geoblock_host))
             .build()?;
 
-        crate::request(&self.inner.client, request, None).await
+        self.send(request, None).await
     }
 
     /// Retrieves the full orderbook for a market outcome token.
@@ -931,13 +1015,15 @@ impl<S: State> Client<S> {
         &self,
         request: &OrderBookSummaryRequest,
     ) -> Result<OrderBookSummaryResponse> {
+        self.inner.throttle(EndpointGroup::Book).await?;
+
         let params = request.query_params(None);
         let request = self
             .client()
             .request(Method::GET, format!("{}book{params}", self.host()))
             .build()?;
 
-        crate::request(&self.inner.client, request, None).await
+        self.send(request, None).await
     }
 
     /// Retrieves orderbooks for multiple market outcome tokens.
@@ -952,13 +1038,15 @@ impl<S: State> Client<S> {
         &self,
         requests: &[OrderBookSummaryRequest],
     ) -> Result<Vec<OrderBookSummaryResponse>> {
+        self.inner.throttle(EndpointGroup::Book).await?;
+
         let request = self
             .client()
             .request(Method::POST, format!("{}books", self.host()))
             .json(requests)
             .build()?;
 
-        crate::request(&self.inner.client, request, None).await
+        self.send(request, None).await
     }
 
     /// Retrieves the price of the most recent trade for a market outcome token.
@@ -973,6 +1061,8 @@ impl<S: State> Client<S> {
         &self,
         request: &LastTradePriceRequest,
     ) -> Result<LastTradePriceResponse> {
+        self.inner.throttle(EndpointGroup::Book).await?;
+
         let params = request.query_params(None);
         let request = self
             .client()
@@ -982,7 +1072,7 @@ impl<S: State> Client<S> {
             )
             .build()?;
 
-        crate::request(&self.inner.client, request, None).await
+        self.send(request, None).await
     }
 
     /// Retrieves the last trade prices for multiple market outcome tokens.
@@ -997,13 +1087,15 @@ impl<S: State> Client<S> {
         &self,
         token_ids: &[LastTradePriceRequest],
     ) -> Result<Vec<LastTradesPricesResponse>> {
+        self.inner.throttle(EndpointGroup::Book).await?;
+
         let request = self
             .client()
             .request(Method::GET, format!("{}last-trades-prices", self.host()))
             .json(token_ids)
             .build()?;
 
-        crate::request(&self.inner.client, request, None).await
+        self.send(request, None).await
     }
 
     /// Retrieves detailed information for a single market by condition ID.
@@ -1023,7 +1115,7 @@ impl<S: State> Client<S> {
             )
             .build()?;
 
-        crate::request(&self.inner.client, request, None).await
+        self.send(request, None).await
     }
 
     /// Retrieves a page of all active markets.
@@ -1042,7 +1134,7 @@ impl<S: State> Client<S> {
             .request(Method::GET, format!("{}markets{cursor}", self.host()))
             .build()?;
 
-        crate::request(&self.inner.client, request, None).await
+        self.send(request, None).await
     }
 
     /// Retrieves a page of sampling markets.
@@ -1067,7 +1159,7 @@ impl<S: State> Client<S> {
             )
             .build()?;
 
-        crate::request(&self.inner.client, request, None).await
+        self.send(request, None).await
     }
 
     /// Retrieves a page of simplified market data.
@@ -1092,7 +1184,7 @@ impl<S: State> Client<S> {
             )
             .build()?;
 
-        crate::request(&self.inner.client, request, None).await
+        self.send(request, None).await
     }
 
     /// Retrieves a page of simplified sampling market data.
@@ -1117,7 +1209,7 @@ impl<S: State> Client<S> {
             )
             .build()?;
 
-        crate::request(&self.inner.client, request, None).await
+        self.send(request, None).await
     }
 
     /// Returns a stream of results, using `self` to repeatedly invoke the provided closure,
@@ -1154,6 +1246,15 @@ impl<S: State> Client<S> {
     fn client(&self) -> &ReqwestClient {
         &self.inner.client
     }
+
+    /// Sends `request` with the configured [`Config::retry_policy`].
+    async fn send<Response: DeserializeOwned>(
+        &self,
+        request: Request,
+        headers: Option<HeaderMap>,
+    ) -> Result<Response> {
+        self.inner.send(request, headers).await
+    }
 }
 
 impl Client<Unauthenticated> {
@@ -1198,6 +1299,7 @@ impl Client<Unauthenticated> {
                 .as_deref()
                 .unwrap_or(DEFAULT_GEOBLOCK_HOST),
         )?;
+        let rate_limiter = config.rate_limits.as_ref().map(RateLimiter::new);
 
         Ok(Self {
             inner: Arc::new(ClientInner {
@@ -1211,7 +1313,9 @@ impl Client<Unauthenticated> {
                 state: Unauthenticated,
                 funder: None,
                 signature_type: SignatureType::Eoa,
-                salt_generator: generate_seed,
+                salt_generator: Arc::new(UuidSalt),
+                rate_limiter,
+                committed_collateral: CommittedCollateral::default(),
             }),
             #[cfg(feature = "heartbeats")]
             heartbeat_token: DroppingCancellationToken(None),
@@ -1323,7 +1427,9 @@ impl<K: Kind> Client<Authenticated<K>> {
                 // Reset the order parameters that were previously stored on the client
                 funder: None,
                 signature_type: SignatureType::Eoa,
-                salt_generator: generate_seed,
+                salt_generator: Arc::new(UuidSalt),
+                rate_limiter: inner.rate_limiter,
+                committed_collateral: CommittedCollateral::default(),
             }),
             #[cfg(feature = "heartbeats")]
             heartbeat_token: DroppingCancellationToken(None),
@@ -1351,13 +1457,15 @@ impl<K: Kind> Client<Authenticated<K>> {
     /// Return all API keys associated with the address corresponding to the inner signer in
     /// [`Authenticated<K>`].
     pub async fn api_keys(&self) -> Result<ApiKeysResponse> {
+        self.inner.throttle(EndpointGroup::Auth).await?;
+
         let request = self
             .client()
             .request(Method::GET, format!("{}auth/api-keys", self.host()))
             .build()?;
         let headers = self.create_headers(&request).await?;
 
-        crate::request(&self.inner.client, request, Some(headers)).await
+        self.send(request, Some(headers)).await
     }
 
     /// Deletes the current API key used by this authenticated client.
@@ -1369,13 +1477,15 @@ impl<K: Kind> Client<Authenticated<K>> {
     ///
     /// Returns an error if the request fails or the API key cannot be deleted.
     pub async fn delete_api_key(&self) -> Result<serde_json::Value> {
+        self.inner.throttle(EndpointGroup::Auth).await?;
+
         let request = self
             .client()
             .request(Method::DELETE, format!("{}auth/api-key", self.host()))
             .build()?;
         let headers = self.create_headers(&request).await?;
 
-        crate::request(&self.inner.client, request, Some(headers)).await
+        self.send(request, Some(headers)).await
     }
 
     /// Checks if the account is in closed-only mode (banned from opening new positions).
@@ -1397,7 +1507,7 @@ impl<K: Kind> Client<Authenticated<K>> {
             .build()?;
         let headers = self.create_headers(&request).await?;
 
-        crate::request(&self.inner.client, request, Some(headers)).await
+        self.send(request, Some(headers)).await
     }
 
     /// Creates an [`OrderBuilder<Limit, K>`] used to construct a limit order.
@@ -1412,50 +1522,112 @@ impl<K: Kind> Client<Authenticated<K>> {
         self.order_builder()
     }
 
+    /// Creates a [`LadderBuilder`] used to lay out and post limit orders around a centre price.
+    #[must_use]
+    pub fn ladder(&self) -> LadderBuilder<K> {
+        LadderBuilder::new(self.clone())
+    }
+
+    #[cfg(feature = "refresh")]
+    /// Spawns a background task that keeps `orders` on the book as GTD orders, re-posting them
+    /// with a new expiration shortly before they expire. See the [`refresh`](crate::clob::refresh)
+    /// module for how rounds are scheduled.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if `orders` is empty, or if `schedule` leaves no time between rounds.
+    ///
+    /// # Note
+    ///
+    /// Requires the `refresh` feature to be enabled.
+    pub fn refresh_gtd<S: Signer + Send + Sync + 'static>(
+        &self,
+        signer: S,
+        orders: Vec<RefreshOrder>,
+        schedule: RefreshSchedule,
+    ) -> Result<RefreshHandle> {
+        RefreshHandle::spawn(self.clone(), signer, orders, schedule)
+    }
+
+    /// Expresses a [`BinaryQuote`] on the YES outcome of the binary market `condition_id` as
+    /// limit orders on its YES and NO tokens, using `holdings` before committing capital. See
+    /// the [`quote`](crate::clob::quote) module for how each side is expressed.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the market cannot be fetched, is not a binary market accepting orders,
+    /// or if a leg fails validation against the market's tick size.
+    pub async fn binary_quote(
+        &self,
+        condition_id: &str,
+        quote: &BinaryQuote,
+        holdings: &Holdings,
+    ) -> Result<Vec<QuoteLeg>> {
+        let market = self.market(condition_id).await?;
+        if !market.accepting_orders {
+            return Err(Error::validation(format!(
+                "Market {} is not accepting orders",
+                market.market_slug
+            )));
+        }
+
+        let (yes, no) = binary_tokens(&market)?;
+        let tick_size = TickSize::try_from(market.minimum_tick_size)?;
+
+        let mut legs = Vec::new();
+        for leg in plan(quote, holdings, market.minimum_order_size) {
+            let token_id = match leg.outcome {
+                Outcome::Yes => yes.token_id,
+                Outcome::No => no.token_id,
+            };
+            let fee_rate = self.fee_rate_bps(token_id).await?;
+
+            let order = self
+                .limit_order()
+                .token_id(token_id)
+                .side(leg.side)
+                .price(leg.price)
+                .size(leg.size)
+                .offline(MarketParameters {
+                    tick_size,
+                    fee_rate_bps: fee_rate.base_fee,
+                })
+                .build()?;
+
+            legs.push(QuoteLeg {
+                quote_side: leg.quote_side,
+                outcome: leg.outcome,
+                token_id,
+                side: leg.side,
+                price: leg.price,
+                size: leg.size,
+                order,
+            });
+        }
+
+        Ok(legs)
+    }
+
     /// Attempts to sign the provided [`SignableOrder`] using the inner signer of [`Authenticated<K>`]
     #[expect(
         clippy::missing_panics_doc,
         reason = "No need to publicly document as we are guarded by the typestate pattern. \
         We cannot call `sign` without first calling `authenticate`"
     )]
-    pub async fn sign<S: Signer>(
-        &self,
-        signer: &S,
-        SignableOrder {
-            order,
-            order_type,
-            post_only,
-        }: SignableOrder,
-    ) -> Result<SignedOrder> {
-        let token_id = order.tokenId;
-        let neg_risk = self.neg_risk(token_id).await?.neg_risk;
+    pub async fn sign<S: Signer>(&self, signer: &S, order: SignableOrder) -> Result<SignedOrder> {
+        let neg_risk = self.neg_risk(order.order.tokenId).await?.neg_risk;
         let chain_id = signer
             .chain_id()
             .expect("Validated not none in `authenticate`");
 
-        let exchange_contract = contract_config(chain_id, neg_risk)
-            .ok_or(Error::missing_contract_config(chain_id, neg_risk))?
-            .exchange;
-
-        let domain = Eip712Domain {
-            name: ORDER_NAME,
-            version: VERSION,
-            chain_id: Some(U256::from(chain_id)),
-            verifying_contract: Some(exchange_contract),
-            ..Eip712Domain::default()
-        };
-
-        let signature = signer
-            .sign_hash(&order.eip712_signing_hash(&domain))
-            .await?;
-
-        Ok(SignedOrder {
+        sign_order(
+            signer,
             order,
-            signature,
-            order_type,
-            owner: self.state().credentials.key,
-            post_only,
-        })
+            chain_id,
+            neg_risk,
+            self.state().credentials.key,
+        )
+        .await
     }
 
     /// Posts a signed order to the orderbook.
@@ -1472,26 +1644,57 @@ impl<K: Kind> Client<Authenticated<K>> {
     /// - The order price/size violates market rules
     /// - The request fails
     pub async fn post_order(&self, order: SignedOrder) -> Result<PostOrderResponse> {
+        self.inner.throttle(EndpointGroup::Orders).await?;
+        if let Some(check) = self.inner.config.balance_check {
+            self.check_funds(std::slice::from_ref(&order), check)
+                .await?;
+        }
+        let reservation = self.reserve_risk(std::slice::from_ref(&order)).await?;
+
         let request = self
             .client()
             .request(Method::POST, format!("{}order", self.host()))
             .json(&order)
             .build()?;
         let headers = self.create_headers(&request).await?;
+        #[cfg(feature = "retry")]
+        let retry_policy = self
+            .inner
+            .config
+            .retry_policy
+            .filter(|policy| policy.retry_post_order)
+            .map(RetryPolicy::including_non_idempotent);
+        #[cfg(not(feature = "retry"))]
+        let retry_policy: Option<RetryPolicy> = None;
+
+        let result = crate::request(
+            &self.inner.client,
+            request,
+            Some(headers),
+            retry_policy.as_ref(),
+        )
+        .await;
+        self.settle_risk(reservation, result.as_ref().ok().map(std::slice::from_ref));
 
-        crate::request(&self.inner.client, request, Some(headers)).await
+        result
     }
 
     /// Posts multiple signed orders to the orderbook in a single request.
     ///
     /// This is the batch version of [`Self::post_order`], allowing efficient
     /// submission of multiple orders at once. All orders are validated and
-    /// processed atomically.
+    /// processed atomically. The CLOB accepts at most [`MAX_BATCH_SIZE`] orders per request.
     ///
     /// # Errors
     ///
     /// Returns an error if any order fails validation or the request fails.
     pub async fn post_orders(&self, orders: Vec<SignedOrder>) -> Result<Vec<PostOrderResponse>> {
+        self.inner.throttle(EndpointGroup::Orders).await?;
+        if let Some(check) = self.inner.config.balance_check {
+            self.check_funds(&orders, check).await?;
+        }
+        let reservation = self.reserve_risk(&orders).await?;
+
         let request = self
             .client()
             .request(Method::POST, format!("{}orders", self.host()))
@@ -1499,7 +1702,41 @@ impl<K: Kind> Client<Authenticated<K>> {
             .build()?;
         let headers = self.create_headers(&request).await?;
 
-        crate::request(&self.inner.client, request, Some(headers)).await
+        let result: Result<Vec<PostOrderResponse>> = self.send(request, Some(headers)).await;
+        self.settle_risk(reservation, result.as_deref().ok());
+
+        result
+    }
+
+    /// Posts any number of signed orders in chunks of at most [`MAX_BATCH_SIZE`], with up to
+    /// `concurrency` chunks in flight at a time, and returns the [`OrderOutcome`] of each order
+    /// in the same order as `orders`. See the [`batch`](crate::clob::batch) module for how the
+    /// outcomes are decided.
+    ///
+    /// Chunks are independent: a chunk that fails does not stop the others, and its orders can be
+    /// posted again by keeping those whose outcome [`is_retryable`](OrderOutcome::is_retryable).
+    /// A `concurrency` of zero is treated as one.
+    pub async fn post_batch(
+        &self,
+        orders: Vec<SignedOrder>,
+        concurrency: usize,
+    ) -> Vec<OrderOutcome> {
+        let len = orders.len();
+        let mut orders = orders.into_iter();
+        let chunks: Vec<Vec<_>> = chunk_sizes(len)
+            .map(|size| orders.by_ref().take(size).collect())
+            .collect();
+
+        let outcomes: Vec<_> = futures::stream::iter(chunks)
+            .map(|chunk| async move {
+                let len = chunk.len();
+                outcomes(len, self.post_orders(chunk).await)
+            })
+            .buffered(concurrency.max(1))
+            .collect()
+            .await;
+
+        outcomes.into_iter().flatten().collect()
     }
 
     /// Attempts to return the corresponding order at the provided `order_id`
@@ -1510,7 +1747,7 @@ impl<K: Kind> Client<Authenticated<K>> {
             .build()?;
         let headers = self.create_headers(&request).await?;
 
-        crate::request(&self.inner.client, request, Some(headers)).await
+        self.send(request, Some(headers)).await
     }
 
     /// Retrieves a paginated list of orders matching the specified criteria.
@@ -1534,7 +1771,7 @@ impl<K: Kind> Client<Authenticated<K>> {
             .build()?;
         let headers = self.create_headers(&request).await?;
 
-        crate::request(&self.inner.client, request, Some(headers)).await
+        self.send(request, Some(headers)).await
     }
 
     /// Cancels a single order by its order ID.
@@ -1547,6 +1784,8 @@ impl<K: Kind> Client<Authenticated<K>> {
     /// Returns an error if the order ID is invalid, the order doesn't exist,
     /// or the request fails.
     pub async fn cancel_order(&self, order_id: &str) -> Result<CancelOrdersResponse> {
+        self.inner.throttle(EndpointGroup::Cancels).await?;
+
         let request = self
             .client()
             .request(Method::DELETE, format!("{}order", self.host()))
@@ -1554,7 +1793,10 @@ impl<K: Kind> Client<Authenticated<K>> {
             .build()?;
         let headers = self.create_headers(&request).await?;
 
-        crate::request(&self.inner.client, request, Some(headers)).await
+        let response: CancelOrdersResponse = self.send(request, Some(headers)).await?;
+        self.release_risk(&response);
+
+        Ok(response)
     }
 
     /// Cancels multiple orders by their order IDs in a single request.
@@ -1567,6 +1809,8 @@ impl<K: Kind> Client<Authenticated<K>> {
     ///
     /// Returns an error if any order ID is invalid or the request fails.
     pub async fn cancel_orders(&self, order_ids: &[&str]) -> Result<CancelOrdersResponse> {
+        self.inner.throttle(EndpointGroup::Cancels).await?;
+
         let request = self
             .client()
             .request(Method::DELETE, format!("{}orders", self.host()))
@@ -1574,7 +1818,10 @@ impl<K: Kind> Client<Authenticated<K>> {
             .build()?;
         let headers = self.create_headers(&request).await?;
 
-        crate::request(&self.inner.client, request, Some(headers)).await
+        let response: CancelOrdersResponse = self.send(request, Some(headers)).await?;
+        self.release_risk(&response);
+
+        Ok(response)
     }
 
     /// Cancels all open orders for the authenticated user.
@@ -1586,13 +1833,20 @@ impl<K: Kind> Client<Authenticated<K>> {
     ///
     /// Returns an error if the request fails.
     pub async fn cancel_all_orders(&self) -> Result<CancelOrdersResponse> {
+        self.inner.throttle(EndpointGroup::Cancels).await?;
+
         let request = self
             .client()
             .request(Method::DELETE, format!("{}cancel-all", self.host()))
             .build()?;
         let headers = self.create_headers(&request).await?;
 
-        crate::request(&self.inner.client, request, Some(headers)).await
+        let response: CancelOrdersResponse = self.send(request, Some(headers)).await?;
+        if let Some(guard) = &self.inner.config.risk_guard {
+            guard.release_all();
+        }
+
+        Ok(response)
     }
 
     /// Attempts to cancel all open orders for a particular [`CancelMarketOrderRequest::market`]
@@ -1601,6 +1855,8 @@ impl<K: Kind> Client<Authenticated<K>> {
         &self,
         request: &CancelMarketOrderRequest,
     ) -> Result<CancelOrdersResponse> {
+        self.inner.throttle(EndpointGroup::Cancels).await?;
+
         let request = self
             .client()
             .request(
@@ -1611,7 +1867,62 @@ impl<K: Kind> Client<Authenticated<K>> {
             .build()?;
         let headers = self.create_headers(&request).await?;
 
-        crate::request(&self.inner.client, request, Some(headers)).await
+        let response: CancelOrdersResponse = self.send(request, Some(headers)).await?;
+        self.release_risk(&response);
+
+        Ok(response)
+    }
+
+    /// Replaces a resting order with a new one.
+    ///
+    /// The new order is signed before anything is sent, and the cancellation of `order_id` and
+    /// the new order are then sent concurrently, so that the new quote reaches the book with
+    /// a single round trip of latency. The cancellation request is started first.
+    ///
+    /// Because the two requests are independent, the new order is posted even when the old one
+    /// can no longer be cancelled, for example because it was filled in the meantime. Both
+    /// outcomes are reported in the returned [`ReplaceOrderResponse`].
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the new order cannot be signed, in which case nothing is sent.
+    pub async fn replace_order<S: Signer>(
+        &self,
+        signer: &S,
+        order_id: &str,
+        order: SignableOrder,
+    ) -> Result<ReplaceOrderResponse> {
+        let signed = self.sign(signer, order).await?;
+
+        let (cancel, post) = futures::join!(self.cancel_order(order_id), self.post_order(signed));
+
+        Ok(ReplaceOrderResponse {
+            old_order_id: order_id.to_owned(),
+            cancel: CancelOutcome::from_response(order_id, cancel),
+            post,
+        })
+    }
+
+    /// Engages the kill switch of the configured [`RiskGuard`], blocking new orders, and
+    /// cancels all open orders.
+    ///
+    /// Without a [`RiskGuard`] this only cancels all open orders.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if cancelling the orders fails. The kill switch stays engaged either way.
+    pub async fn kill_switch(&self) -> Result<CancelOrdersResponse> {
+        if let Some(guard) = &self.inner.config.risk_guard {
+            guard.halt();
+        }
+
+        self.cancel_all_orders().await
+    }
+
+    /// Returns the [`RiskGuard`] checking this client's orders, if one is configured.
+    #[must_use]
+    pub fn risk_guard(&self) -> Option<&RiskGuard> {
+        self.inner.config.risk_guard.as_ref()
     }
 
     /// Retrieves a paginated list of trades for the authenticated user.
@@ -1635,7 +1946,7 @@ impl<K: Kind> Client<Authenticated<K>> {
             .build()?;
         let headers = self.create_headers(&request).await?;
 
-        crate::request(&self.inner.client, request, Some(headers)).await
+        self.send(request, Some(headers)).await
     }
 
     /// Retrieves all notifications for the authenticated user.
@@ -1654,7 +1965,7 @@ impl<K: Kind> Client<Authenticated<K>> {
             .build()?;
         let headers = self.create_headers(&request).await?;
 
-        crate::request(&self.inner.client, request, Some(headers)).await
+        self.send(request, Some(headers)).await
     }
 
     /// Deletes notifications matching the specified IDs.
@@ -1712,7 +2023,113 @@ impl<K: Kind> Client<Authenticated<K>> {
             .build()?;
         let headers = self.create_headers(&request).await?;
 
-        crate::request(&self.inner.client, request, Some(headers)).await
+        self.send(request, Some(headers)).await
+    }
+
+    /// Checks that the balances and allowances reported by the CLOB cover `orders` on top of the
+    /// user's open orders, as described in the [`funds`](crate::clob::funds) module.
+    ///
+    /// With [`BalanceCheck::Refresh`], assets that fall short are refreshed with
+    /// [`Self::update_balance_allowance`] and checked once more.
+    ///
+    /// # Errors
+    ///
+    /// Returns an [`InsufficientFunds`] error listing every [`Shortfall`], or an error if a
+    /// request fails.
+    pub async fn check_funds(&self, orders: &[SignedOrder], check: BalanceCheck) -> Result<()> {
+        let mut requirements = requirements(orders);
+        if requirements.is_empty() {
+            return Ok(());
+        }
+
+        let mut reused = false;
+        for (asset, requirement) in &mut requirements {
+            requirement.committed = if let Some(token_id) = asset {
+                self.committed_tokens(*token_id).await?
+            } else {
+                let (collateral, cached) = self.committed_collateral(true).await?;
+                reused = cached;
+                collateral
+            };
+        }
+
+        let mut shortfalls = self.shortfalls(&requirements).await?;
+        if reused
+            && shortfalls
+                .iter()
+                .any(|shortfall| shortfall.token_id.is_none())
+        {
+            // The reused commitment may count orders since cancelled or filled
+            requirements[0].1.committed = self.committed_collateral(false).await?.0;
+            shortfalls = self.shortfalls(&requirements).await?;
+        }
+
+        if !shortfalls.is_empty() && matches!(check, BalanceCheck::Refresh) {
+            let mut refreshed = Vec::new();
+            for shortfall in &shortfalls {
+                if !refreshed.contains(&shortfall.token_id) {
+                    refreshed.push(shortfall.token_id);
+                    self.update_balance_allowance(balance_request(shortfall.token_id))
+                        .await?;
+                }
+            }
+
+            shortfalls = self.shortfalls(&requirements).await?;
+        }
+
+        if !shortfalls.is_empty() {
+            return Err(InsufficientFunds { shortfalls }.into());
+        }
+
+        if let Some((None, requirement)) = requirements.first() {
+            self.inner.committed_collateral.add(requirement.required);
+        }
+
+        Ok(())
+    }
+
+    /// Returns what the user's open sells commit of `token_id`.
+    async fn committed_tokens(&self, token_id: U256) -> Result<Decimal> {
+        let request = OrdersRequest::builder().asset_id(token_id).build();
+        let open_orders: Vec<_> = self
+            .stream_data(|client, cursor| client.orders(&request, cursor))
+            .try_collect()
+            .await?;
+
+        Ok(committed(Some(token_id), &open_orders))
+    }
+
+    /// Returns the collateral committed to the user's open buys across every market, reused
+    /// from a recent listing when `reuse` is set, and whether it was.
+    async fn committed_collateral(&self, reuse: bool) -> Result<(Decimal, bool)> {
+        if reuse && let Some(collateral) = self.inner.committed_collateral.get() {
+            return Ok((collateral, true));
+        }
+
+        let request = OrdersRequest::default();
+        let open_orders: Vec<_> = self
+            .stream_data(|client, cursor| client.orders(&request, cursor))
+            .try_collect()
+            .await?;
+
+        let collateral = committed(None, &open_orders);
+        self.inner.committed_collateral.set(collateral);
+        Ok((collateral, false))
+    }
+
+    /// Fetches the balance and allowances of each asset in `requirements` and returns the ones
+    /// that fall short.
+    async fn shortfalls(
+        &self,
+        requirements: &[(Option<U256>, Requirement)],
+    ) -> Result<Vec<Shortfall>> {
+        let mut found = Vec::new();
+        for (asset, requirement) in requirements {
+            let response = self.balance_allowance(balance_request(*asset)).await?;
+            found.extend(shortfalls(*asset, requirement, &response));
+        }
+
+        Ok(found)
     }
 
     /// Forces an update of the cached balance and allowance data.
@@ -1746,7 +2163,7 @@ impl<K: Kind> Client<Authenticated<K>> {
 
         // We have to send the request separately from `self.request` because this endpoint does
         // not return anything in the response body. Otherwise, we would get an EOF error from reqwest
-        self.client().execute(request).await?;
+        crate::retry::execute(self.client(), request, self.inner.config.retry_policy()).await?;
 
         Ok(())
     }
@@ -1768,7 +2185,7 @@ impl<K: Kind> Client<Authenticated<K>> {
             .build()?;
         let headers = self.create_headers(&request).await?;
 
-        crate::request(&self.inner.client, request, Some(headers)).await
+        self.send(request, Some(headers)).await
     }
 
     /// Checks if multiple orders are eligible for market maker rewards.
@@ -1787,7 +2204,7 @@ impl<K: Kind> Client<Authenticated<K>> {
             .build()?;
         let headers = self.create_headers(&request).await?;
 
-        crate::request(&self.inner.client, request, Some(headers)).await
+        self.send(request, Some(headers)).await
     }
 
     /// Retrieves detailed market maker earnings for a specific day.
@@ -1817,7 +2234,7 @@ impl<K: Kind> Client<Authenticated<K>> {
             .build()?;
         let headers = self.create_headers(&request).await?;
 
-        crate::request(&self.inner.client, request, Some(headers)).await
+        self.send(request, Some(headers)).await
     }
 
     /// Retrieves total market maker earnings summary for a specific day.
@@ -1845,7 +2262,7 @@ impl<K: Kind> Client<Authenticated<K>> {
             .build()?;
         let headers = self.create_headers(&request).await?;
 
-        crate::request(&self.inner.client, request, Some(headers)).await
+        self.send(request, Some(headers)).await
     }
 
     /// Retrieves user earnings along with market reward configurations.
@@ -1875,7 +2292,7 @@ impl<K: Kind> Client<Authenticated<K>> {
             .build()?;
         let headers = self.create_headers(&request).await?;
 
-        crate::request(&self.inner.client, request, Some(headers)).await
+        self.send(request, Some(headers)).await
     }
 
     /// Retrieves the user's current reward earning percentages.
@@ -1900,7 +2317,7 @@ impl<K: Kind> Client<Authenticated<K>> {
             .build()?;
         let headers = self.create_headers(&request).await?;
 
-        crate::request(&self.inner.client, request, Some(headers)).await
+        self.send(request, Some(headers)).await
     }
 
     /// Retrieves current active reward programs and their configurations.
@@ -1926,7 +2343,7 @@ impl<K: Kind> Client<Authenticated<K>> {
             .build()?;
         let headers = self.create_headers(&request).await?;
 
-        crate::request(&self.inner.client, request, Some(headers)).await
+        self.send(request, Some(headers)).await
     }
 
     /// Retrieves detailed reward data for a specific market.
@@ -1952,7 +2369,7 @@ impl<K: Kind> Client<Authenticated<K>> {
             .build()?;
         let headers = self.create_headers(&request).await?;
 
-        crate::request(&self.inner.client, request, Some(headers)).await
+        self.send(request, Some(headers)).await
     }
 
     /// Creates a new Builder API key for order attribution.
@@ -1965,13 +2382,15 @@ impl<K: Kind> Client<Authenticated<K>> {
     ///
     /// Returns an error if the request fails or the account is not eligible for builder keys.
     pub async fn create_builder_api_key(&self) -> Result<Credentials> {
+        self.inner.throttle(EndpointGroup::Auth).await?;
+
         let request = self
             .client()
             .request(Method::POST, format!("{}auth/builder-api-key", self.host()))
             .build()?;
         let headers = self.create_headers(&request).await?;
 
-        crate::request(&self.inner.client, request, Some(headers)).await
+        self.send(request, Some(headers)).await
     }
 
     /// Posts a heartbeat to maintain order liveness.
@@ -1991,7 +2410,7 @@ impl<K: Kind> Client<Authenticated<K>> {
             .build()?;
         let headers = self.create_headers(&request).await?;
 
-        crate::request(&self.inner.client, request, Some(headers)).await
+        self.send(request, Some(headers)).await
     }
 
     #[cfg(feature = "heartbeats")]
@@ -2086,6 +2505,48 @@ impl<K: Kind> Client<Authenticated<K>> {
         self.heartbeat_token.cancel_and_wait().await
     }
 
+    /// Checks `orders` against the configured [`RiskGuard`], fetching midpoints it does not know
+    /// yet when a price band is set.
+    async fn reserve_risk(&self, orders: &[SignedOrder]) -> Result<Option<Reservation>> {
+        let Some(guard) = &self.inner.config.risk_guard else {
+            return Ok(None);
+        };
+
+        let mut midpoints = HashMap::new();
+        if guard.limits().price_band.is_some() {
+            for token_id in orders.iter().map(|order| order.order.tokenId) {
+                if guard.midpoint(token_id).is_none() && !midpoints.contains_key(&token_id) {
+                    let request = MidpointRequest::builder().token_id(token_id).build();
+                    midpoints.insert(token_id, self.midpoint(&request).await?.mid);
+                }
+            }
+        }
+
+        guard.reserve(orders, &midpoints).map(Some)
+    }
+
+    /// Records the outcome of posting orders reserved by [`Self::reserve_risk`]. The exposure of
+    /// orders that were not posted is released when their reservation is dropped.
+    fn settle_risk(
+        &self,
+        reservation: Option<Reservation>,
+        responses: Option<&[PostOrderResponse]>,
+    ) {
+        if let (Some(guard), Some(reservation), Some(responses)) =
+            (&self.inner.config.risk_guard, reservation, responses)
+        {
+            guard.confirm(reservation, responses);
+        }
+    }
+
+    fn release_risk(&self, response: &CancelOrdersResponse) {
+        if let Some(guard) = &self.inner.config.risk_guard {
+            for order_id in &response.canceled {
+                _ = guard.release(order_id);
+            }
+        }
+    }
+
     async fn create_headers(&self, request: &Request) -> Result<HeaderMap> {
         let timestamp = if self.inner.config.use_server_time {
             self.server_time().await?
@@ -2101,7 +2562,7 @@ impl<K: Kind> Client<Authenticated<K>> {
             signer: self.address(),
             signature_type: self.inner.signature_type,
             funder: self.inner.funder,
-            salt_generator: self.inner.salt_generator,
+            salt_generator: Arc::clone(&self.inner.salt_generator),
             token_id: None,
             price: None,
             size: None,
@@ -2109,9 +2570,13 @@ impl<K: Kind> Client<Authenticated<K>> {
             side: None,
             nonce: None,
             expiration: None,
+            expires_in: None,
             taker: None,
             order_type: None,
             post_only: Some(false),
+            max_slippage_bps: None,
+            worst_price: None,
+            slippage_action: SlippageAction::default(),
             client: Client {
                 inner: Arc::clone(&self.inner),
                 #[cfg(feature = "heartbeats")]
@@ -2167,6 +2632,8 @@ impl Client<Authenticated<Normal>> {
             funder: inner.funder,
             signature_type: inner.signature_type,
             salt_generator: inner.salt_generator,
+            rate_limiter: inner.rate_limiter,
+            committed_collateral: inner.committed_collateral,
         };
 
         #[cfg_attr(
@@ -2191,16 +2658,20 @@ impl Client<Authenticated<Normal>> {
 
 impl Client<Authenticated<Builder>> {
     pub async fn builder_api_keys(&self) -> Result<Vec<BuilderApiKeyResponse>> {
+        self.inner.throttle(EndpointGroup::Auth).await?;
+
         let request = self
             .client()
             .request(Method::GET, format!("{}auth/builder-api-key", self.host()))
             .build()?;
         let headers = self.create_headers(&request).await?;
 
-        crate::request(&self.inner.client, request, Some(headers)).await
+        self.send(request, Some(headers)).await
     }
 
     pub async fn revoke_builder_api_key(&self) -> Result<()> {
+        self.inner.throttle(EndpointGroup::Auth).await?;
+
         let mut request = self
             .client()
             .request(
@@ -2235,7 +2706,7 @@ impl Client<Authenticated<Builder>> {
             .build()?;
         let headers = self.create_headers(&request).await?;
 
-        crate::request(&self.inner.client, request, Some(headers)).await
+        self.send(request, Some(headers)).await
     }
 }
 
@@ -2259,7 +2730,7 @@ impl<K: Kind> Client<Authenticated<K>> {
             .build()?;
         let headers = self.create_headers(&http_request).await?;
 
-        crate::request(&self.inner.client, http_request, Some(headers)).await
+        self.send(http_request, Some(headers)).await
     }
 
     /// Cancels an RFQ request.
@@ -2303,7 +2774,7 @@ impl<K: Kind> Client<Authenticated<K>> {
             .build()?;
         let headers = self.create_headers(&http_request).await?;
 
-        crate::request(&self.inner.client, http_request, Some(headers)).await
+        self.send(http_request, Some(headers)).await
     }
 
     /// Creates an RFQ Quote in response to a Request.
@@ -2322,7 +2793,7 @@ impl<K: Kind> Client<Authenticated<K>> {
             .build()?;
         let headers = self.create_headers(&http_request).await?;
 
-        crate::request(&self.inner.client, http_request, Some(headers)).await
+        self.send(http_request, Some(headers)).await
     }
 
     /// Cancels an RFQ quote.
@@ -2364,7 +2835,7 @@ impl<K: Kind> Client<Authenticated<K>> {
             .build()?;
         let headers = self.create_headers(&http_request).await?;
 
-        crate::request(&self.inner.client, http_request, Some(headers)).await
+        self.send(http_request, Some(headers)).await
     }
 
     /// Requester accepts an RFQ Quote.
@@ -2408,7 +2879,7 @@ impl<K: Kind> Client<Authenticated<K>> {
             .build()?;
         let headers = self.create_headers(&http_request).await?;
 
-        crate::request(&self.inner.client, http_request, Some(headers)).await
+        self.send(http_request, Some(headers)).await
     }
 
     /// Helper method for RFQ endpoints that return plain text instead of JSON.
@@ -2423,7 +2894,12 @@ impl<K: Kind> Client<Authenticated<K>> {
 
         *request.headers_mut() = headers;
 
-        let response = self.inner.client.execute(request).await?;
+        let response = crate::retry::execute(
+            &self.inner.client,
+            request,
+            self.inner.config.retry_policy(),
+        )
+        .await?;
         let status = response.status();
 
         if !status.is_success() {
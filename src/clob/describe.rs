@@ -0,0 +1,504 @@
+//! Human readable descriptions of orders.
+//!
+//! Orders carry their terms as raw integers: a `makerAmount` and a `takerAmount` in units of
+//! 10<sup>-6</sup>, a side and a signature type as `u8`s and an expiration in seconds.
+//! [`Order::describe`], [`SignableOrder::describe`] and [`SignedOrder::describe`] decode them
+//! into an [`OrderDescription`] with the price, size and notional of the order, and flag
+//! anything the CLOB would reject as an [`OrderIssue`]. The [`Display`](fmt::Display)
+//! implementations of these types print the same description.
+//!
+//! For a [`SignedOrder`], the description also names the [`Exchange`] the order was signed for,
+//! found by recovering the signature against every known exchange contract.
+//!
+//! Since the market's tick size is not part of the order, prices are only checked against the
+//! finest tick size, [`TickSize::TenThousandth`]. Use [`OrderDescription::is_on_tick`] to check
+//! against the market's tick size.
+//!
+//! The size of a market buy spending a USDC amount is derived from its price and truncated, so
+//! its amounts do not divide back to the price exactly. When the size of a buy has more decimal
+//! places than the lot size and matches such a truncation, the coarsest price it can have been
+//! derived from is reported instead, and the size is not flagged as [`OrderIssue::OffLot`].
+//!
+//! [`Order::describe`]: crate::clob::types::Order::describe
+//! [`SignableOrder::describe`]: crate::clob::types::SignableOrder::describe
+//! [`SignedOrder::describe`]: crate::clob::types::SignedOrder::describe
+
+use std::fmt;
+
+use alloy::primitives::{ChainId, U256};
+use chrono::{DateTime, Utc};
+
+use crate::clob::order_builder::{LOT_SIZE_SCALE, USDC_DECIMALS, order_domain};
+use crate::clob::types::{Order, Side, SignatureType, SignedOrder, TickSize};
+use crate::types::{Address, Decimal};
+use crate::{AMOY, POLYGON};
+
+/// The exchange contract an order was signed for.
+#[non_exhaustive]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Exchange {
+    /// Chain the exchange is deployed on.
+    pub chain_id: ChainId,
+    /// Whether this is the neg risk exchange.
+    pub neg_risk: bool,
+    /// Address of the exchange contract.
+    pub address: Address,
+}
+
+/// An inconsistency found while describing an order.
+#[non_exhaustive]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum OrderIssue {
+    /// The side is neither buy nor sell.
+    UnknownSide(u8),
+    /// The signature type is not one of the [`SignatureType`]s.
+    UnknownSignatureType(u8),
+    /// An amount is too large to be represented as a [`Decimal`].
+    AmountOutOfRange,
+    /// The maker or taker amount is zero.
+    ZeroAmount,
+    /// The price is not strictly between zero and one.
+    PriceOutOfRange(Decimal),
+    /// The price has more decimal places than the finest tick size.
+    OffTick(Decimal),
+    /// The size has more decimal places than the lot size.
+    OffLot(Decimal),
+    /// The expiration cannot be represented as a date.
+    InvalidExpiration(U256),
+    /// The signature does not recover to the signer on any known exchange.
+    UnknownExchange,
+}
+
+impl fmt::Display for OrderIssue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            OrderIssue::UnknownSide(side) => write!(f, "unknown side {side}"),
+            OrderIssue::UnknownSignatureType(signature_type) => {
+                write!(f, "unknown signature type {signature_type}")
+            }
+            OrderIssue::AmountOutOfRange => f.write_str("amount out of range"),
+            OrderIssue::ZeroAmount => f.write_str("zero amount"),
+            OrderIssue::PriceOutOfRange(price) => write!(f, "price {price} out of range"),
+            OrderIssue::OffTick(price) => write!(f, "price {price} off tick"),
+            OrderIssue::OffLot(size) => write!(f, "size {size} off lot"),
+            OrderIssue::InvalidExpiration(expiration) => {
+                write!(f, "invalid expiration {expiration}")
+            }
+            OrderIssue::UnknownExchange => {
+                f.write_str("signature does not match any known exchange")
+            }
+        }
+    }
+}
+
+/// The decoded terms of an order. See the [module](self) documentation.
+#[non_exhaustive]
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct OrderDescription {
+    pub token_id: U256,
+    /// Side of the order, [`Side::Unknown`] if it is neither buy nor sell.
+    pub side: Side,
+    /// Amount the maker gives up: USDC for buys, shares for sells.
+    pub maker_amount: Decimal,
+    /// Amount the maker receives: shares for buys, USDC for sells.
+    pub taker_amount: Decimal,
+    /// Price in USDC per share, or `None` if the side is unknown or an amount is zero.
+    pub price: Option<Decimal>,
+    /// Number of shares, or `None` if the side is unknown.
+    pub size: Option<Decimal>,
+    /// USDC exchanged, or `None` if the side is unknown.
+    pub notional: Option<Decimal>,
+    pub fee_rate_bps: U256,
+    /// When the order expires, or `None` if it does not.
+    pub expiration: Option<DateTime<Utc>>,
+    /// Signature type, or `None` if it is unknown or not part of the order.
+    pub signature_type: Option<SignatureType>,
+    pub maker: Address,
+    pub signer: Address,
+    pub taker: Address,
+    pub nonce: U256,
+    /// Exchange the order was signed for, only known for signed orders.
+    pub exchange: Option<Exchange>,
+    /// Inconsistencies found in the order. Empty if none were found.
+    pub issues: Vec<OrderIssue>,
+}
+
+impl OrderDescription {
+    /// Returns `true` if the price is a multiple of `tick_size` within the valid price range.
+    #[must_use]
+    pub fn is_on_tick(&self, tick_size: TickSize) -> bool {
+        let tick = tick_size.as_decimal();
+
+        self.price.is_some_and(|price| {
+            price.normalize().scale() <= tick.scale()
+                && price >= tick
+                && price <= Decimal::ONE - tick
+        })
+    }
+}
+
+impl fmt::Display for OrderDescription {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let or_unknown =
+            |value: Option<Decimal>| value.map_or_else(|| "?".to_owned(), |v| v.to_string());
+
+        write!(
+            f,
+            "{} {} shares of {} @ {} for {} USDC, fee {} bps",
+            self.side,
+            or_unknown(self.size),
+            self.token_id,
+            or_unknown(self.price),
+            or_unknown(self.notional),
+            self.fee_rate_bps,
+        )?;
+
+        match self.expiration {
+            Some(expiration) => write!(f, ", expires {expiration}")?,
+            None => f.write_str(", no expiration")?,
+        }
+
+        match self.signature_type {
+            Some(signature_type) => write!(f, ", {signature_type} maker {}", self.maker)?,
+            None => write!(f, ", maker {}", self.maker)?,
+        }
+        write!(f, " signer {}", self.signer)?;
+        if self.taker != Address::ZERO {
+            write!(f, " taker {}", self.taker)?;
+        }
+
+        if let Some(exchange) = self.exchange {
+            write!(
+                f,
+                ", exchange {} on chain {}",
+                exchange.address, exchange.chain_id
+            )?;
+            if exchange.neg_risk {
+                f.write_str(" (neg risk)")?;
+            }
+        }
+
+        for (i, issue) in self.issues.iter().enumerate() {
+            f.write_str(if i == 0 { " [" } else { ", " })?;
+            write!(f, "{issue}")?;
+        }
+        if !self.issues.is_empty() {
+            f.write_str("]")?;
+        }
+
+        Ok(())
+    }
+}
+
+/// The raw terms of an order, with amounts already scaled to whole units.
+pub(crate) struct OrderTerms {
+    pub(crate) token_id: U256,
+    pub(crate) side: u8,
+    pub(crate) maker_amount: Option<Decimal>,
+    pub(crate) taker_amount: Option<Decimal>,
+    pub(crate) fee_rate_bps: U256,
+    pub(crate) expiration: U256,
+    pub(crate) signature_type: Option<u8>,
+    pub(crate) maker: Address,
+    pub(crate) signer: Address,
+    pub(crate) taker: Address,
+    pub(crate) nonce: U256,
+}
+
+impl From<&Order> for OrderTerms {
+    fn from(order: &Order) -> Self {
+        let amount = |amount: U256| {
+            let amount = i128::try_from(amount).ok()?;
+            Decimal::try_from_i128_with_scale(amount, USDC_DECIMALS)
+                .ok()
+                .map(|amount| amount.normalize())
+        };
+
+        Self {
+            token_id: order.tokenId,
+            side: order.side,
+            maker_amount: amount(order.makerAmount),
+            taker_amount: amount(order.takerAmount),
+            fee_rate_bps: order.feeRateBps,
+            expiration: order.expiration,
+            signature_type: Some(order.signatureType),
+            maker: order.maker,
+            signer: order.signer,
+            taker: order.taker,
+            nonce: order.nonce,
+        }
+    }
+}
+
+/// Decodes `terms` into an [`OrderDescription`], flagging any inconsistencies.
+pub(crate) fn describe(terms: &OrderTerms) -> OrderDescription {
+    let mut issues = Vec::new();
+
+    let (maker_amount, taker_amount) = match (terms.maker_amount, terms.taker_amount) {
+        (Some(maker_amount), Some(taker_amount)) => (maker_amount, taker_amount),
+        (maker_amount, taker_amount) => {
+            issues.push(OrderIssue::AmountOutOfRange);
+            (
+                maker_amount.unwrap_or_default(),
+                taker_amount.unwrap_or_default(),
+            )
+        }
+    };
+    if maker_amount.is_zero() || taker_amount.is_zero() {
+        issues.push(OrderIssue::ZeroAmount);
+    }
+
+    let side = Side::try_from(terms.side).unwrap_or(Side::Unknown);
+    let (size, notional) = match side {
+        Side::Buy => (Some(taker_amount), Some(maker_amount)),
+        Side::Sell => (Some(maker_amount), Some(taker_amount)),
+        _ => {
+            issues.push(OrderIssue::UnknownSide(terms.side));
+            (None, None)
+        }
+    };
+
+    let market_buy_price = size
+        .zip(notional)
+        .filter(|_| side == Side::Buy)
+        .and_then(|(size, notional)| market_buy_price(size, notional));
+    let price = market_buy_price.or_else(|| {
+        size.zip(notional)
+            .filter(|(size, _)| !size.is_zero())
+            .map(|(size, notional)| (notional / size).normalize())
+    });
+    if let Some(price) = price {
+        if price <= Decimal::ZERO || price >= Decimal::ONE {
+            issues.push(OrderIssue::PriceOutOfRange(price));
+        } else if price.scale() > TickSize::TenThousandth.as_decimal().scale() {
+            issues.push(OrderIssue::OffTick(price));
+        }
+    }
+    if let Some(size) = size.map(|size| size.normalize())
+        && size.scale() > LOT_SIZE_SCALE
+        && market_buy_price.is_none()
+    {
+        issues.push(OrderIssue::OffLot(size));
+    }
+
+    let expiration = if terms.expiration.is_zero() {
+        None
+    } else {
+        let expiration = i64::try_from(terms.expiration)
+            .ok()
+            .and_then(|seconds| DateTime::<Utc>::from_timestamp(seconds, 0));
+        if expiration.is_none() {
+            issues.push(OrderIssue::InvalidExpiration(terms.expiration));
+        }
+        expiration
+    };
+
+    let signature_type = terms.signature_type.and_then(|signature_type| {
+        let known = [
+            SignatureType::Eoa,
+            SignatureType::Proxy,
+            SignatureType::GnosisSafe,
+        ]
+        .into_iter()
+        .find(|known| *known as u8 == signature_type);
+        if known.is_none() {
+            issues.push(OrderIssue::UnknownSignatureType(signature_type));
+        }
+        known
+    });
+
+    OrderDescription {
+        token_id: terms.token_id,
+        side,
+        maker_amount,
+        taker_amount,
+        price,
+        size,
+        notional,
+        fee_rate_bps: terms.fee_rate_bps,
+        expiration,
+        signature_type,
+        maker: terms.maker,
+        signer: terms.signer,
+        taker: terms.taker,
+        nonce: terms.nonce,
+        exchange: None,
+        issues,
+    }
+}
+
+/// Recovers the price of a market buy spending `notional` USDC, whose `size` in shares was
+/// derived from the price and truncated to the tick size and lot size, as
+/// [`OfflineOrderBuilder<Market>`](crate::clob::order_builder::OfflineOrderBuilder) does.
+///
+/// Returns the price on the coarsest tick size from which truncating `notional` divided by the
+/// price gives back exactly `size`, or `None` if there is none or the size has no more decimal
+/// places than the lot size.
+fn market_buy_price(size: Decimal, notional: Decimal) -> Option<Decimal> {
+    let size = size.normalize();
+    if size.scale() <= LOT_SIZE_SCALE || size.is_zero() {
+        return None;
+    }
+    let implied = notional.checked_div(size)?;
+
+    // Truncating the size can only raise the price implied by the amounts
+    (TickSize::Tenth.as_decimal().scale()..=TickSize::TenThousandth.as_decimal().scale())
+        .map(|scale| implied.trunc_with_scale(scale).normalize())
+        .find(|price| {
+            !price.is_zero()
+                && notional
+                    .checked_div(*price)
+                    .is_some_and(|derived| derived.trunc_with_scale(size.scale()) == size)
+        })
+}
+
+/// Finds the known exchange whose domain the signature of `order` recovers to its signer under.
+pub(crate) fn exchange(order: &SignedOrder) -> Option<Exchange> {
+    [POLYGON, AMOY]
+        .into_iter()
+        .flat_map(|chain_id| [(chain_id, false), (chain_id, true)])
+        .find_map(|(chain_id, neg_risk)| {
+            let address = order_domain(chain_id, neg_risk).ok()?.verifying_contract?;
+            let hash = order.order_hash(chain_id, neg_risk).ok()?;
+            let recovered = order.signature.recover_address_from_prehash(&hash).ok()?;
+
+            (recovered == order.order.signer).then_some(Exchange {
+                chain_id,
+                neg_risk,
+                address,
+            })
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use alloy::signers::local::PrivateKeySigner;
+
+    use super::*;
+    use crate::auth::ApiKey;
+    use crate::clob::order_builder::{MarketParameters, OfflineOrderBuilder, sign_order};
+    use crate::clob::types::{Amount, OrderType, SignableOrder};
+    use crate::types::dec;
+
+    fn limit_order(signer: Address) -> SignableOrder {
+        let market = MarketParameters::builder()
+            .tick_size(TickSize::Hundredth)
+            .build();
+
+        OfflineOrderBuilder::limit(signer, market)
+            .token_id(U256::from(7))
+            .side(Side::Buy)
+            .price(dec!(0.34))
+            .size(dec!(100))
+            .order_type(OrderType::GTD)
+            .expiration(DateTime::<Utc>::from_timestamp(1_800_000_000, 0).unwrap())
+            .build()
+            .unwrap()
+    }
+
+    #[test]
+    fn describe_should_recover_price_and_size() {
+        let order = limit_order(Address::repeat_byte(1));
+        let description = order.describe();
+
+        assert_eq!(description.side, Side::Buy);
+        assert_eq!(description.price, Some(dec!(0.34)));
+        assert_eq!(description.size, Some(dec!(100)));
+        assert_eq!(description.notional, Some(dec!(34)));
+        assert_eq!(
+            description.expiration,
+            DateTime::<Utc>::from_timestamp(1_800_000_000, 0)
+        );
+        assert_eq!(description.signature_type, Some(SignatureType::Eoa));
+        assert!(description.issues.is_empty());
+        assert!(description.is_on_tick(TickSize::Hundredth));
+        assert!(!description.is_on_tick(TickSize::Tenth));
+        assert!(
+            order
+                .to_string()
+                .starts_with("GTD BUY 100 shares of 7 @ 0.34 for 34 USDC, fee 0 bps, expires 2027")
+        );
+    }
+
+    #[test]
+    fn describe_should_flag_inconsistencies() {
+        let mut order = limit_order(Address::ZERO).order;
+        order.makerAmount = U256::from(34_567_800);
+        order.takerAmount = U256::from(100_001_000);
+        order.signatureType = 9;
+
+        let issues = order.describe().issues;
+        assert!(matches!(issues[0], OrderIssue::OffTick(_)));
+        assert_eq!(issues[1], OrderIssue::OffLot(dec!(100.001)));
+        assert_eq!(issues[2], OrderIssue::UnknownSignatureType(9));
+
+        order.side = 5;
+        let description = order.describe();
+        assert_eq!(description.side, Side::Unknown);
+        assert_eq!(description.price, None);
+        assert!(description.issues.contains(&OrderIssue::UnknownSide(5)));
+        assert!(
+            order
+                .to_string()
+                .ends_with("[unknown side 5, unknown signature type 9]")
+        );
+    }
+
+    #[test]
+    fn describe_should_accept_market_buy_of_usdc_amount() {
+        let market = MarketParameters::builder()
+            .tick_size(TickSize::Hundredth)
+            .build();
+        let order = OfflineOrderBuilder::market(Address::ZERO, market)
+            .token_id(U256::from(7))
+            .side(Side::Buy)
+            .price(dec!(0.34))
+            .amount(Amount::usdc(dec!(100)).unwrap())
+            .build()
+            .unwrap();
+
+        let description = order.describe();
+        assert_eq!(description.size, Some(dec!(294.1176)));
+        assert_eq!(description.notional, Some(dec!(100)));
+        assert_eq!(description.price, Some(dec!(0.34)));
+        assert!(description.issues.is_empty(), "{:?}", description.issues);
+        assert!(description.is_on_tick(TickSize::Hundredth));
+
+        for (tick_size, price, usdc) in [
+            (TickSize::TenThousandth, dec!(0.0123), dec!(7.5)),
+            (TickSize::Thousandth, dec!(0.999), dec!(1)),
+            (TickSize::Tenth, dec!(0.3), dec!(0.01)),
+        ] {
+            let market = MarketParameters::builder().tick_size(tick_size).build();
+            let description = OfflineOrderBuilder::market(Address::ZERO, market)
+                .token_id(U256::from(7))
+                .side(Side::Buy)
+                .price(price)
+                .amount(Amount::usdc(usdc).unwrap())
+                .build()
+                .unwrap()
+                .describe();
+
+            assert_eq!(description.price, Some(price), "{description}");
+            assert!(description.issues.is_empty(), "{description}");
+        }
+    }
+
+    #[tokio::test]
+    async fn describe_should_find_exchange_of_signed_order() {
+        let signer = PrivateKeySigner::random();
+        let order = limit_order(signer.address());
+
+        let mut signed = sign_order(&signer, order, AMOY, true, ApiKey::nil())
+            .await
+            .unwrap();
+        let exchange = signed.describe().exchange.unwrap();
+        assert_eq!(exchange.chain_id, AMOY);
+        assert!(exchange.neg_risk);
+
+        signed.order.nonce = U256::from(1);
+        let description = signed.describe();
+        assert_eq!(description.exchange, None);
+        assert_eq!(description.issues, vec![OrderIssue::UnknownExchange]);
+    }
+}
@@ -0,0 +1,343 @@
+//! Balance and allowance checks for orders before they are posted.
+//!
+//! [`Client::check_funds`](crate::clob::Client::check_funds) compares what a set of signed orders
+//! would lock up against the balances and allowances the CLOB reports through
+//! [`Client::balance_allowance`](crate::clob::Client::balance_allowance), after subtracting what
+//! is already committed to the user's open orders:
+//!
+//! - Buys lock up their maker amount of collateral, and open buys commit the unmatched part of
+//!   their size at their price.
+//! - Sells lock up their maker amount of the conditional token they sell, and open sells commit
+//!   the unmatched part of their size.
+//!
+//! Open sells are listed for the tokens being sold only. Open buys commit collateral across
+//! every market, so they are listed in full, and what they commit is then reused for a few
+//! seconds, with the buys checked since counted in. An asset that falls short against a reused
+//! commitment is checked again against a fresh listing before failing, so orders cancelled or
+//! filled in the meantime never cause a false shortfall. Buys posted by another client within
+//! those seconds are only counted once listed again.
+//!
+//! Allowances are checked against the exchange each order was signed for, found by recovering
+//! its signature, see [`SignedOrder::describe`].
+//!
+//! Every asset that falls short is reported at once in an [`InsufficientFunds`] error of kind
+//! [`Kind::Validation`]. Setting a [`BalanceCheck`] on the client's
+//! [`Config`](crate::clob::Config) runs the check before every
+//! [`Client::post_order`](crate::clob::Client::post_order) and
+//! [`Client::post_orders`](crate::clob::Client::post_orders).
+
+#![expect(
+    clippy::module_name_repetitions,
+    reason = "`InsufficientFunds` reads better at the call site than a bare `Insufficient`"
+)]
+
+use std::collections::HashMap;
+use std::error::Error as StdError;
+use std::fmt;
+use std::sync::{Mutex, MutexGuard, PoisonError};
+use std::time::{Duration, Instant};
+
+use crate::clob::order_builder::USDC_DECIMALS;
+use crate::clob::types::request::BalanceAllowanceRequest;
+use crate::clob::types::response::{BalanceAllowanceResponse, OpenOrderResponse};
+use crate::clob::types::{AssetType, Side, SignedOrder};
+use crate::error::{Error, Kind};
+use crate::types::{Address, Decimal, U256};
+
+/// How long the collateral committed to open buys is reused before listing them again.
+pub(crate) const COMMITMENT_TTL: Duration = Duration::from_secs(5);
+
+/// How orders are checked against balances and allowances before they are posted.
+#[non_exhaustive]
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum BalanceCheck {
+    /// Fail with [`InsufficientFunds`] as soon as an asset falls short.
+    #[default]
+    Strict,
+    /// When an asset falls short, ask the CLOB to refresh its view of that asset with
+    /// [`Client::update_balance_allowance`](crate::clob::Client::update_balance_allowance) and
+    /// check once more before failing.
+    Refresh,
+}
+
+/// Whether a [`Shortfall`] is in the balance or the allowance of an asset.
+#[non_exhaustive]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, strum_macros::Display)]
+#[strum(serialize_all = "lowercase")]
+pub enum Funds {
+    Balance,
+    Allowance,
+}
+
+/// An asset whose balance or allowance cannot cover the orders being checked.
+#[non_exhaustive]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Shortfall {
+    /// The conditional token that falls short, or `None` for collateral.
+    pub token_id: Option<U256>,
+    pub funds: Funds,
+    /// Amount the orders being checked would lock up.
+    pub required: Decimal,
+    /// Amount already committed to open orders.
+    pub committed: Decimal,
+    /// The balance or allowance reported by the CLOB.
+    pub available: Decimal,
+}
+
+impl Shortfall {
+    /// Amount missing to cover both the open orders and the orders being checked.
+    #[must_use]
+    pub fn missing(&self) -> Decimal {
+        self.required + self.committed - self.available
+    }
+}
+
+impl fmt::Display for Shortfall {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.token_id {
+            Some(token_id) => write!(f, "token {token_id}")?,
+            None => f.write_str("collateral")?,
+        }
+
+        write!(
+            f,
+            " {} {} is {} short: {} required, {} committed to open orders",
+            self.funds,
+            self.available,
+            self.missing(),
+            self.required,
+            self.committed
+        )
+    }
+}
+
+/// Error returned when the orders being checked cannot be covered, listing every [`Shortfall`].
+#[non_exhaustive]
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct InsufficientFunds {
+    pub shortfalls: Vec<Shortfall>,
+}
+
+impl fmt::Display for InsufficientFunds {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("insufficient funds: ")?;
+        for (i, shortfall) in self.shortfalls.iter().enumerate() {
+            if i > 0 {
+                f.write_str("; ")?;
+            }
+            write!(f, "{shortfall}")?;
+        }
+
+        Ok(())
+    }
+}
+
+impl StdError for InsufficientFunds {}
+
+impl From<InsufficientFunds> for Error {
+    fn from(error: InsufficientFunds) -> Self {
+        Error::with_source(Kind::Validation, error)
+    }
+}
+
+/// What the orders being checked need from one asset.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub(crate) struct Requirement {
+    /// Amount the orders would lock up.
+    pub(crate) required: Decimal,
+    /// Amount committed to open orders.
+    pub(crate) committed: Decimal,
+    /// Exchanges whose allowance must cover the orders.
+    pub(crate) exchanges: Vec<Address>,
+}
+
+/// Sums up what `orders` need from each asset, keyed by token ID or `None` for collateral,
+/// collateral first.
+pub(crate) fn requirements(orders: &[SignedOrder]) -> Vec<(Option<U256>, Requirement)> {
+    let mut requirements: HashMap<Option<U256>, Requirement> = HashMap::new();
+
+    for order in orders {
+        let description = order.describe();
+        let asset = match description.side {
+            Side::Buy => None,
+            Side::Sell => Some(description.token_id),
+            _ => continue,
+        };
+
+        let requirement = requirements.entry(asset).or_default();
+        requirement.required += description.maker_amount;
+        if let Some(exchange) = description.exchange
+            && !requirement.exchanges.contains(&exchange.address)
+        {
+            requirement.exchanges.push(exchange.address);
+        }
+    }
+
+    let mut requirements: Vec<_> = requirements.into_iter().collect();
+    requirements.sort_by_key(|(asset, _)| *asset);
+    requirements
+}
+
+/// Returns what `open_orders` commit of `asset`, a conditional token or `None` for collateral.
+pub(crate) fn committed(asset: Option<U256>, open_orders: &[OpenOrderResponse]) -> Decimal {
+    open_orders
+        .iter()
+        .filter_map(|order| {
+            let remaining = (order.original_size - order.size_matched).max(Decimal::ZERO);
+            match (order.side, asset) {
+                (Side::Buy, None) => Some(remaining * order.price),
+                (Side::Sell, Some(token_id)) if order.asset_id == token_id => Some(remaining),
+                _ => None,
+            }
+        })
+        .sum()
+}
+
+/// Collateral committed to the user's open buys, as last listed by
+/// [`Client::check_funds`](crate::clob::Client::check_funds).
+///
+/// Buys commit collateral across every market, so finding what they commit takes listing all
+/// the user's open orders. The last listing is reused for [`COMMITMENT_TTL`], with the buys
+/// checked since counted in.
+#[derive(Debug, Default)]
+pub(crate) struct CommittedCollateral(Mutex<Option<(Instant, Decimal)>>);
+
+impl CommittedCollateral {
+    /// Returns the committed collateral if it was listed less than [`COMMITMENT_TTL`] ago.
+    pub(crate) fn get(&self) -> Option<Decimal> {
+        let cached = *self.lock();
+        cached
+            .filter(|(listed_at, _)| listed_at.elapsed() < COMMITMENT_TTL)
+            .map(|(_, committed)| committed)
+    }
+
+    /// Stores the collateral committed as just listed.
+    pub(crate) fn set(&self, committed: Decimal) {
+        *self.lock() = Some((Instant::now(), committed));
+    }
+
+    /// Counts in collateral committed by orders checked since the last listing.
+    pub(crate) fn add(&self, committed: Decimal) {
+        if let Some((_, total)) = self.lock().as_mut() {
+            *total += committed;
+        }
+    }
+
+    fn lock(&self) -> MutexGuard<'_, Option<(Instant, Decimal)>> {
+        // The cached value is replaced whole, so a poisoned lock still holds a valid one
+        self.0.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+}
+
+/// Returns the request for the balance and allowances of `asset`, a conditional token or `None`
+/// for collateral.
+pub(crate) fn balance_request(asset: Option<U256>) -> BalanceAllowanceRequest {
+    match asset {
+        Some(token_id) => BalanceAllowanceRequest::builder()
+            .asset_type(AssetType::Conditional)
+            .token_id(token_id)
+            .build(),
+        None => BalanceAllowanceRequest::builder()
+            .asset_type(AssetType::Collateral)
+            .build(),
+    }
+}
+
+/// Returns the shortfalls of `asset` given its `requirement` and the `response` of
+/// [`Client::balance_allowance`](crate::clob::Client::balance_allowance).
+///
+/// Balances and allowances are reported in base units of 10<sup>-6</sup>. An allowance is only
+/// checked when the CLOB reports allowances at all, and one that does not fit in a [`Decimal`]
+/// is treated as unlimited.
+pub(crate) fn shortfalls(
+    asset: Option<U256>,
+    requirement: &Requirement,
+    response: &BalanceAllowanceResponse,
+) -> Vec<Shortfall> {
+    let scale = |value: Decimal| (value * Decimal::new(1, USDC_DECIMALS)).normalize();
+    let shortfall = |funds, available| Shortfall {
+        token_id: asset,
+        funds,
+        required: requirement.required,
+        committed: requirement.committed,
+        available,
+    };
+
+    let mut shortfalls = Vec::new();
+
+    let balance = scale(response.balance);
+    if requirement.required + requirement.committed > balance {
+        shortfalls.push(shortfall(Funds::Balance, balance));
+    }
+
+    if !response.allowances.is_empty() {
+        for exchange in &requirement.exchanges {
+            let allowance = response
+                .allowances
+                .get(exchange)
+                .map_or(Some(Decimal::ZERO), |allowance| parse_allowance(allowance));
+
+            if let Some(allowance) = allowance
+                && requirement.required + requirement.committed > allowance
+            {
+                shortfalls.push(shortfall(Funds::Allowance, allowance));
+            }
+        }
+    }
+
+    shortfalls
+}
+
+/// Parses an allowance in base units, returning `None` when it does not fit in a [`Decimal`].
+fn parse_allowance(allowance: &str) -> Option<Decimal> {
+    let allowance = i128::try_from(allowance.parse::<U256>().ok()?).ok()?;
+    let allowance = Decimal::try_from_i128_with_scale(allowance, USDC_DECIMALS).ok()?;
+
+    Some(allowance.normalize())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::dec;
+
+    #[test]
+    fn shortfalls_should_account_for_commitments_and_allowances() {
+        let exchange = Address::repeat_byte(1);
+        let requirement = Requirement {
+            required: dec!(40),
+            committed: dec!(70),
+            exchanges: vec![exchange],
+        };
+
+        let response = BalanceAllowanceResponse::builder()
+            .balance(dec!(100_000_000))
+            .allowances(HashMap::from([(exchange, "500000000".to_owned())]))
+            .build();
+        let found = shortfalls(None, &requirement, &response);
+
+        assert_eq!(found.len(), 1);
+        assert_eq!(found[0].funds, Funds::Balance);
+        assert_eq!(found[0].available, dec!(100));
+        assert_eq!(found[0].missing(), dec!(10));
+        assert_eq!(
+            found[0].to_string(),
+            "collateral balance 100 is 10 short: 40 required, 70 committed to open orders"
+        );
+
+        // An unlimited allowance is never short, a missing one always is
+        let response = BalanceAllowanceResponse::builder()
+            .balance(dec!(500_000_000))
+            .allowances(HashMap::from([(exchange, U256::MAX.to_string())]))
+            .build();
+        assert!(shortfalls(None, &requirement, &response).is_empty());
+
+        let response = BalanceAllowanceResponse::builder()
+            .balance(dec!(500_000_000))
+            .allowances(HashMap::from([(Address::ZERO, U256::MAX.to_string())]))
+            .build();
+        let found = shortfalls(Some(U256::from(1)), &requirement, &response);
+        assert_eq!(found[0].funds, Funds::Allowance);
+        assert_eq!(found[0].available, Decimal::ZERO);
+    }
+}
@@ -0,0 +1,370 @@
+//! Ladders of limit orders around a centre price, for market making.
+//!
+//! A [`LadderBuilder`], created with [`Client::ladder`], lays out `levels` limit orders on one or
+//! both sides of a centre price. Level `i`, counting from zero nearest the centre, sits
+//! `(i + 1) * spacing` ticks away from the centre and is rounded away from it to the token's tick
+//! size, so that bids and asks never cross the centre. Its size comes from a [`SizeCurve`],
+//! truncated to the lot size.
+//!
+//! Levels that fall outside the valid price range, or whose size is below the market's minimum
+//! order size, are left out of the ladder. The remaining orders can be signed and posted with
+//! [`LadderBuilder::post`], which splits them into batches of at most [`MAX_BATCH_SIZE`] orders.
+
+#![expect(
+    clippy::module_name_repetitions,
+    reason = "`LadderBuilder` mirrors the naming of `OrderBuilder`"
+)]
+
+use alloy::primitives::U256;
+use alloy::signers::Signer;
+use chrono::{DateTime, Utc};
+use rust_decimal::RoundingStrategy;
+
+use crate::Result;
+use crate::auth::Kind as AuthKind;
+use crate::auth::state::Authenticated;
+use crate::clob::Client;
+use crate::clob::client::MAX_BATCH_SIZE;
+use crate::clob::order_builder::{LOT_SIZE_SCALE, MarketParameters};
+use crate::clob::types::request::OrderBookSummaryRequest;
+use crate::clob::types::response::PostOrderResponse;
+use crate::clob::types::{OrderType, Side, SignableOrder};
+use crate::error::Error;
+use crate::types::Decimal;
+
+/// How the size of each ladder level is derived from its distance to the centre.
+///
+/// Levels are numbered from zero, nearest the centre.
+#[non_exhaustive]
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum SizeCurve {
+    /// The same size at every level.
+    Flat(Decimal),
+    /// `base + step * level`. A negative `step` shrinks the ladder away from the centre.
+    Linear { base: Decimal, step: Decimal },
+    /// `base * ratio^level`.
+    Geometric { base: Decimal, ratio: Decimal },
+    /// An explicit size for each level. Levels beyond the end of the list are left out.
+    Custom(Vec<Decimal>),
+}
+
+impl SizeCurve {
+    /// Returns the size of `level`, or `None` if the curve does not cover it or the size does
+    /// not fit in a [`Decimal`].
+    #[must_use]
+    pub fn size(&self, level: u32) -> Option<Decimal> {
+        match self {
+            SizeCurve::Flat(size) => Some(*size),
+            SizeCurve::Linear { base, step } => {
+                base.checked_add(step.checked_mul(Decimal::from(level))?)
+            }
+            SizeCurve::Geometric { base, ratio } => {
+                (0..level).try_fold(*base, |size, _| size.checked_mul(*ratio))
+            }
+            SizeCurve::Custom(sizes) => sizes.get(usize::try_from(level).ok()?).copied(),
+        }
+    }
+}
+
+/// Builds a ladder of limit orders around a centre price. See the [module](self) documentation
+/// for how levels are laid out.
+#[derive(Debug)]
+pub struct LadderBuilder<K: AuthKind> {
+    client: Client<Authenticated<K>>,
+    token_id: Option<U256>,
+    side: Option<Side>,
+    centre: Option<Decimal>,
+    spacing: u32,
+    levels: Option<u32>,
+    sizes: Option<SizeCurve>,
+    order_type: Option<OrderType>,
+    expiration: Option<DateTime<Utc>>,
+    post_only: Option<bool>,
+}
+
+impl<K: AuthKind> LadderBuilder<K> {
+    pub(crate) fn new(client: Client<Authenticated<K>>) -> Self {
+        Self {
+            client,
+            token_id: None,
+            side: None,
+            centre: None,
+            spacing: 1,
+            levels: None,
+            sizes: None,
+            order_type: None,
+            expiration: None,
+            post_only: None,
+        }
+    }
+
+    /// Sets the `token_id` for this ladder. This is a required field.
+    #[must_use]
+    pub fn token_id(mut self, token_id: U256) -> Self {
+        self.token_id = Some(token_id);
+        self
+    }
+
+    /// Restricts the ladder to bids ([`Side::Buy`]) or asks ([`Side::Sell`]). Without a side,
+    /// the ladder quotes both.
+    #[must_use]
+    pub fn side(mut self, side: Side) -> Self {
+        self.side = Some(side);
+        self
+    }
+
+    /// Sets the fair value the ladder is centred on. This is a required field.
+    #[must_use]
+    pub fn centre(mut self, centre: Decimal) -> Self {
+        self.centre = Some(centre);
+        self
+    }
+
+    /// Sets the distance between consecutive levels, in ticks. Defaults to one tick.
+    #[must_use]
+    pub fn spacing(mut self, ticks: u32) -> Self {
+        self.spacing = ticks;
+        self
+    }
+
+    /// Sets the number of levels on each side. This is a required field.
+    #[must_use]
+    pub fn levels(mut self, levels: u32) -> Self {
+        self.levels = Some(levels);
+        self
+    }
+
+    /// Sets the [`SizeCurve`] giving the size of each level. This is a required field.
+    #[must_use]
+    pub fn sizes(mut self, sizes: SizeCurve) -> Self {
+        self.sizes = Some(sizes);
+        self
+    }
+
+    /// Sets the order type of every level. Defaults to [`OrderType::GTC`].
+    #[must_use]
+    pub fn order_type(mut self, order_type: OrderType) -> Self {
+        self.order_type = Some(order_type);
+        self
+    }
+
+    /// Sets the expiration of every level, for [`OrderType::GTD`] ladders.
+    #[must_use]
+    pub fn expiration(mut self, expiration: DateTime<Utc>) -> Self {
+        self.expiration = Some(expiration);
+        self
+    }
+
+    /// Sets the `postOnly` flag of every level.
+    #[must_use]
+    pub fn post_only(mut self, post_only: bool) -> Self {
+        self.post_only = Some(post_only);
+        self
+    }
+
+    /// Validates the ladder and builds a [`SignableOrder`] for each of its levels, nearest the
+    /// centre first and alternating between bids and asks.
+    ///
+    /// The token's tick size, fee rate and minimum order size are looked up from the CLOB.
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(skip(self), err(level = "warn"))
+    )]
+    pub async fn build(self) -> Result<Vec<SignableOrder>> {
+        let Some(token_id) = self.token_id else {
+            return Err(Error::validation(
+                "Unable to build ladder due to missing token ID",
+            ));
+        };
+
+        let Some(centre) = self.centre else {
+            return Err(Error::validation(
+                "Unable to build ladder due to missing centre price",
+            ));
+        };
+
+        if centre <= Decimal::ZERO || centre >= Decimal::ONE {
+            return Err(Error::validation(format!(
+                "Unable to build ladder: centre price {centre} must be between 0 and 1"
+            )));
+        }
+
+        let Some(levels) = self.levels else {
+            return Err(Error::validation(
+                "Unable to build ladder due to missing number of levels",
+            ));
+        };
+
+        let Some(sizes) = &self.sizes else {
+            return Err(Error::validation(
+                "Unable to build ladder due to missing size curve",
+            ));
+        };
+
+        if self.spacing == 0 {
+            return Err(Error::validation(
+                "Unable to build ladder: level spacing must be at least one tick",
+            ));
+        }
+
+        let sides: &[Side] = match self.side {
+            None => &[Side::Buy, Side::Sell],
+            Some(Side::Buy) => &[Side::Buy],
+            Some(Side::Sell) => &[Side::Sell],
+            Some(side) => return Err(Error::validation(format!("Invalid side: {side}"))),
+        };
+
+        let request = OrderBookSummaryRequest {
+            token_id,
+            side: None,
+        };
+        let (tick_size, fee_rate, book) = futures::try_join!(
+            self.client.tick_size(token_id),
+            self.client.fee_rate_bps(token_id),
+            self.client.order_book(&request),
+        )?;
+        let market = MarketParameters {
+            tick_size: tick_size.minimum_tick_size,
+            fee_rate_bps: fee_rate.base_fee,
+        };
+        let tick = market.tick_size.as_decimal();
+
+        let mut orders = Vec::new();
+        for level in 0..levels {
+            let distance = tick * Decimal::from(self.spacing) * Decimal::from(level + 1);
+            let prices: Vec<(Side, Decimal)> = sides
+                .iter()
+                .map(|&side| {
+                    let price = match side {
+                        Side::Buy => (centre - distance).round_dp_with_strategy(
+                            tick.scale(),
+                            RoundingStrategy::ToNegativeInfinity,
+                        ),
+                        _ => (centre + distance).round_dp_with_strategy(
+                            tick.scale(),
+                            RoundingStrategy::ToPositiveInfinity,
+                        ),
+                    };
+                    (side, price)
+                })
+                .filter(|(_, price)| *price >= tick && *price <= Decimal::ONE - tick)
+                .collect();
+            // Levels only move away from the centre, so no further level has a valid price either
+            if prices.is_empty() {
+                break;
+            }
+
+            let Some(size) = sizes.size(level) else {
+                break;
+            };
+            let size = size.trunc_with_scale(LOT_SIZE_SCALE);
+            if size <= Decimal::ZERO || size < book.min_order_size {
+                continue;
+            }
+
+            for (side, price) in prices {
+                let mut builder = self
+                    .client
+                    .limit_order()
+                    .token_id(token_id)
+                    .side(side)
+                    .price(price.normalize())
+                    .size(size.normalize());
+                if let Some(order_type) = &self.order_type {
+                    builder = builder.order_type(order_type.clone());
+                }
+                if let Some(expiration) = self.expiration {
+                    builder = builder.expiration(expiration);
+                }
+                if let Some(post_only) = self.post_only {
+                    builder = builder.post_only(post_only);
+                }
+
+                orders.push(builder.offline(market).build()?);
+            }
+        }
+
+        if orders.is_empty() {
+            return Err(Error::validation(format!(
+                "Unable to build ladder: no level of {token_id} has a valid price and a size of \
+                at least {}",
+                book.min_order_size
+            )));
+        }
+
+        #[cfg(feature = "tracing")]
+        tracing::debug!(token_id = %token_id, orders = orders.len(), "ladder built");
+
+        Ok(orders)
+    }
+
+    /// Builds the ladder, signs every level with `signer` and posts them with
+    /// [`Client::post_orders`] in batches of at most [`MAX_BATCH_SIZE`] orders.
+    ///
+    /// Batches are posted in order, nearest the centre first. If a batch fails, the error is
+    /// returned and the remaining batches are not posted, while earlier batches stay on the book.
+    pub async fn post<S: Signer>(self, signer: &S) -> Result<Vec<PostOrderResponse>> {
+        let client = self.client.clone();
+        let orders = self.build().await?;
+
+        let mut signed = Vec::with_capacity(orders.len());
+        for order in orders {
+            signed.push(client.sign(signer, order).await?);
+        }
+
+        let mut responses = Vec::with_capacity(signed.len());
+        while !signed.is_empty() {
+            let rest = signed.split_off(signed.len().min(MAX_BATCH_SIZE));
+            responses.extend(client.post_orders(signed).await?);
+            signed = rest;
+        }
+
+        Ok(responses)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::dec;
+
+    #[test]
+    fn size_curves_should_follow_level() {
+        assert_eq!(SizeCurve::Flat(dec!(10)).size(3), Some(dec!(10)));
+        assert_eq!(
+            SizeCurve::Linear {
+                base: dec!(10),
+                step: dec!(-2),
+            }
+            .size(2),
+            Some(dec!(6))
+        );
+        assert_eq!(
+            SizeCurve::Geometric {
+                base: dec!(10),
+                ratio: dec!(1.5),
+            }
+            .size(2),
+            Some(dec!(22.5))
+        );
+
+        // Sizes that overflow are not covered rather than panicking
+        let doubling = SizeCurve::Geometric {
+            base: dec!(10),
+            ratio: dec!(2),
+        };
+        assert_eq!(doubling.size(200), None);
+        assert_eq!(
+            SizeCurve::Linear {
+                base: Decimal::MAX,
+                step: dec!(1),
+            }
+            .size(1),
+            None
+        );
+
+        let custom = SizeCurve::Custom(vec![dec!(5), dec!(7)]);
+        assert_eq!(custom.size(1), Some(dec!(7)));
+        assert_eq!(custom.size(2), None);
+    }
+}
@@ -0,0 +1,711 @@
+//! Local position and `PnL` accounting computed from trade fills.
+//!
+//! The data API reports positions with server-computed profit and loss, but those figures lag
+//! behind the trades that produce them. A [`Ledger`] instead folds fills into per-token
+//! positions as they arrive, either from user channel [`TradeMessage`]s or from
+//! [`TradeResponse`] pages returned by [`Client::trades`](crate::clob::Client::trades), and marks
+//! open positions to the latest midpoint.
+//!
+//! Positions use average cost accounting: buys into a long position (or sells into a short one)
+//! move the average price, while fills that reduce a position realize `PnL` against it. Each trade
+//! is applied once, however many status updates it goes through, and a trade that later fails
+//! on chain is reverted.
+//!
+//! Fees are estimated from each fill's fee rate as `fee_rate_bps / 10_000 * min(price, 1 -
+//! price) * size`, in USDC, and are reported separately from realized `PnL`.
+//!
+//! [`TradeMessage`]: crate::clob::ws::types::response::TradeMessage
+
+use std::collections::{HashMap, HashSet};
+
+use crate::clob::types::response::TradeResponse;
+use crate::clob::types::{Side, TradeStatusType, TraderSide};
+#[cfg(feature = "ws")]
+use crate::clob::ws::types::response::{MidpointUpdate, TradeMessage, TradeMessageStatus};
+#[cfg(feature = "data")]
+use crate::data::types::response::Position;
+use crate::types::{B256, Decimal, U256, dec};
+
+const BPS: Decimal = dec!(10_000);
+
+/// A single fill attributed to the ledger's owner.
+#[non_exhaustive]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Fill {
+    /// The trade this fill is part of.
+    pub trade_id: String,
+    /// The market condition ID.
+    pub market: B256,
+    /// The token that was bought or sold.
+    pub asset_id: U256,
+    /// The owner's side of the fill, which differs from the trade's side for maker fills on the
+    /// same token.
+    pub side: Side,
+    /// Number of tokens filled.
+    pub size: Decimal,
+    /// Execution price of the fill.
+    pub price: Decimal,
+    /// Estimated fee paid for the fill, in USDC.
+    pub fee: Decimal,
+}
+
+/// Locally computed position in a single token.
+#[non_exhaustive]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TokenPosition {
+    /// The token held.
+    pub asset_id: U256,
+    /// The market condition ID of the token.
+    pub market: B256,
+    /// Number of tokens held. Negative if more tokens were sold than bought since the ledger
+    /// was created.
+    pub size: Decimal,
+    /// Average price paid for the tokens currently held, or zero when flat.
+    pub average_price: Decimal,
+    /// Profit and loss realized by reducing the position, excluding fees.
+    pub realized_pnl: Decimal,
+    /// Estimated fees paid on all fills in this token.
+    pub fees_paid: Decimal,
+    /// The latest price the position was marked to, if any.
+    pub mark_price: Option<Decimal>,
+}
+
+impl TokenPosition {
+    const fn new(asset_id: U256, market: B256) -> Self {
+        Self {
+            asset_id,
+            market,
+            size: Decimal::ZERO,
+            average_price: Decimal::ZERO,
+            realized_pnl: Decimal::ZERO,
+            fees_paid: Decimal::ZERO,
+            mark_price: None,
+        }
+    }
+
+    /// Profit and loss of the open position at the mark price, or `None` if the position has
+    /// not been marked yet.
+    #[must_use]
+    pub fn unrealized_pnl(&self) -> Option<Decimal> {
+        self.mark_price
+            .map(|mark| (mark - self.average_price) * self.size)
+    }
+
+    /// Amount paid for the tokens currently held.
+    #[must_use]
+    pub fn cost_basis(&self) -> Decimal {
+        self.average_price * self.size
+    }
+
+    fn apply(&mut self, fill: &Fill) {
+        let signed = match fill.side {
+            Side::Buy => fill.size,
+            Side::Sell => -fill.size,
+            Side::Unknown => return,
+        };
+        self.fees_paid += fill.fee;
+
+        if signed.is_zero() {
+            return;
+        }
+
+        // Adding to the position (or opening one) moves the average price
+        if self.size.is_zero() || self.size.is_sign_positive() == signed.is_sign_positive() {
+            let size = self.size + signed;
+            self.average_price = (self.average_price * self.size + fill.price * signed) / size;
+            self.size = size;
+            return;
+        }
+
+        // Reducing the position realizes PnL on the closed portion, and any remainder opens a
+        // position on the other side at the fill price
+        let closed = signed.abs().min(self.size.abs());
+        let direction = if self.size.is_sign_positive() {
+            Decimal::ONE
+        } else {
+            Decimal::NEGATIVE_ONE
+        };
+        self.realized_pnl += (fill.price - self.average_price) * closed * direction;
+        self.size += signed;
+
+        if self.size.is_zero() {
+            self.average_price = Decimal::ZERO;
+        } else if self.size.is_sign_positive() == signed.is_sign_positive() {
+            self.average_price = fill.price;
+        }
+    }
+
+    fn reset(&mut self) {
+        *self = Self {
+            mark_price: self.mark_price,
+            ..Self::new(self.asset_id, self.market)
+        };
+    }
+}
+
+/// Which value of a position differs between the ledger and the data API.
+#[cfg(feature = "data")]
+#[non_exhaustive]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum DiscrepancyKind {
+    /// Number of tokens held.
+    Size,
+    /// Average entry price.
+    AveragePrice,
+}
+
+/// A difference between the ledger and a position reported by the data API.
+#[cfg(feature = "data")]
+#[non_exhaustive]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Discrepancy {
+    /// The token whose position differs.
+    pub asset_id: U256,
+    /// The value that differs.
+    pub kind: DiscrepancyKind,
+    /// The value computed by the ledger.
+    pub local: Decimal,
+    /// The value reported by the data API.
+    pub remote: Decimal,
+}
+
+/// Per-token positions, `PnL` and fees accumulated from trade fills.
+///
+/// # Example
+///
+/// ```rust, no_run
+/// use kuest_client_sdk::clob::ledger::Ledger;
+/// use kuest_client_sdk::clob::types::request::TradesRequest;
+/// # async fn example(
+/// #     client: kuest_client_sdk::clob::Client<kuest_client_sdk::auth::state::Authenticated<kuest_client_sdk::auth::Normal>>,
+/// # ) -> anyhow::Result<()> {
+///
+/// let mut ledger = Ledger::new();
+///
+/// let page = client.trades(&TradesRequest::default(), None).await?;
+/// ledger.apply_trades(&page.data);
+///
+/// for position in ledger.positions() {
+///     println!("{}: {} @ {}", position.asset_id, position.size, position.average_price);
+/// }
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct Ledger {
+    positions: HashMap<U256, TokenPosition>,
+    fills: Vec<Fill>,
+    trades: HashSet<String>,
+}
+
+impl Ledger {
+    /// Creates an empty ledger.
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Applies a trade returned by the REST API.
+    ///
+    /// Returns `true` if the ledger changed: the trade was applied for the first time, or a
+    /// previously applied trade failed and was reverted.
+    pub fn apply_trade(&mut self, trade: &TradeResponse) -> bool {
+        if trade.status == TradeStatusType::Failed {
+            return self.revert(&trade.id);
+        }
+
+        let fills = match trade.trader_side {
+            TraderSide::Taker => vec![Fill {
+                trade_id: trade.id.clone(),
+                market: trade.market,
+                asset_id: trade.asset_id,
+                side: trade.side,
+                size: trade.size,
+                price: trade.price,
+                fee: fee(trade.fee_rate_bps, trade.price, trade.size),
+            }],
+            TraderSide::Maker => trade
+                .maker_orders
+                .iter()
+                .filter(|order| order.owner == trade.owner)
+                .map(|order| Fill {
+                    trade_id: trade.id.clone(),
+                    market: trade.market,
+                    asset_id: order.asset_id,
+                    side: order.side,
+                    size: order.matched_amount,
+                    price: order.price,
+                    fee: fee(order.fee_rate_bps, order.price, order.matched_amount),
+                })
+                .collect(),
+            TraderSide::Unknown(_) => Vec::new(),
+        };
+
+        self.record(&trade.id, fills)
+    }
+
+    /// Applies every trade of a REST page, in order.
+    ///
+    /// Returns `true` if any trade changed the ledger.
+    pub fn apply_trades<'trade, I>(&mut self, trades: I) -> bool
+    where
+        I: IntoIterator<Item = &'trade TradeResponse>,
+    {
+        trades
+            .into_iter()
+            .fold(false, |changed, trade| self.apply_trade(trade) | changed)
+    }
+
+    /// Applies a trade event from the user channel.
+    ///
+    /// The owner's fills are identified by the event's `owner` API key. Maker fills received
+    /// over the user channel carry no fee rate and are recorded without fees.
+    ///
+    /// Returns `true` if the ledger changed, as for [`Ledger::apply_trade`].
+    #[cfg(feature = "ws")]
+    pub fn apply_trade_message(&mut self, message: &TradeMessage) -> bool {
+        if message.status == TradeMessageStatus::Failed {
+            return self.revert(&message.id);
+        }
+
+        let is_maker = match message.trader_side {
+            Some(TraderSide::Maker) => true,
+            Some(TraderSide::Taker) => false,
+            _ => message
+                .maker_orders
+                .iter()
+                .any(|order| Some(order.owner) == message.owner),
+        };
+
+        let fills = if is_maker {
+            message
+                .maker_orders
+                .iter()
+                .filter(|order| Some(order.owner) == message.owner)
+                .map(|order| Fill {
+                    trade_id: message.id.clone(),
+                    market: message.market,
+                    asset_id: order.asset_id,
+                    // A maker on the same token takes the other side of the trade, while a maker
+                    // on the complementary token trades in the same direction
+                    side: if order.asset_id == message.asset_id {
+                        opposite(message.side)
+                    } else {
+                        message.side
+                    },
+                    size: order.matched_amount,
+                    price: order.price,
+                    fee: Decimal::ZERO,
+                })
+                .collect()
+        } else {
+            vec![Fill {
+                trade_id: message.id.clone(),
+                market: message.market,
+                asset_id: message.asset_id,
+                side: message.side,
+                size: message.size,
+                price: message.price,
+                fee: message
+                    .fee_rate_bps
+                    .map_or(Decimal::ZERO, |rate| fee(rate, message.price, message.size)),
+            }]
+        };
+
+        self.record(&message.id, fills)
+    }
+
+    /// Marks a token's position to `price`, which is used for its unrealized `PnL`.
+    ///
+    /// Marks for tokens without a position are ignored.
+    pub fn mark(&mut self, asset_id: U256, price: Decimal) {
+        if let Some(position) = self.positions.get_mut(&asset_id) {
+            position.mark_price = Some(price);
+        }
+    }
+
+    /// Marks a token's position to a midpoint received from the market channel.
+    #[cfg(feature = "ws")]
+    pub fn apply_midpoint(&mut self, update: &MidpointUpdate) {
+        self.mark(update.asset_id, update.midpoint);
+    }
+
+    /// The position in a token, if any fill for it has been applied.
+    #[must_use]
+    pub fn position(&self, asset_id: &U256) -> Option<&TokenPosition> {
+        self.positions.get(asset_id)
+    }
+
+    /// All positions, including flat ones, in no particular order.
+    pub fn positions(&self) -> impl Iterator<Item = &TokenPosition> {
+        self.positions.values()
+    }
+
+    /// All fills applied to the ledger, in the order they were applied.
+    #[must_use]
+    pub fn fills(&self) -> &[Fill] {
+        &self.fills
+    }
+
+    /// Realized `PnL` across all positions, excluding fees.
+    #[must_use]
+    pub fn realized_pnl(&self) -> Decimal {
+        self.positions
+            .values()
+            .map(|position| position.realized_pnl)
+            .sum()
+    }
+
+    /// Unrealized `PnL` across all marked positions.
+    #[must_use]
+    pub fn unrealized_pnl(&self) -> Decimal {
+        self.positions
+            .values()
+            .filter_map(TokenPosition::unrealized_pnl)
+            .sum()
+    }
+
+    /// Estimated fees paid across all positions.
+    #[must_use]
+    pub fn fees_paid(&self) -> Decimal {
+        self.positions
+            .values()
+            .map(|position| position.fees_paid)
+            .sum()
+    }
+
+    /// Compares the ledger with positions reported by the data API.
+    ///
+    /// Sizes are compared for every token held on either side, and average prices for tokens
+    /// held on both. Differences up to `tolerance` are ignored. Realized `PnL` is not compared,
+    /// since the ledger only knows about fills applied since it was created.
+    #[cfg(feature = "data")]
+    #[must_use]
+    pub fn reconcile(&self, positions: &[Position], tolerance: Decimal) -> Vec<Discrepancy> {
+        let mut discrepancies = Vec::new();
+        let mut check = |asset_id, kind, local: Decimal, remote: Decimal| {
+            if (local - remote).abs() > tolerance {
+                discrepancies.push(Discrepancy {
+                    asset_id,
+                    kind,
+                    local,
+                    remote,
+                });
+            }
+        };
+
+        for remote in positions {
+            let local = self.positions.get(&remote.asset);
+            let size = local.map_or(Decimal::ZERO, |position| position.size);
+
+            check(remote.asset, DiscrepancyKind::Size, size, remote.size);
+            if let Some(local) = local.filter(|_| !size.is_zero() && !remote.size.is_zero()) {
+                check(
+                    remote.asset,
+                    DiscrepancyKind::AveragePrice,
+                    local.average_price,
+                    remote.avg_price,
+                );
+            }
+        }
+
+        let mut missing: Vec<_> = self
+            .positions
+            .values()
+            .filter(|local| {
+                !positions
+                    .iter()
+                    .any(|remote| remote.asset == local.asset_id)
+            })
+            .collect();
+        missing.sort_by_key(|local| local.asset_id);
+        for local in missing {
+            check(
+                local.asset_id,
+                DiscrepancyKind::Size,
+                local.size,
+                Decimal::ZERO,
+            );
+        }
+
+        discrepancies
+    }
+
+    fn record(&mut self, trade_id: &str, fills: Vec<Fill>) -> bool {
+        if fills.is_empty() || !self.trades.insert(trade_id.to_owned()) {
+            return false;
+        }
+
+        for fill in fills {
+            self.positions
+                .entry(fill.asset_id)
+                .or_insert_with(|| TokenPosition::new(fill.asset_id, fill.market))
+                .apply(&fill);
+            self.fills.push(fill);
+        }
+
+        true
+    }
+
+    /// Removes a failed trade's fills and replays the remaining fills of the affected tokens.
+    fn revert(&mut self, trade_id: &str) -> bool {
+        if !self.trades.remove(trade_id) {
+            return false;
+        }
+
+        let mut affected = HashSet::new();
+        self.fills.retain(|fill| {
+            let failed = fill.trade_id == trade_id;
+            if failed {
+                affected.insert(fill.asset_id);
+            }
+            !failed
+        });
+
+        for asset_id in affected {
+            if let Some(position) = self.positions.get_mut(&asset_id) {
+                position.reset();
+                for fill in self.fills.iter().filter(|fill| fill.asset_id == asset_id) {
+                    position.apply(fill);
+                }
+            }
+        }
+
+        true
+    }
+}
+
+pub(crate) fn fee(rate_bps: Decimal, price: Decimal, size: Decimal) -> Decimal {
+    rate_bps / BPS * price.min(Decimal::ONE - price) * size
+}
+
+#[cfg(feature = "ws")]
+const fn opposite(side: Side) -> Side {
+    match side {
+        Side::Buy => Side::Sell,
+        Side::Sell => Side::Buy,
+        Side::Unknown => Side::Unknown,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::Utc;
+    use rust_decimal_macros::dec;
+    use uuid::Uuid;
+
+    use super::*;
+    use crate::clob::types::response::MakerOrder;
+    use crate::types::Address;
+
+    const OWNER: Uuid = Uuid::from_u128(1);
+
+    fn trade(id: &str, side: Side, size: Decimal, price: Decimal) -> TradeResponse {
+        TradeResponse::builder()
+            .id(id)
+            .taker_order_id("0x01")
+            .market(B256::ZERO)
+            .asset_id(U256::from(1))
+            .side(side)
+            .size(size)
+            .fee_rate_bps(Decimal::ZERO)
+            .price(price)
+            .status(TradeStatusType::Matched)
+            .match_time(Utc::now())
+            .last_update(Utc::now())
+            .outcome("Yes")
+            .bucket_index(0)
+            .owner(OWNER)
+            .maker_address(Address::ZERO)
+            .maker_orders(vec![])
+            .transaction_hash(B256::ZERO)
+            .trader_side(TraderSide::Taker)
+            .build()
+    }
+
+    #[test]
+    fn average_cost_and_realized_pnl() {
+        let mut ledger = Ledger::new();
+
+        assert!(ledger.apply_trade(&trade("1", Side::Buy, dec!(10), dec!(0.40))));
+        assert!(ledger.apply_trade(&trade("2", Side::Buy, dec!(10), dec!(0.60))));
+        assert!(ledger.apply_trade(&trade("3", Side::Sell, dec!(5), dec!(0.70))));
+
+        let position = ledger.position(&U256::from(1)).unwrap();
+        assert_eq!(position.size, dec!(15));
+        assert_eq!(position.average_price, dec!(0.5));
+        assert_eq!(position.realized_pnl, dec!(1));
+        assert_eq!(position.unrealized_pnl(), None);
+
+        ledger.mark(U256::from(1), dec!(0.55));
+        assert_eq!(ledger.unrealized_pnl(), dec!(0.75));
+    }
+
+    #[test]
+    fn flipping_position_reopens_at_fill_price() {
+        let mut ledger = Ledger::new();
+
+        _ = ledger.apply_trade(&trade("1", Side::Buy, dec!(5), dec!(0.40)));
+        _ = ledger.apply_trade(&trade("2", Side::Sell, dec!(8), dec!(0.50)));
+
+        let position = ledger.position(&U256::from(1)).unwrap();
+        assert_eq!(position.size, dec!(-3));
+        assert_eq!(position.average_price, dec!(0.50));
+        assert_eq!(position.realized_pnl, dec!(0.5));
+    }
+
+    #[test]
+    fn status_updates_apply_once_and_failures_revert() {
+        let mut ledger = Ledger::new();
+        let mut second = trade("2", Side::Buy, dec!(10), dec!(0.60));
+
+        _ = ledger.apply_trade(&trade("1", Side::Buy, dec!(10), dec!(0.40)));
+        assert!(ledger.apply_trade(&second));
+
+        second.status = TradeStatusType::Mined;
+        assert!(!ledger.apply_trade(&second));
+        assert_eq!(ledger.position(&U256::from(1)).unwrap().size, dec!(20));
+
+        second.status = TradeStatusType::Failed;
+        assert!(ledger.apply_trade(&second));
+
+        let position = ledger.position(&U256::from(1)).unwrap();
+        assert_eq!(position.size, dec!(10));
+        assert_eq!(position.average_price, dec!(0.40));
+        assert_eq!(ledger.fills().len(), 1);
+    }
+
+    #[test]
+    fn maker_fills_use_own_orders_and_fees() {
+        let maker_order = |owner, side, price| {
+            MakerOrder::builder()
+                .order_id("0x02")
+                .owner(owner)
+                .maker_address(Address::ZERO)
+                .matched_amount(dec!(4))
+                .price(price)
+                .fee_rate_bps(dec!(100))
+                .asset_id(U256::from(2))
+                .outcome("No")
+                .side(side)
+                .build()
+        };
+
+        let mut maker = trade("1", Side::Buy, dec!(8), dec!(0.70));
+        maker.trader_side = TraderSide::Maker;
+        maker.maker_orders = vec![
+            maker_order(OWNER, Side::Buy, dec!(0.30)),
+            maker_order(Uuid::from_u128(2), Side::Buy, dec!(0.30)),
+        ];
+
+        let mut ledger = Ledger::new();
+        assert!(ledger.apply_trade(&maker));
+
+        assert!(ledger.position(&U256::from(1)).is_none());
+        let position = ledger.position(&U256::from(2)).unwrap();
+        assert_eq!(position.size, dec!(4));
+        assert_eq!(position.average_price, dec!(0.30));
+        assert_eq!(ledger.fees_paid(), dec!(0.012));
+    }
+
+    #[cfg(feature = "ws")]
+    #[test]
+    fn trade_message_maker_takes_opposite_side() {
+        let message: TradeMessage = serde_json::from_value(serde_json::json!({
+            "id": "1",
+            "market": B256::ZERO,
+            "asset_id": "1",
+            "side": "BUY",
+            "size": "10",
+            "price": "0.6",
+            "status": "MATCHED",
+            "owner": OWNER,
+            "maker_orders": [{
+                "asset_id": "1",
+                "matched_amount": "10",
+                "order_id": "0x02",
+                "outcome": "Yes",
+                "owner": OWNER,
+                "price": "0.6"
+            }]
+        }))
+        .unwrap();
+
+        let mut ledger = Ledger::new();
+        assert!(ledger.apply_trade_message(&message));
+
+        let position = ledger.position(&U256::from(1)).unwrap();
+        assert_eq!(position.size, dec!(-10));
+        assert_eq!(position.average_price, dec!(0.6));
+    }
+
+    #[cfg(feature = "data")]
+    #[test]
+    fn reconcile_reports_size_and_price_differences() {
+        let position = |asset: u64, size: &str, avg_price: &str| -> Position {
+            serde_json::from_value(serde_json::json!({
+                "proxyWallet": Address::ZERO,
+                "asset": asset.to_string(),
+                "conditionId": B256::ZERO,
+                "size": size,
+                "avgPrice": avg_price,
+                "initialValue": "0",
+                "currentValue": "0",
+                "cashPnl": "0",
+                "percentPnl": "0",
+                "totalBought": "0",
+                "realizedPnl": "0",
+                "percentRealizedPnl": "0",
+                "curPrice": "0",
+                "redeemable": false,
+                "mergeable": false,
+                "title": "",
+                "slug": "",
+                "icon": "",
+                "eventSlug": "",
+                "outcome": "Yes",
+                "outcomeIndex": 0,
+                "oppositeOutcome": "No",
+                "oppositeAsset": "0",
+                "endDate": "2026-01-01",
+                "negativeRisk": false
+            }))
+            .unwrap()
+        };
+
+        let mut ledger = Ledger::new();
+        _ = ledger.apply_trade(&trade("1", Side::Buy, dec!(10), dec!(0.40)));
+        let mut other = trade("2", Side::Buy, dec!(5), dec!(0.20));
+        other.asset_id = U256::from(3);
+        _ = ledger.apply_trade(&other);
+
+        let discrepancies = ledger.reconcile(
+            &[position(1, "10", "0.45"), position(2, "7", "0.5")],
+            dec!(0.001),
+        );
+
+        assert_eq!(
+            discrepancies,
+            vec![
+                Discrepancy {
+                    asset_id: U256::from(1),
+                    kind: DiscrepancyKind::AveragePrice,
+                    local: dec!(0.40),
+                    remote: dec!(0.45),
+                },
+                Discrepancy {
+                    asset_id: U256::from(2),
+                    kind: DiscrepancyKind::Size,
+                    local: Decimal::ZERO,
+                    remote: dec!(7),
+                },
+                Discrepancy {
+                    asset_id: U256::from(3),
+                    kind: DiscrepancyKind::Size,
+                    local: dec!(5),
+                    remote: Decimal::ZERO,
+                },
+            ]
+        );
+    }
+}
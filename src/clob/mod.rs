@@ -136,6 +136,7 @@
 //!
 //! - **`ws`**: Enables WebSocket support for real-time orderbook and trade streams
 //! - **`heartbeats`**: Enables automatic heartbeat mechanism for authenticated sessions
+//! - **`refresh`**: Enables automatic re-posting of expiring GTD orders
 //! - **`tracing`**: Enables detailed request/response tracing
 //! - **`rfq`**: Enables RFQ (Request for Quote) endpoints for institutional trading
 //!
@@ -143,8 +144,21 @@
 //!
 //! The default API endpoint is `https://clob.kuest.com`.
 
+pub mod batch;
+pub mod book_hash;
 pub mod client;
+pub mod describe;
+pub mod funds;
+pub mod ladder;
+pub mod ledger;
 pub mod order_builder;
+pub mod quote;
+pub mod rate_limit;
+#[cfg(feature = "refresh")]
+pub mod refresh;
+pub mod risk;
+pub mod salt;
+pub mod simulation;
 pub mod types;
 #[cfg(feature = "ws")]
 pub mod ws;
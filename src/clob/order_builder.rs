@@ -1,27 +1,46 @@
+use std::borrow::Cow;
 use std::marker::PhantomData;
-use std::time::{SystemTime, UNIX_EPOCH};
-
-use alloy::primitives::U256;
-use chrono::{DateTime, Utc};
-use rand::Rng as _;
+use std::sync::Arc;
+use std::time::Duration;
+
+use alloy::dyn_abi::Eip712Domain;
+use alloy::primitives::{ChainId, U256};
+use alloy::signers::Signer;
+use alloy::sol_types::SolStruct as _;
+use bon::Builder;
+use chrono::{DateTime, TimeDelta, Utc};
+use rust_decimal::RoundingStrategy;
 use rust_decimal::prelude::ToPrimitive as _;
 
-use crate::Result;
+use crate::auth::ApiKey;
 use crate::auth::Kind as AuthKind;
 use crate::auth::state::Authenticated;
 use crate::clob::Client;
+use crate::clob::salt::{SALT_MASK, SaltGenerator, UuidSalt};
+use crate::clob::simulation::simulate_market_order;
 use crate::clob::types::request::OrderBookSummaryRequest;
 use crate::clob::types::{
-    Amount, AmountInner, Order, OrderType, Side, SignableOrder, SignatureType,
+    Amount, AmountInner, Order, OrderType, Side, SignableOrder, SignatureType, SignedOrder,
+    TickSize,
 };
 use crate::error::Error;
-use crate::types::{Address, Decimal};
+use crate::types::{Address, Decimal, dec};
+use crate::{Result, Timestamp, contract_config};
 
 pub(crate) const USDC_DECIMALS: u32 = 6;
 
 /// Maximum number of decimal places for `size`
 pub(crate) const LOT_SIZE_SCALE: u32 = 2;
 
+const BPS: Decimal = dec!(10_000);
+
+/// Margin the CLOB requires between its clock and the expiration of a [`OrderType::GTD`] order.
+/// An order meant to rest on the book for `N` seconds must expire `N` seconds after this buffer.
+pub const GTD_SECURITY_BUFFER: Duration = Duration::from_secs(60);
+
+const ORDER_NAME: Option<Cow<'static, str>> = Some(Cow::Borrowed("Kuest CTF Exchange"));
+const VERSION: Option<Cow<'static, str>> = Some(Cow::Borrowed("1"));
+
 /// Placeholder type for compile-time checks on limit order builders
 #[non_exhaustive]
 #[derive(Debug)]
@@ -38,7 +57,7 @@ pub struct OrderBuilder<OrderKind, K: AuthKind> {
     pub(crate) client: Client<Authenticated<K>>,
     pub(crate) signer: Address,
     pub(crate) signature_type: SignatureType,
-    pub(crate) salt_generator: fn() -> u64,
+    pub(crate) salt_generator: Arc<dyn SaltGenerator>,
     pub(crate) token_id: Option<U256>,
     pub(crate) price: Option<Decimal>,
     pub(crate) size: Option<Decimal>,
@@ -46,10 +65,14 @@ pub struct OrderBuilder<OrderKind, K: AuthKind> {
     pub(crate) side: Option<Side>,
     pub(crate) nonce: Option<u64>,
     pub(crate) expiration: Option<DateTime<Utc>>,
+    pub(crate) expires_in: Option<Duration>,
     pub(crate) taker: Option<Address>,
     pub(crate) order_type: Option<OrderType>,
     pub(crate) post_only: Option<bool>,
     pub(crate) funder: Option<Address>,
+    pub(crate) max_slippage_bps: Option<u32>,
+    pub(crate) worst_price: Option<Decimal>,
+    pub(crate) slippage_action: SlippageAction,
     pub(crate) _kind: PhantomData<OrderKind>,
 }
 
@@ -99,6 +122,28 @@ impl<OrderKind, K: AuthKind> OrderBuilder<OrderKind, K> {
         self.post_only = Some(post_only);
         self
     }
+
+    /// Carries the fields of this builder over to an [`OfflineOrderBuilder`].
+    pub(crate) fn offline(self, market: MarketParameters) -> OfflineOrderBuilder<OrderKind> {
+        OfflineOrderBuilder {
+            market,
+            signer: self.signer,
+            signature_type: self.signature_type,
+            salt_generator: self.salt_generator,
+            token_id: self.token_id,
+            price: self.price,
+            size: self.size,
+            amount: self.amount,
+            side: self.side,
+            nonce: self.nonce,
+            expiration: self.expiration,
+            taker: self.taker,
+            order_type: self.order_type,
+            post_only: self.post_only,
+            funder: self.funder,
+            _kind: PhantomData,
+        }
+    }
 }
 
 impl<K: AuthKind> OrderBuilder<Limit, K> {
@@ -116,11 +161,262 @@ impl<K: AuthKind> OrderBuilder<Limit, K> {
         self
     }
 
+    /// Makes this a [`OrderType::GTD`] order that rests on the book for `lifetime`, measured
+    /// against the server's clock.
+    ///
+    /// [`Self::build`] fetches the server time and signs the order with an expiration of
+    /// `server time + GTD_SECURITY_BUFFER + lifetime`, see [`GTD_SECURITY_BUFFER`]. This cannot be
+    /// combined with [`Self::expiration`], nor with an order type other than GTD.
+    #[must_use]
+    pub fn expires_in(mut self, lifetime: Duration) -> Self {
+        self.expires_in = Some(lifetime);
+        self
+    }
+
     /// Validates and transforms this limit builder into a [`SignableOrder`]
     #[cfg_attr(
         feature = "tracing",
         tracing::instrument(skip(self), err(level = "warn"))
     )]
+    pub async fn build(mut self) -> Result<SignableOrder> {
+        let Some(token_id) = self.token_id else {
+            return Err(Error::validation(
+                "Unable to build Order due to missing token ID",
+            ));
+        };
+
+        if self.side.is_none() {
+            return Err(Error::validation(
+                "Unable to build Order due to missing token side",
+            ));
+        }
+
+        let Some(price) = self.price else {
+            return Err(Error::validation(
+                "Unable to build Order due to missing price",
+            ));
+        };
+
+        if price.is_sign_negative() {
+            return Err(Error::validation(format!(
+                "Unable to build Order due to negative price {price}"
+            )));
+        }
+
+        if let Some(lifetime) = self.expires_in {
+            if self.expiration.is_some() {
+                return Err(Error::validation(
+                    "Unable to build Order: `expires_in` and `expiration` cannot both be set",
+                ));
+            }
+
+            if let Some(order_type) = &self.order_type
+                && !matches!(order_type, OrderType::GTD)
+            {
+                return Err(Error::validation(format!(
+                    "Only GTD orders may have a non-zero expiration, found {order_type}"
+                )));
+            }
+
+            let server_time = self.client.server_time().await?;
+            self.expiration = Some(gtd_expiration(server_time, lifetime)?);
+            self.order_type = Some(OrderType::GTD);
+        }
+
+        let fee_rate = self.client.fee_rate_bps(token_id).await?;
+        let tick_size = self.client.tick_size(token_id).await?.minimum_tick_size;
+
+        self.offline(MarketParameters {
+            tick_size,
+            fee_rate_bps: fee_rate.base_fee,
+        })
+        .build()
+    }
+}
+
+/// Returns the expiration of a [`OrderType::GTD`] order resting on the book for `lifetime` from
+/// `server_time`, including the [`GTD_SECURITY_BUFFER`].
+pub(crate) fn gtd_expiration(server_time: Timestamp, lifetime: Duration) -> Result<DateTime<Utc>> {
+    if lifetime.is_zero() {
+        return Err(Error::validation(
+            "Unable to build Order: `expires_in` must be greater than zero",
+        ));
+    }
+
+    let Some(now) = DateTime::<Utc>::from_timestamp(server_time, 0) else {
+        return Err(Error::validation(format!(
+            "Unable to represent server time {server_time} as a date"
+        )));
+    };
+
+    TimeDelta::from_std(GTD_SECURITY_BUFFER + lifetime)
+        .ok()
+        .and_then(|delta| now.checked_add_signed(delta))
+        .ok_or_else(|| {
+            Error::validation(format!(
+                "Unable to build Order: expiring {lifetime:?} after {now} is out of range"
+            ))
+        })
+}
+
+impl<K: AuthKind> OrderBuilder<Market, K> {
+    /// Sets the price for this market builder. This is an optional field.
+    #[must_use]
+    pub fn price(mut self, price: Decimal) -> Self {
+        self.price = Some(price);
+        self
+    }
+
+    /// Sets the [`Amount`] for this market order. This is a required field.
+    #[must_use]
+    pub fn amount(mut self, amount: Amount) -> Self {
+        self.amount = Some(amount);
+        self
+    }
+
+    /// Bounds the market price at `max_slippage_bps` basis points away from the best opposing
+    /// price: above the best ask for buys, and below the best bid for sells.
+    ///
+    /// Only applies when the price is derived from the order book, since a price set with
+    /// [`Self::price`] already caps the fill price. When the bound is breached, the builder
+    /// behaves according to [`Self::on_slippage`].
+    #[must_use]
+    pub fn max_slippage_bps(mut self, max_slippage_bps: u32) -> Self {
+        self.max_slippage_bps = Some(max_slippage_bps);
+        self
+    }
+
+    /// Bounds the market price at `worst_price`: the highest price a buy may pay, or the lowest
+    /// price a sell may receive.
+    ///
+    /// Only applies when the price is derived from the order book, and is combined with
+    /// [`Self::max_slippage_bps`] by taking the tighter of the two bounds.
+    #[must_use]
+    pub fn worst_price(mut self, worst_price: Decimal) -> Self {
+        self.worst_price = Some(worst_price);
+        self
+    }
+
+    /// Sets what happens when the market price breaches a slippage bound. Defaults to
+    /// [`SlippageAction::Reject`].
+    #[must_use]
+    pub fn on_slippage(mut self, action: SlippageAction) -> Self {
+        self.slippage_action = action;
+        self
+    }
+
+    // Attempts to calculate the market price from the top of the book for the particular token.
+    // - Uses `simulate_market_order` to find the cutoff price:
+    //   - BUY + USDC: walk asks until notional >= USDC
+    //   - BUY + Shares: walk asks until shares >= N
+    //   - SELL + Shares: walk bids until shares >= N
+    async fn calculate_price(&self, order_type: OrderType) -> Result<Decimal> {
+        let token_id = self
+            .token_id
+            .expect("Token ID was already validated in `build`");
+        let side = self.side.expect("Side was already validated in `build`");
+        let amount = self
+            .amount
+            .expect("Amount was already validated in `build`");
+
+        let book = self
+            .client
+            .order_book(&OrderBookSummaryRequest {
+                token_id,
+                side: None,
+            })
+            .await?;
+
+        if !matches!(order_type, OrderType::FAK | OrderType::FOK) {
+            return Err(Error::validation(
+                "Cannot set an order type other than FAK/FOK for a market order",
+            ));
+        }
+
+        let simulation = simulate_market_order(&book, side, amount, 0)?;
+
+        let Some(worst_price) = simulation.worst_price else {
+            return Err(Error::validation(format!(
+                "No opposing orders for {token_id} which means there is no market price"
+            )));
+        };
+
+        if !simulation.is_fully_filled() && matches!(order_type, OrderType::FOK) {
+            return Err(Error::validation(format!(
+                "Insufficient liquidity to fill order for {token_id} at {}",
+                amount.as_inner()
+            )));
+        }
+
+        let best_price = simulation
+            .fills
+            .first()
+            .map_or(worst_price, |fill| fill.price);
+        self.bound_price(best_price, worst_price).await
+    }
+
+    // Checks the cutoff price against the `worst_price` and `max_slippage_bps` bounds, either
+    // rejecting the order or clamping the price to the bound, rounded to the tick size in the
+    // direction that keeps it within the bound.
+    async fn bound_price(&self, best_price: Decimal, price: Decimal) -> Result<Decimal> {
+        let token_id = self
+            .token_id
+            .expect("Token ID was already validated in `build`");
+        let side = self.side.expect("Side was already validated in `build`");
+
+        let tolerance = self
+            .max_slippage_bps
+            .map(|bps| best_price * Decimal::from(bps) / BPS);
+        let buying = side == Side::Buy;
+        let bounds = [
+            self.worst_price,
+            tolerance.map(|t| {
+                if buying {
+                    best_price + t
+                } else {
+                    best_price - t
+                }
+            }),
+        ]
+        .into_iter()
+        .flatten();
+        let bound = if buying { bounds.min() } else { bounds.max() };
+
+        let Some(bound) = bound.filter(|&bound| if buying { price > bound } else { price < bound })
+        else {
+            return Ok(price);
+        };
+
+        match self.slippage_action {
+            SlippageAction::Reject => Err(Error::validation(format!(
+                "Market price {price} for {token_id} breaches the slippage bound {bound}"
+            ))),
+            SlippageAction::Clamp => {
+                let decimals = self
+                    .client
+                    .tick_size(token_id)
+                    .await?
+                    .minimum_tick_size
+                    .as_decimal()
+                    .scale();
+
+                #[cfg(feature = "tracing")]
+                tracing::debug!(token_id = %token_id, price = %price, bound = %bound, "market price clamped to slippage bound");
+
+                Ok(if buying {
+                    bound.trunc_with_scale(decimals)
+                } else {
+                    bound.round_dp_with_strategy(decimals, RoundingStrategy::AwayFromZero)
+                })
+            }
+        }
+    }
+
+    /// Validates and transforms this market builder into a [`SignableOrder`]
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(skip(self), err(level = "warn"))
+    )]
     pub async fn build(self) -> Result<SignableOrder> {
         let Some(token_id) = self.token_id else {
             return Err(Error::validation(
@@ -128,6 +424,246 @@ impl<K: AuthKind> OrderBuilder<Limit, K> {
             ));
         };
 
+        if self.side.is_none() {
+            return Err(Error::validation(
+                "Unable to build Order due to missing token side",
+            ));
+        }
+
+        if self.amount.is_none() {
+            return Err(Error::validation(
+                "Unable to build Order due to missing amount",
+            ));
+        }
+
+        if self.post_only == Some(true) {
+            return Err(Error::validation(
+                "postOnly is only supported for limit orders",
+            ));
+        }
+
+        let order_type = self.order_type.clone().unwrap_or(OrderType::FAK);
+        let price = match self.price {
+            Some(price) => price,
+            None => self.calculate_price(order_type).await?,
+        };
+
+        let tick_size = self.client.tick_size(token_id).await?.minimum_tick_size;
+        let fee_rate = self.client.fee_rate_bps(token_id).await?;
+
+        self.offline(MarketParameters {
+            tick_size,
+            fee_rate_bps: fee_rate.base_fee,
+        })
+        .price(price)
+        .build()
+    }
+}
+
+/// What a market [`OrderBuilder`] does when the price derived from the order book breaches
+/// its [`worst_price`](OrderBuilder::worst_price) or
+/// [`max_slippage_bps`](OrderBuilder::max_slippage_bps) bound.
+#[non_exhaustive]
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum SlippageAction {
+    /// Refuse to build the order.
+    #[default]
+    Reject,
+    /// Build the order with the bound as its price. The order then only fills the part of the
+    /// book within the bound, so a FOK order may be killed by the exchange.
+    Clamp,
+}
+
+/// Market parameters that [`OrderBuilder`] otherwise looks up from the CLOB while building.
+///
+/// These are the token's minimum tick size and fee rate, as returned by
+/// [`Client::tick_size`] and [`Client::fee_rate_bps`]. Whether the token trades on the neg risk
+/// exchange only matters for signing, and is passed to [`sign_order`] instead.
+#[non_exhaustive]
+#[derive(Clone, Copy, Debug, Builder)]
+pub struct MarketParameters {
+    /// Minimum tick size of the token's market.
+    pub tick_size: TickSize,
+    /// Base fee rate of the token, in basis points.
+    #[builder(default)]
+    pub fee_rate_bps: u32,
+}
+
+/// Builds orders without a [`Client`] and without any network requests.
+///
+/// The market parameters that [`OrderBuilder`] fetches from the CLOB are supplied up front as
+/// [`MarketParameters`], so that orders can be prepared on a machine that holds the signing key
+/// but has no connection to the exchange. Together with [`sign_order`], this produces a
+/// [`SignedOrder`] whose JSON can be submitted later with [`Client::post_order`] once
+/// deserialized.
+///
+/// Unlike [`OrderBuilder`], the maker address is not derived from the signer. Orders signed for
+/// a proxy or Safe wallet must set both [`Self::signature_type`] and [`Self::funder`].
+///
+/// # Example
+///
+/// ```
+/// use kuest_client_sdk::clob::order_builder::{MarketParameters, OfflineOrderBuilder};
+/// use kuest_client_sdk::clob::types::{Side, TickSize};
+/// use kuest_client_sdk::types::{Address, U256};
+/// use rust_decimal_macros::dec;
+///
+/// let market = MarketParameters::builder()
+///     .tick_size(TickSize::Hundredth)
+///     .build();
+///
+/// let order = OfflineOrderBuilder::limit(Address::ZERO, market)
+///     .token_id(U256::from(1))
+///     .side(Side::Buy)
+///     .price(dec!(0.5))
+///     .size(dec!(10))
+///     .build()?;
+/// # Ok::<(), kuest_client_sdk::error::Error>(())
+/// ```
+#[expect(
+    clippy::module_name_repetitions,
+    reason = "Named after the `OrderBuilder` it mirrors"
+)]
+#[derive(Debug)]
+pub struct OfflineOrderBuilder<OrderKind> {
+    market: MarketParameters,
+    signer: Address,
+    signature_type: SignatureType,
+    salt_generator: Arc<dyn SaltGenerator>,
+    token_id: Option<U256>,
+    price: Option<Decimal>,
+    size: Option<Decimal>,
+    amount: Option<Amount>,
+    side: Option<Side>,
+    nonce: Option<u64>,
+    expiration: Option<DateTime<Utc>>,
+    taker: Option<Address>,
+    order_type: Option<OrderType>,
+    post_only: Option<bool>,
+    funder: Option<Address>,
+    _kind: PhantomData<OrderKind>,
+}
+
+impl<OrderKind> OfflineOrderBuilder<OrderKind> {
+    fn new(signer: Address, market: MarketParameters) -> Self {
+        Self {
+            market,
+            signer,
+            signature_type: SignatureType::Eoa,
+            salt_generator: Arc::new(UuidSalt),
+            token_id: None,
+            price: None,
+            size: None,
+            amount: None,
+            side: None,
+            nonce: None,
+            expiration: None,
+            taker: None,
+            order_type: None,
+            post_only: None,
+            funder: None,
+            _kind: PhantomData,
+        }
+    }
+
+    /// Sets the `token_id` for this builder. This is a required field.
+    #[must_use]
+    pub fn token_id(mut self, token_id: U256) -> Self {
+        self.token_id = Some(token_id);
+        self
+    }
+
+    /// Sets the [`Side`] for this builder. This is a required field.
+    #[must_use]
+    pub fn side(mut self, side: Side) -> Self {
+        self.side = Some(side);
+        self
+    }
+
+    /// Sets the nonce for this builder.
+    #[must_use]
+    pub fn nonce(mut self, nonce: u64) -> Self {
+        self.nonce = Some(nonce);
+        self
+    }
+
+    #[must_use]
+    pub fn expiration(mut self, expiration: DateTime<Utc>) -> Self {
+        self.expiration = Some(expiration);
+        self
+    }
+
+    #[must_use]
+    pub fn taker(mut self, taker: Address) -> Self {
+        self.taker = Some(taker);
+        self
+    }
+
+    #[must_use]
+    pub fn order_type(mut self, order_type: OrderType) -> Self {
+        self.order_type = Some(order_type);
+        self
+    }
+
+    /// Sets the `postOnly` flag for this builder.
+    #[must_use]
+    pub fn post_only(mut self, post_only: bool) -> Self {
+        self.post_only = Some(post_only);
+        self
+    }
+
+    /// Sets the [`SignatureType`] of the order. Defaults to [`SignatureType::Eoa`].
+    #[must_use]
+    pub fn signature_type(mut self, signature_type: SignatureType) -> Self {
+        self.signature_type = signature_type;
+        self
+    }
+
+    /// Sets the address holding the funds, which becomes the order's maker. Defaults to the
+    /// signer.
+    #[must_use]
+    pub fn funder(mut self, funder: Address) -> Self {
+        self.funder = Some(funder);
+        self
+    }
+
+    /// Sets the [`SaltGenerator`] used to generate the order's salt. Defaults to [`UuidSalt`].
+    #[must_use]
+    pub fn salt_generator<G: SaltGenerator + 'static>(mut self, salt_generator: G) -> Self {
+        self.salt_generator = Arc::new(salt_generator);
+        self
+    }
+}
+
+impl OfflineOrderBuilder<Limit> {
+    /// Creates a builder for a limit order signed by `signer`.
+    #[must_use]
+    pub fn limit(signer: Address, market: MarketParameters) -> Self {
+        Self::new(signer, market)
+    }
+
+    /// Sets the price for this limit builder. This is a required field.
+    #[must_use]
+    pub fn price(mut self, price: Decimal) -> Self {
+        self.price = Some(price);
+        self
+    }
+
+    /// Sets the size for this limit builder. This is a required field.
+    #[must_use]
+    pub fn size(mut self, size: Decimal) -> Self {
+        self.size = Some(size);
+        self
+    }
+
+    /// Validates and transforms this limit builder into a [`SignableOrder`]
+    pub fn build(self) -> Result<SignableOrder> {
+        let Some(token_id) = self.token_id else {
+            return Err(Error::validation(
+                "Unable to build Order due to missing token ID",
+            ));
+        };
+
         let Some(side) = self.side else {
             return Err(Error::validation(
                 "Unable to build Order due to missing token side",
@@ -146,13 +682,7 @@ impl<K: AuthKind> OrderBuilder<Limit, K> {
             )));
         }
 
-        let fee_rate = self.client.fee_rate_bps(token_id).await?;
-        let minimum_tick_size = self
-            .client
-            .tick_size(token_id)
-            .await?
-            .minimum_tick_size
-            .as_decimal();
+        let minimum_tick_size = self.market.tick_size.as_decimal();
 
         let decimals = minimum_tick_size.scale();
 
@@ -229,7 +759,7 @@ impl<K: AuthKind> OrderBuilder<Limit, K> {
             side => return Err(Error::validation(format!("Invalid side: {side}"))),
         };
 
-        let salt = to_ieee_754_int((self.salt_generator)());
+        let salt = to_ieee_754_int(self.salt_generator.next_salt());
 
         let order = Order {
             salt: U256::from(salt),
@@ -239,7 +769,7 @@ impl<K: AuthKind> OrderBuilder<Limit, K> {
             makerAmount: U256::from(to_fixed_u128(maker_amount)),
             takerAmount: U256::from(to_fixed_u128(taker_amount)),
             side: side as u8,
-            feeRateBps: U256::from(fee_rate.base_fee),
+            feeRateBps: U256::from(self.market.fee_rate_bps),
             nonce: U256::from(nonce),
             signer: self.signer,
             expiration: U256::from(expiration.timestamp().to_u64().ok_or(Error::validation(
@@ -259,8 +789,15 @@ impl<K: AuthKind> OrderBuilder<Limit, K> {
     }
 }
 
-impl<K: AuthKind> OrderBuilder<Market, K> {
-    /// Sets the price for this market builder. This is an optional field.
+impl OfflineOrderBuilder<Market> {
+    /// Creates a builder for a market order signed by `signer`.
+    #[must_use]
+    pub fn market(signer: Address, market: MarketParameters) -> Self {
+        Self::new(signer, market)
+    }
+
+    /// Sets the worst price this market order may fill at. This is a required field, since
+    /// there is no order book to derive it from.
     #[must_use]
     pub fn price(mut self, price: Decimal) -> Self {
         self.price = Some(price);
@@ -274,78 +811,8 @@ impl<K: AuthKind> OrderBuilder<Market, K> {
         self
     }
 
-    // Attempts to calculate the market price from the top of the book for the particular token.
-    // - Uses an orderbook depth search to find the cutoff price:
-    //   - BUY + USDC: walk asks until notional >= USDC
-    //   - BUY + Shares: walk asks until shares >= N
-    //   - SELL + Shares: walk bids until shares >= N
-    async fn calculate_price(&self, order_type: OrderType) -> Result<Decimal> {
-        let token_id = self
-            .token_id
-            .expect("Token ID was already validated in `build`");
-        let side = self.side.expect("Side was already validated in `build`");
-        let amount = self
-            .amount
-            .as_ref()
-            .expect("Amount was already validated in `build`");
-
-        let book = self
-            .client
-            .order_book(&OrderBookSummaryRequest {
-                token_id,
-                side: None,
-            })
-            .await?;
-
-        if !matches!(order_type, OrderType::FAK | OrderType::FOK) {
-            return Err(Error::validation(
-                "Cannot set an order type other than FAK/FOK for a market order",
-            ));
-        }
-
-        let (levels, amount) = match side {
-            Side::Buy => (book.asks, amount.0),
-            Side::Sell => match amount.0 {
-                a @ AmountInner::Shares(_) => (book.bids, a),
-                AmountInner::Usdc(_) => {
-                    return Err(Error::validation(
-                        "Sell Orders must specify their `amount`s in shares",
-                    ));
-                }
-            },
-
-            side => return Err(Error::validation(format!("Invalid side: {side}"))),
-        };
-
-        let first = levels.first().ok_or(Error::validation(format!(
-            "No opposing orders for {token_id} which means there is no market price"
-        )))?;
-
-        let mut sum = Decimal::ZERO;
-        let cutoff_price = levels.iter().rev().find_map(|level| {
-            match amount {
-                AmountInner::Usdc(_) => sum += level.size * level.price,
-                AmountInner::Shares(_) => sum += level.size,
-            }
-            (sum >= amount.as_inner()).then_some(level.price)
-        });
-
-        match cutoff_price {
-            Some(price) => Ok(price),
-            None if matches!(order_type, OrderType::FOK) => Err(Error::validation(format!(
-                "Insufficient liquidity to fill order for {token_id} at {}",
-                amount.as_inner()
-            ))),
-            None => Ok(first.price),
-        }
-    }
-
     /// Validates and transforms this market builder into a [`SignableOrder`]
-    #[cfg_attr(
-        feature = "tracing",
-        tracing::instrument(skip(self), err(level = "warn"))
-    )]
-    pub async fn build(self) -> Result<SignableOrder> {
+    pub fn build(self) -> Result<SignableOrder> {
         let Some(token_id) = self.token_id else {
             return Err(Error::validation(
                 "Unable to build Order due to missing token ID",
@@ -365,25 +832,20 @@ impl<K: AuthKind> OrderBuilder<Market, K> {
         let nonce = self.nonce.unwrap_or(0);
         let taker = self.taker.unwrap_or(Address::ZERO);
 
-        let order_type = self.order_type.clone().unwrap_or(OrderType::FAK);
-        let post_only = self.post_only;
-        if post_only == Some(true) {
+        let order_type = self.order_type.unwrap_or(OrderType::FAK);
+        if self.post_only == Some(true) {
             return Err(Error::validation(
                 "postOnly is only supported for limit orders",
             ));
         }
-        let price = match self.price {
-            Some(price) => price,
-            None => self.calculate_price(order_type.clone()).await?,
+
+        let Some(price) = self.price else {
+            return Err(Error::validation(
+                "Unable to build Order due to missing price",
+            ));
         };
 
-        let minimum_tick_size = self
-            .client
-            .tick_size(token_id)
-            .await?
-            .minimum_tick_size
-            .as_decimal();
-        let fee_rate = self.client.fee_rate_bps(token_id).await?;
+        let minimum_tick_size = self.market.tick_size.as_decimal();
 
         let decimals = minimum_tick_size.scale();
 
@@ -441,7 +903,7 @@ impl<K: AuthKind> OrderBuilder<Market, K> {
             (side, _) => return Err(Error::validation(format!("Invalid side: {side}"))),
         };
 
-        let salt = to_ieee_754_int((self.salt_generator)());
+        let salt = to_ieee_754_int(self.salt_generator.next_salt());
 
         let order = Order {
             salt: U256::from(salt),
@@ -451,7 +913,7 @@ impl<K: AuthKind> OrderBuilder<Market, K> {
             makerAmount: U256::from(to_fixed_u128(maker_amount)),
             takerAmount: U256::from(to_fixed_u128(taker_amount)),
             side: side as u8,
-            feeRateBps: U256::from(fee_rate.base_fee),
+            feeRateBps: U256::from(self.market.fee_rate_bps),
             nonce: U256::from(nonce),
             signer: self.signer,
             expiration: U256::ZERO,
@@ -469,6 +931,54 @@ impl<K: AuthKind> OrderBuilder<Market, K> {
     }
 }
 
+/// Signs `order` for the exchange contract of `chain_id` without any network requests.
+///
+/// `neg_risk` selects the neg risk exchange as the verifying contract, and must match
+/// [`Client::neg_risk`] for the order's token. `owner` is the API key of the account that will
+/// post the order. [`Client::sign`] calls this with values looked up from the CLOB.
+///
+/// # Errors
+///
+/// Returns an error if there is no contract configuration for `chain_id` or if signing fails.
+pub async fn sign_order<S: Signer>(
+    signer: &S,
+    SignableOrder {
+        order,
+        order_type,
+        post_only,
+    }: SignableOrder,
+    chain_id: ChainId,
+    neg_risk: bool,
+    owner: ApiKey,
+) -> Result<SignedOrder> {
+    let signature = signer
+        .sign_hash(&order.eip712_signing_hash(&order_domain(chain_id, neg_risk)?))
+        .await?;
+
+    Ok(SignedOrder {
+        order,
+        signature,
+        order_type,
+        owner,
+        post_only,
+    })
+}
+
+/// Returns the EIP-712 domain of the exchange contract that settles orders on `chain_id`.
+pub(crate) fn order_domain(chain_id: ChainId, neg_risk: bool) -> Result<Eip712Domain> {
+    let exchange_contract = contract_config(chain_id, neg_risk)
+        .ok_or(Error::missing_contract_config(chain_id, neg_risk))?
+        .exchange;
+
+    Ok(Eip712Domain {
+        name: ORDER_NAME,
+        version: VERSION,
+        chain_id: Some(U256::from(chain_id)),
+        verifying_contract: Some(exchange_contract),
+        ..Eip712Domain::default()
+    })
+}
+
 /// Removes trailing zeros, truncates to [`USDC_DECIMALS`] decimal places, and quanitizes as an
 /// integer.
 fn to_fixed_u128(d: Decimal) -> u128 {
@@ -481,28 +991,7 @@ fn to_fixed_u128(d: Decimal) -> u128 {
 
 /// Mask the salt to be <= 2^53 - 1, as the backend parses as an IEEE 754.
 fn to_ieee_754_int(salt: u64) -> u64 {
-    salt & ((1 << 53) - 1)
-}
-
-#[must_use]
-#[expect(
-    clippy::float_arithmetic,
-    reason = "We are not concerned with precision for the seed"
-)]
-#[expect(
-    clippy::cast_possible_truncation,
-    reason = "We are not concerned with truncation for a seed"
-)]
-#[expect(clippy::cast_sign_loss, reason = "We only need positive integers")]
-pub(crate) fn generate_seed() -> u64 {
-    let now = SystemTime::now()
-        .duration_since(UNIX_EPOCH)
-        .expect("time went backwards");
-
-    let seconds = now.as_secs_f64();
-    let rand = rand::rng().random::<f64>();
-
-    (seconds * rand).round() as u64
+    salt & SALT_MASK
 }
 
 #[cfg(test)]
@@ -535,4 +1024,133 @@ mod tests {
 
         assert!(masked_salt < (1 << 53));
     }
+
+    fn market() -> MarketParameters {
+        MarketParameters::builder()
+            .tick_size(TickSize::Hundredth)
+            .fee_rate_bps(100)
+            .build()
+    }
+
+    #[test]
+    fn offline_limit_order_should_use_market_parameters() {
+        let signer = Address::repeat_byte(1);
+        let order = OfflineOrderBuilder::limit(signer, market())
+            .token_id(U256::from(1))
+            .side(Side::Buy)
+            .price(dec!(0.34))
+            .size(dec!(100))
+            .salt_generator(|| 7)
+            .build()
+            .unwrap();
+
+        assert_eq!(order.order.maker, signer);
+        assert_eq!(order.order.signer, signer);
+        assert_eq!(order.order.makerAmount, U256::from(34_000_000));
+        assert_eq!(order.order.takerAmount, U256::from(100_000_000));
+        assert_eq!(order.order.feeRateBps, U256::from(100));
+        assert_eq!(order.order.salt, U256::from(7));
+        assert_eq!(order.post_only, Some(false));
+
+        let err = OfflineOrderBuilder::limit(signer, market())
+            .token_id(U256::from(1))
+            .side(Side::Buy)
+            .price(dec!(0.345))
+            .size(dec!(100))
+            .build()
+            .unwrap_err();
+        assert!(err.to_string().contains("has 3 decimal places"));
+    }
+
+    #[test]
+    fn offline_market_order_should_require_price() {
+        let builder = || {
+            OfflineOrderBuilder::market(Address::ZERO, market())
+                .token_id(U256::from(1))
+                .side(Side::Buy)
+                .amount(Amount::usdc(dec!(100)).unwrap())
+        };
+
+        let err = builder().build().unwrap_err();
+        assert!(
+            err.to_string()
+                .contains("Unable to build Order due to missing price")
+        );
+
+        let order = builder().price(dec!(0.5)).build().unwrap();
+        assert_eq!(order.order.makerAmount, U256::from(100_000_000));
+        assert_eq!(order.order.takerAmount, U256::from(200_000_000));
+        assert_eq!(order.order_type, OrderType::FAK);
+    }
+
+    #[tokio::test]
+    async fn sign_order_should_recover_signer() {
+        let signer = alloy::signers::local::PrivateKeySigner::random();
+        let order = OfflineOrderBuilder::limit(signer.address(), market())
+            .token_id(U256::from(1))
+            .side(Side::Sell)
+            .price(dec!(0.5))
+            .size(dec!(10))
+            .build()
+            .unwrap();
+
+        let hash = order.order_hash(crate::POLYGON, true).unwrap();
+        let signed = sign_order(&signer, order, crate::POLYGON, true, ApiKey::nil())
+            .await
+            .unwrap();
+
+        let recovered = signed
+            .signature
+            .recover_address_from_prehash(&hash)
+            .unwrap();
+
+        assert_eq!(recovered, signer.address());
+        assert_eq!(signed.order_hash(crate::POLYGON, true).unwrap(), hash);
+        assert_ne!(
+            signed.order_hash(crate::POLYGON, false).unwrap(),
+            hash,
+            "neg risk orders settle on a different exchange"
+        );
+    }
+
+    #[tokio::test]
+    async fn verify_should_check_signer_and_maker() {
+        let signer = alloy::signers::local::PrivateKeySigner::random();
+        let order = |signature_type, funder| {
+            OfflineOrderBuilder::limit(signer.address(), market())
+                .token_id(U256::from(1))
+                .side(Side::Buy)
+                .price(dec!(0.5))
+                .size(dec!(10))
+                .signature_type(signature_type)
+                .funder(funder)
+                .build()
+                .unwrap()
+        };
+        let sign = |order| sign_order(&signer, order, crate::POLYGON, false, ApiKey::nil());
+
+        let eoa = sign(order(SignatureType::Eoa, signer.address()))
+            .await
+            .unwrap();
+        eoa.verify(crate::POLYGON, false).unwrap();
+
+        let proxy = crate::derive_proxy_wallet(signer.address(), crate::POLYGON).unwrap();
+        let signed = sign(order(SignatureType::Proxy, proxy)).await.unwrap();
+        signed.verify(crate::POLYGON, false).unwrap();
+
+        let mut tampered = sign(order(SignatureType::Eoa, signer.address()))
+            .await
+            .unwrap();
+        tampered.order.takerAmount += U256::from(1);
+        let err = tampered.verify(crate::POLYGON, false).unwrap_err();
+        assert!(err.to_string().contains("was not produced by its signer"));
+
+        let foreign = sign(order(SignatureType::GnosisSafe, proxy)).await.unwrap();
+        let err = foreign.verify(crate::POLYGON, false).unwrap_err();
+        assert!(err.to_string().contains("does not belong to signer"));
+
+        // An order signed for the standard exchange does not verify for the neg risk one
+        let err = eoa.verify(crate::POLYGON, true).unwrap_err();
+        assert!(err.to_string().contains("for the neg risk exchange"));
+    }
 }
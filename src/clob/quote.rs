@@ -0,0 +1,268 @@
+//! Two-sided quoting on binary markets through complementary YES and NO tokens.
+//!
+//! A binary market has two outcome tokens whose prices sum to one: buying NO at `1 - p` takes
+//! the same position as selling YES at `p`, and selling NO at `1 - p` the same as buying YES at
+//! `p`. The exchange matches both expressions against the same book, by minting or merging
+//! complete sets, in standard and neg risk markets alike.
+//!
+//! A [`BinaryQuote`] states a bid and an ask on the YES outcome. [`Client::binary_quote`]
+//! resolves the market's tokens and expresses each side in the way that ties up the least
+//! capital given the current [`Holdings`]:
+//!
+//! - The bid sells NO held in inventory, and buys YES for the rest.
+//! - The ask sells YES held in inventory, and buys NO for the rest, since YES that is not held
+//!   cannot be sold.
+//!
+//! Inventory is only used when it covers at least the market's minimum order size, and a
+//! remainder below the minimum order size is not quoted.
+//!
+//! [`Client::binary_quote`]: crate::clob::Client::binary_quote
+
+#![expect(
+    clippy::module_name_repetitions,
+    reason = "Quote types are named for what they describe, not the module they live in"
+)]
+
+use alloy::primitives::U256;
+use bon::Builder;
+
+use crate::Result;
+use crate::clob::order_builder::LOT_SIZE_SCALE;
+use crate::clob::types::response::{MarketResponse, Token};
+use crate::clob::types::{Side, SignableOrder};
+use crate::error::Error;
+use crate::types::Decimal;
+
+/// One side of a [`BinaryQuote`]: a price and size on the YES outcome.
+#[non_exhaustive]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct QuoteLevel {
+    /// Price of the YES outcome.
+    pub price: Decimal,
+    /// Number of shares.
+    pub size: Decimal,
+}
+
+impl QuoteLevel {
+    /// Creates a level quoting `size` shares at `price`.
+    #[must_use]
+    pub const fn new(price: Decimal, size: Decimal) -> Self {
+        Self { price, size }
+    }
+}
+
+/// A bid and an ask on the YES outcome of a binary market. Either side may be left out.
+#[non_exhaustive]
+#[derive(Clone, Copy, Debug, Default, Builder, PartialEq, Eq)]
+pub struct BinaryQuote {
+    /// Price and size at which to buy YES.
+    pub bid: Option<QuoteLevel>,
+    /// Price and size at which to sell YES.
+    pub ask: Option<QuoteLevel>,
+}
+
+/// Shares of each outcome currently held, for example from a
+/// [`Ledger`](crate::clob::ledger::Ledger).
+#[non_exhaustive]
+#[derive(Clone, Copy, Debug, Default, Builder, PartialEq, Eq)]
+pub struct Holdings {
+    /// Shares of the YES outcome.
+    #[builder(default)]
+    pub yes: Decimal,
+    /// Shares of the NO outcome.
+    #[builder(default)]
+    pub no: Decimal,
+}
+
+/// An outcome of a binary market.
+#[non_exhaustive]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, strum_macros::Display)]
+#[strum(serialize_all = "UPPERCASE")]
+pub enum Outcome {
+    Yes,
+    No,
+}
+
+/// An order expressing part of a [`BinaryQuote`].
+#[non_exhaustive]
+#[derive(Clone, Debug, PartialEq)]
+pub struct QuoteLeg {
+    /// The side of the quote this leg expresses: [`Side::Buy`] for the bid and [`Side::Sell`]
+    /// for the ask.
+    pub quote_side: Side,
+    /// Outcome traded by this leg.
+    pub outcome: Outcome,
+    /// Token traded by this leg.
+    pub token_id: U256,
+    /// Side of the order on `token_id`.
+    pub side: Side,
+    /// Price of the order on `token_id`.
+    pub price: Decimal,
+    /// Size of the order.
+    pub size: Decimal,
+    /// The order to sign and post.
+    pub order: SignableOrder,
+}
+
+/// Returns the YES and NO tokens of a binary market.
+///
+/// Outcomes named `Yes` and `No` are matched regardless of case. Markets with other outcome
+/// names, such as the two teams of a game, treat the first token as YES.
+///
+/// # Errors
+///
+/// Returns a validation error if the market does not have exactly two tokens.
+pub fn binary_tokens(market: &MarketResponse) -> Result<(&Token, &Token)> {
+    let [first, second] = market.tokens.as_slice() else {
+        return Err(Error::validation(format!(
+            "Market {} is not binary: expected 2 tokens, found {}",
+            market.market_slug,
+            market.tokens.len()
+        )));
+    };
+
+    if first.outcome.eq_ignore_ascii_case("no") || second.outcome.eq_ignore_ascii_case("yes") {
+        Ok((second, first))
+    } else {
+        Ok((first, second))
+    }
+}
+
+/// A leg of a [`BinaryQuote`] before its order is built.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(crate) struct PlannedLeg {
+    pub(crate) quote_side: Side,
+    pub(crate) outcome: Outcome,
+    pub(crate) side: Side,
+    pub(crate) price: Decimal,
+    pub(crate) size: Decimal,
+}
+
+/// Splits each side of `quote` between inventory and capital, as described in the
+/// [module](self) documentation.
+pub(crate) fn plan(
+    quote: &BinaryQuote,
+    holdings: &Holdings,
+    minimum_order_size: Decimal,
+) -> Vec<PlannedLeg> {
+    let mut legs = Vec::new();
+
+    // The bid sells NO in inventory and buys YES for the rest, the ask sells YES in inventory
+    // and buys NO for the rest
+    let sides = [
+        (Side::Buy, quote.bid, holdings.no, Outcome::No, Outcome::Yes),
+        (
+            Side::Sell,
+            quote.ask,
+            holdings.yes,
+            Outcome::Yes,
+            Outcome::No,
+        ),
+    ];
+    for (quote_side, level, held, inventory, capital) in sides {
+        let Some(QuoteLevel { price, size }) = level else {
+            continue;
+        };
+        let size = size.trunc_with_scale(LOT_SIZE_SCALE);
+
+        let from_inventory = size.min(held.trunc_with_scale(LOT_SIZE_SCALE));
+        let from_inventory = if from_inventory >= minimum_order_size {
+            from_inventory
+        } else {
+            Decimal::ZERO
+        };
+        let from_capital = size - from_inventory;
+
+        let leg = |outcome, side, size| PlannedLeg {
+            quote_side,
+            outcome,
+            side,
+            price: match outcome {
+                Outcome::Yes => price,
+                Outcome::No => Decimal::ONE - price,
+            },
+            size,
+        };
+
+        if from_inventory > Decimal::ZERO {
+            legs.push(leg(inventory, Side::Sell, from_inventory));
+        }
+        if from_capital > Decimal::ZERO && from_capital >= minimum_order_size {
+            legs.push(leg(capital, Side::Buy, from_capital));
+        }
+    }
+
+    legs
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::dec;
+
+    const fn leg(
+        quote_side: Side,
+        outcome: Outcome,
+        side: Side,
+        price: Decimal,
+        size: Decimal,
+    ) -> PlannedLeg {
+        PlannedLeg {
+            quote_side,
+            outcome,
+            side,
+            price,
+            size,
+        }
+    }
+
+    fn quote() -> BinaryQuote {
+        BinaryQuote::builder()
+            .bid(QuoteLevel::new(dec!(0.40), dec!(100)))
+            .ask(QuoteLevel::new(dec!(0.45), dec!(100)))
+            .build()
+    }
+
+    #[test]
+    fn plan_without_holdings_should_buy_both_outcomes() {
+        let legs = plan(&quote(), &Holdings::default(), dec!(5));
+
+        assert_eq!(
+            legs,
+            vec![
+                leg(Side::Buy, Outcome::Yes, Side::Buy, dec!(0.40), dec!(100)),
+                leg(Side::Sell, Outcome::No, Side::Buy, dec!(0.55), dec!(100)),
+            ]
+        );
+    }
+
+    #[test]
+    fn plan_should_sell_inventory_first() {
+        let holdings = Holdings::builder().yes(dec!(30)).no(dec!(250)).build();
+        let legs = plan(&quote(), &holdings, dec!(5));
+
+        assert_eq!(
+            legs,
+            vec![
+                leg(Side::Buy, Outcome::No, Side::Sell, dec!(0.60), dec!(100)),
+                leg(Side::Sell, Outcome::Yes, Side::Sell, dec!(0.45), dec!(30)),
+                leg(Side::Sell, Outcome::No, Side::Buy, dec!(0.55), dec!(70)),
+            ]
+        );
+    }
+
+    #[test]
+    fn plan_should_respect_minimum_order_size() {
+        // Two YES shares are too few to sell, and the three YES shares left of the bid too few to buy
+        let holdings = Holdings::builder().yes(dec!(2)).no(dec!(97)).build();
+        let legs = plan(&quote(), &holdings, dec!(5));
+
+        assert_eq!(
+            legs,
+            vec![
+                leg(Side::Buy, Outcome::No, Side::Sell, dec!(0.60), dec!(97)),
+                leg(Side::Sell, Outcome::No, Side::Buy, dec!(0.55), dec!(100)),
+            ]
+        );
+    }
+}
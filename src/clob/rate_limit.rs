@@ -0,0 +1,304 @@
+//! Client-side rate limiting for CLOB requests.
+//!
+//! The CLOB enforces request quotas per group of endpoints and throttles clients that exceed
+//! them. Attaching [`RateLimits`] to a client's [`Config`](crate::clob::Config) keeps a token
+//! bucket per [`EndpointGroup`], so that bursts of order placements or cancellations are spread
+//! out on the client instead of being rejected by the server.
+//!
+//! Each bucket holds up to [`Limit::requests`] tokens and refills continuously over
+//! [`Limit::per`]. When a bucket is empty, the request either waits for the next token or fails
+//! immediately with a [`RateLimitExceeded`] error of kind
+//! [`Kind::RateLimited`], depending on the configured [`Mode`].
+
+#![expect(
+    clippy::module_name_repetitions,
+    reason = "Rate limit types intentionally mirror the module name for clarity"
+)]
+
+use std::error::Error as StdError;
+use std::fmt;
+use std::sync::{Mutex, PoisonError};
+use std::time::Duration;
+
+use bon::Builder;
+use tokio::time::Instant;
+
+use crate::Result;
+use crate::error::{Error, Kind};
+
+/// A group of CLOB endpoints sharing a request quota.
+#[non_exhaustive]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, strum_macros::Display)]
+#[strum(serialize_all = "snake_case")]
+pub enum EndpointGroup {
+    /// Order placement: `POST /order` and `POST /orders`.
+    Orders,
+    /// Order cancellation: `DELETE /order`, `DELETE /orders`, `DELETE /cancel-all` and
+    /// `DELETE /cancel-market-orders`.
+    Cancels,
+    /// Public order book data: books, prices, midpoints, spreads and last trade prices.
+    Book,
+    /// API key management: creating, deriving, listing and deleting API keys.
+    Auth,
+}
+
+/// What to do when a request would exceed its group's quota.
+#[non_exhaustive]
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Mode {
+    /// Wait until the bucket has capacity, then send the request.
+    #[default]
+    Wait,
+    /// Fail the request immediately with a [`RateLimitExceeded`] error.
+    FailFast,
+}
+
+/// A request quota: at most `requests` requests within any window of `per`.
+#[non_exhaustive]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Limit {
+    /// Maximum number of requests, which is also the largest burst allowed.
+    pub requests: u32,
+    /// Length of the window over which the bucket fully refills.
+    pub per: Duration,
+}
+
+impl Limit {
+    /// Creates a quota of `requests` requests per `per`.
+    #[must_use]
+    pub const fn new(requests: u32, per: Duration) -> Self {
+        Self { requests, per }
+    }
+}
+
+/// Per-group request quotas for a [`Client`](crate::clob::Client).
+///
+/// The defaults match the burst limits published for the Kuest CLOB. Endpoints outside these
+/// groups are not limited on the client.
+///
+/// # Example
+///
+/// ```
+/// use std::time::Duration;
+///
+/// use kuest_client_sdk::clob::rate_limit::{Limit, Mode, RateLimits};
+///
+/// let limits = RateLimits::builder()
+///     .orders(Limit::new(50, Duration::from_secs(1)))
+///     .mode(Mode::FailFast)
+///     .build();
+/// ```
+#[non_exhaustive]
+#[derive(Clone, Copy, Debug, Builder, PartialEq, Eq)]
+pub struct RateLimits {
+    /// Quota for [`EndpointGroup::Orders`]. Defaults to 3500 requests per 10 seconds.
+    #[builder(default = Limit::new(3500, Duration::from_secs(10)))]
+    pub orders: Limit,
+    /// Quota for [`EndpointGroup::Cancels`]. Defaults to 3000 requests per 10 seconds.
+    #[builder(default = Limit::new(3000, Duration::from_secs(10)))]
+    pub cancels: Limit,
+    /// Quota for [`EndpointGroup::Book`]. Defaults to 1500 requests per 10 seconds.
+    #[builder(default = Limit::new(1500, Duration::from_secs(10)))]
+    pub book: Limit,
+    /// Quota for [`EndpointGroup::Auth`]. Defaults to 100 requests per 10 seconds.
+    #[builder(default = Limit::new(100, Duration::from_secs(10)))]
+    pub auth: Limit,
+    /// Behaviour when a quota is exhausted. Defaults to [`Mode::Wait`].
+    #[builder(default)]
+    pub mode: Mode,
+}
+
+impl Default for RateLimits {
+    fn default() -> Self {
+        Self::builder().build()
+    }
+}
+
+/// Error returned in [`Mode::FailFast`] when a request would exceed its group's quota.
+#[non_exhaustive]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RateLimitExceeded {
+    /// The endpoint group whose quota is exhausted.
+    pub group: EndpointGroup,
+    /// How long until the group has capacity for another request.
+    pub retry_after: Duration,
+}
+
+impl fmt::Display for RateLimitExceeded {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "rate limit exceeded for {} requests, retry after {:?}",
+            self.group, self.retry_after
+        )
+    }
+}
+
+impl StdError for RateLimitExceeded {}
+
+impl From<RateLimitExceeded> for Error {
+    fn from(err: RateLimitExceeded) -> Self {
+        Error::with_source(Kind::RateLimited, err)
+    }
+}
+
+/// Token buckets for each [`EndpointGroup`], shared by every request made through a client.
+#[derive(Debug)]
+pub(crate) struct RateLimiter {
+    mode: Mode,
+    orders: Bucket,
+    cancels: Bucket,
+    book: Bucket,
+    auth: Bucket,
+}
+
+impl RateLimiter {
+    pub(crate) fn new(limits: &RateLimits) -> Self {
+        Self {
+            mode: limits.mode,
+            orders: Bucket::new(limits.orders),
+            cancels: Bucket::new(limits.cancels),
+            book: Bucket::new(limits.book),
+            auth: Bucket::new(limits.auth),
+        }
+    }
+
+    /// Takes a token from `group`'s bucket, waiting for one to become available in
+    /// [`Mode::Wait`].
+    pub(crate) async fn acquire(&self, group: EndpointGroup) -> Result<()> {
+        let bucket = match group {
+            EndpointGroup::Orders => &self.orders,
+            EndpointGroup::Cancels => &self.cancels,
+            EndpointGroup::Book => &self.book,
+            EndpointGroup::Auth => &self.auth,
+        };
+
+        let Some(delay) = bucket.reserve(Instant::now(), self.mode) else {
+            return Ok(());
+        };
+
+        match self.mode {
+            Mode::FailFast => Err(RateLimitExceeded {
+                group,
+                retry_after: delay,
+            }
+            .into()),
+            Mode::Wait => {
+                #[cfg(feature = "tracing")]
+                tracing::debug!(%group, ?delay, "Waiting for rate limit capacity");
+
+                tokio::time::sleep(delay).await;
+                Ok(())
+            }
+        }
+    }
+}
+
+/// A token bucket tracked as the time at which it will be full again.
+///
+/// Every request pushes that time forward by one emission interval (`per / requests`). A
+/// request is allowed as long as the bucket would be full again within `per`, which admits
+/// bursts of up to `requests` requests and then one request per emission interval.
+#[derive(Debug)]
+struct Bucket {
+    interval: Duration,
+    tolerance: Duration,
+    full_at: Mutex<Option<Instant>>,
+}
+
+impl Bucket {
+    fn new(limit: Limit) -> Self {
+        let interval = limit.per / limit.requests.max(1);
+
+        Self {
+            interval,
+            tolerance: limit.per.saturating_sub(interval),
+            full_at: Mutex::new(None),
+        }
+    }
+
+    /// Reserves a token at `now`, returning how long the caller has to wait before using it.
+    ///
+    /// In [`Mode::FailFast`] no token is taken when the caller would have to wait.
+    fn reserve(&self, now: Instant, mode: Mode) -> Option<Duration> {
+        let mut full_at = self.full_at.lock().unwrap_or_else(PoisonError::into_inner);
+
+        let start = full_at.map_or(now, |full_at| full_at.max(now));
+        let next = start + self.interval;
+        let delay = (start - now).saturating_sub(self.tolerance);
+
+        if delay.is_zero() || mode == Mode::Wait {
+            *full_at = Some(next);
+        }
+
+        (!delay.is_zero()).then_some(delay)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn bucket_should_allow_burst_then_space_requests() {
+        let bucket = Bucket::new(Limit::new(3, Duration::from_secs(3)));
+        let now = Instant::now();
+
+        for _ in 0..3 {
+            assert_eq!(bucket.reserve(now, Mode::Wait), None);
+        }
+        assert_eq!(
+            bucket.reserve(now, Mode::Wait),
+            Some(Duration::from_secs(1))
+        );
+        assert_eq!(
+            bucket.reserve(now, Mode::Wait),
+            Some(Duration::from_secs(2))
+        );
+
+        // Waiting callers hold their reservations, so capacity returns one interval later
+        assert_eq!(
+            bucket.reserve(now + Duration::from_secs(2), Mode::Wait),
+            Some(Duration::from_secs(1))
+        );
+    }
+
+    #[test]
+    fn bucket_should_refill_over_time() {
+        let bucket = Bucket::new(Limit::new(2, Duration::from_secs(2)));
+        let now = Instant::now();
+
+        assert_eq!(bucket.reserve(now, Mode::FailFast), None);
+        assert_eq!(bucket.reserve(now, Mode::FailFast), None);
+        assert_eq!(
+            bucket.reserve(now, Mode::FailFast),
+            Some(Duration::from_secs(1))
+        );
+
+        let later = now + Duration::from_secs(1);
+        assert_eq!(bucket.reserve(later, Mode::FailFast), None);
+        assert_eq!(
+            bucket.reserve(later, Mode::FailFast),
+            Some(Duration::from_secs(1))
+        );
+    }
+
+    #[tokio::test]
+    async fn fail_fast_should_return_rate_limited_error() {
+        let limits = RateLimits::builder()
+            .cancels(Limit::new(1, Duration::from_secs(60)))
+            .mode(Mode::FailFast)
+            .build();
+        let limiter = RateLimiter::new(&limits);
+
+        limiter.acquire(EndpointGroup::Cancels).await.unwrap();
+        limiter.acquire(EndpointGroup::Orders).await.unwrap();
+
+        let err = limiter.acquire(EndpointGroup::Cancels).await.unwrap_err();
+        assert_eq!(err.kind(), Kind::RateLimited);
+
+        let exceeded = err.downcast_ref::<RateLimitExceeded>().unwrap();
+        assert_eq!(exceeded.group, EndpointGroup::Cancels);
+        assert!(exceeded.retry_after > Duration::from_secs(59));
+    }
+}
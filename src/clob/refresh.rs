@@ -0,0 +1,223 @@
+//! Automatic refresh of [`OrderType::GTD`] quotes.
+//!
+//! **Feature flag:** `refresh`
+//!
+//! [`Client::refresh_gtd`] spawns a background task that keeps a set of [`RefreshOrder`]s on the
+//! book as GTD orders. Each round signs the orders with an expiration of `lifetime` past the
+//! server time and the [`GTD_SECURITY_BUFFER`], so that they rest on the book for `lifetime`,
+//! posts them in batches of at most [`MAX_BATCH_SIZE`] orders and sleeps until `lead` before they
+//! leave the book. The next round cancels the orders still live before posting replacements, so
+//! that the quotes never rest on the book twice.
+//!
+//! When a round fails, the error is logged with the `tracing` feature and the round is retried
+//! after `retry_delay`. The task runs until [`RefreshHandle::stop`] is called or the handle is
+//! dropped, after which the last orders posted stay on the book until they expire.
+//!
+//! [`OrderType::GTD`]: crate::clob::types::OrderType::GTD
+//! [`GTD_SECURITY_BUFFER`]: crate::clob::order_builder::GTD_SECURITY_BUFFER
+
+#![expect(
+    clippy::module_name_repetitions,
+    reason = "`RefreshOrder` and `RefreshHandle` read better than `refresh::Order` at call sites"
+)]
+
+use std::time::Duration;
+
+use alloy::primitives::U256;
+use alloy::signers::Signer;
+use bon::Builder;
+use tokio::sync::watch;
+use tokio::task::JoinHandle;
+use tokio::time;
+use tokio_util::sync::CancellationToken;
+
+use crate::Result;
+use crate::auth::Kind as AuthKind;
+use crate::auth::state::Authenticated;
+use crate::clob::Client;
+use crate::clob::client::MAX_BATCH_SIZE;
+use crate::clob::order_builder::gtd_expiration;
+use crate::clob::types::{OrderType, Side};
+use crate::error::Error;
+use crate::types::Decimal;
+
+/// A limit order kept on the book by [`Client::refresh_gtd`].
+#[non_exhaustive]
+#[derive(Clone, Debug, Builder, PartialEq, Eq)]
+pub struct RefreshOrder {
+    pub token_id: U256,
+    pub side: Side,
+    pub price: Decimal,
+    pub size: Decimal,
+    /// Sets the `postOnly` flag of the order.
+    pub post_only: Option<bool>,
+}
+
+/// Timing of the rounds run by [`Client::refresh_gtd`].
+#[non_exhaustive]
+#[derive(Clone, Copy, Debug, Builder, PartialEq, Eq)]
+pub struct RefreshSchedule {
+    /// How long each round of orders rests on the book. The orders are signed with an
+    /// expiration that adds the
+    /// [`GTD_SECURITY_BUFFER`](crate::clob::order_builder::GTD_SECURITY_BUFFER) to it.
+    pub lifetime: Duration,
+    /// How long before the orders leave the book the next round starts, which must be shorter
+    /// than `lifetime`. Defaults to ten (10) seconds.
+    #[builder(default = Duration::from_secs(10))]
+    pub lead: Duration,
+    /// How long to wait before retrying a failed round. Defaults to five (5) seconds.
+    #[builder(default = Duration::from_secs(5))]
+    pub retry_delay: Duration,
+}
+
+/// Handle to the background task spawned by [`Client::refresh_gtd`]. Dropping the handle stops
+/// the task.
+#[derive(Debug)]
+pub struct RefreshHandle {
+    token: CancellationToken,
+    task: JoinHandle<()>,
+    order_ids: watch::Receiver<Vec<String>>,
+}
+
+impl RefreshHandle {
+    pub(crate) fn spawn<K: AuthKind, S: Signer + Send + Sync + 'static>(
+        client: Client<Authenticated<K>>,
+        signer: S,
+        orders: Vec<RefreshOrder>,
+        schedule: RefreshSchedule,
+    ) -> Result<Self> {
+        if orders.is_empty() {
+            return Err(Error::validation(
+                "Unable to refresh GTD orders: no orders to refresh",
+            ));
+        }
+
+        if schedule.lifetime.is_zero() {
+            return Err(Error::validation(
+                "Unable to refresh GTD orders: lifetime must be greater than zero",
+            ));
+        }
+
+        if schedule.lead >= schedule.lifetime {
+            return Err(Error::validation(format!(
+                "Unable to refresh GTD orders: lead {:?} must be shorter than the lifetime of {:?}",
+                schedule.lead, schedule.lifetime
+            )));
+        }
+
+        let token = CancellationToken::new();
+        let (tx, order_ids) = watch::channel(Vec::new());
+
+        let token_clone = token.clone();
+        let task = tokio::task::spawn(async move {
+            loop {
+                let delay = match refresh(&client, &signer, &orders, schedule.lifetime, &tx).await {
+                    Ok(()) => schedule.lifetime.saturating_sub(schedule.lead),
+                    Err(e) => {
+                        #[cfg(feature = "tracing")]
+                        tracing::error!("Unable to refresh GTD orders: {e:?}");
+                        #[cfg(not(feature = "tracing"))]
+                        let _: &_ = &e;
+                        schedule.retry_delay
+                    }
+                };
+
+                tokio::select! {
+                    () = token_clone.cancelled() => {
+                        #[cfg(feature = "tracing")]
+                        tracing::debug!("GTD refresh cancellation requested, terminating...");
+                        break
+                    },
+                    () = time::sleep(delay) => {}
+                }
+            }
+        });
+
+        Ok(Self {
+            token,
+            task,
+            order_ids,
+        })
+    }
+
+    /// Returns the IDs of the orders posted by the latest round.
+    #[must_use]
+    pub fn order_ids(&self) -> Vec<String> {
+        self.order_ids.borrow().clone()
+    }
+
+    /// Returns `true` while the background task is running.
+    #[must_use]
+    pub fn is_active(&self) -> bool {
+        !self.task.is_finished()
+    }
+
+    /// Stops the background task, waiting for a round in progress to finish, and returns the IDs
+    /// of the orders it left on the book.
+    pub async fn stop(mut self) -> Vec<String> {
+        self.token.cancel();
+        _ = (&mut self.task).await;
+        self.order_ids()
+    }
+}
+
+impl Drop for RefreshHandle {
+    fn drop(&mut self) {
+        self.token.cancel();
+    }
+}
+
+/// Runs a single round: cancels the orders of the previous round, then signs and posts `orders`
+/// with a fresh expiration.
+async fn refresh<K: AuthKind, S: Signer>(
+    client: &Client<Authenticated<K>>,
+    signer: &S,
+    orders: &[RefreshOrder],
+    lifetime: Duration,
+    order_ids: &watch::Sender<Vec<String>>,
+) -> Result<()> {
+    let previous = order_ids.borrow().clone();
+    if !previous.is_empty() {
+        let previous: Vec<&str> = previous.iter().map(String::as_str).collect();
+        client.cancel_orders(&previous).await?;
+        order_ids.send_replace(Vec::new());
+    }
+
+    let expiration = gtd_expiration(client.server_time().await?, lifetime)?;
+
+    let mut signed = Vec::with_capacity(orders.len());
+    for order in orders {
+        let mut builder = client
+            .limit_order()
+            .token_id(order.token_id)
+            .side(order.side)
+            .price(order.price)
+            .size(order.size)
+            .order_type(OrderType::GTD)
+            .expiration(expiration);
+        if let Some(post_only) = order.post_only {
+            builder = builder.post_only(post_only);
+        }
+
+        signed.push(client.sign(signer, builder.build().await?).await?);
+    }
+
+    while !signed.is_empty() {
+        let rest = signed.split_off(signed.len().min(MAX_BATCH_SIZE));
+        let responses = client.post_orders(signed).await?;
+        order_ids.send_modify(|ids| {
+            ids.extend(
+                responses
+                    .into_iter()
+                    .filter(|response| response.success)
+                    .map(|response| response.order_id),
+            );
+        });
+        signed = rest;
+    }
+
+    #[cfg(feature = "tracing")]
+    tracing::debug!(%expiration, orders = orders.len(), "GTD orders refreshed");
+
+    Ok(())
+}
@@ -0,0 +1,877 @@
+//! Pre-trade risk checks for orders posted through the CLOB client.
+//!
+//! Attaching a [`RiskGuard`] to a client's [`Config`](crate::clob::Config) makes
+//! [`Client::post_order`](crate::clob::Client::post_order) and
+//! [`Client::post_orders`](crate::clob::Client::post_orders) check every signed order against
+//! the guard's [`RiskLimits`] before it is sent. An order that breaks a limit is not sent, and the
+//! call fails with a [`RiskViolation`] error of kind
+//! [`Kind::Validation`].
+//!
+//! The guard keeps track of the open orders it has let through, so that it can limit the
+//! notional resting in each token and market. Orders leave the guard's books when they are
+//! cancelled through the client, or when [`RiskGuard::release`] is called for them, for example
+//! once an [`OrderTracker`](crate::clob::ws::OrderTracker) reports them as cancelled. Fills
+//! release exposure as they happen: report them with [`RiskGuard::on_fill`], or feed the user
+//! channel's trade messages to `RiskGuard::on_trade` (with the `ws` feature).
+//!
+//! [`RiskGuard`] is a cheap handle to shared state: keep a clone to inspect the guard, feed it
+//! midpoints, or engage its kill switch while the client is in use.
+
+#![expect(
+    clippy::module_name_repetitions,
+    reason = "Risk types intentionally mirror the module name for clarity"
+)]
+
+#[cfg(feature = "ws")]
+use std::collections::HashSet;
+use std::collections::{HashMap, VecDeque};
+use std::error::Error as StdError;
+use std::fmt;
+use std::hash::Hash;
+use std::sync::{Arc, Mutex, MutexGuard, PoisonError};
+use std::time::Duration;
+
+use bon::Builder;
+use tokio::time::Instant;
+
+use crate::Result;
+use crate::clob::order_builder::USDC_DECIMALS;
+use crate::clob::types::response::PostOrderResponse;
+use crate::clob::types::{OrderStatusType, Side, SignedOrder};
+#[cfg(feature = "ws")]
+use crate::clob::ws::types::response::{TradeMessage, TradeMessageStatus};
+use crate::error::{Error, Kind};
+use crate::types::{B256, Decimal, U256};
+
+/// Limits enforced by a [`RiskGuard`]. Every limit is optional, and unset limits are not
+/// checked.
+///
+/// # Example
+///
+/// ```
+/// use kuest_client_sdk::clob::risk::{RiskGuard, RiskLimits};
+/// use kuest_client_sdk::types::dec;
+///
+/// let guard = RiskGuard::new(
+///     RiskLimits::builder()
+///         .max_order_notional(dec!(500))
+///         .max_token_exposure(dec!(2_000))
+///         .max_orders_per_second(10)
+///         .price_band(dec!(0.05))
+///         .build(),
+/// );
+/// ```
+#[non_exhaustive]
+#[derive(Clone, Copy, Debug, Default, Builder, PartialEq, Eq)]
+pub struct RiskLimits {
+    /// Maximum notional of a single order, in USDC.
+    pub max_order_notional: Option<Decimal>,
+    /// Maximum notional of open orders in a single token, in USDC.
+    pub max_token_exposure: Option<Decimal>,
+    /// Maximum notional of open orders across the tokens of a single market, in USDC. Only
+    /// tokens whose market was registered with [`RiskGuard::set_market`] count towards it.
+    pub max_market_exposure: Option<Decimal>,
+    /// Maximum number of orders sent within any one second window.
+    pub max_orders_per_second: Option<u32>,
+    /// Maximum distance between an order's price and the token's current midpoint.
+    pub price_band: Option<Decimal>,
+}
+
+/// A limit that an order would break.
+#[non_exhaustive]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum RiskViolation {
+    /// The kill switch is engaged and no new orders are accepted.
+    Halted,
+    /// The order's notional is above [`RiskLimits::max_order_notional`].
+    OrderNotional {
+        token_id: U256,
+        notional: Decimal,
+        limit: Decimal,
+    },
+    /// The order would take the token's open notional above [`RiskLimits::max_token_exposure`].
+    TokenExposure {
+        token_id: U256,
+        exposure: Decimal,
+        limit: Decimal,
+    },
+    /// The order would take the market's open notional above
+    /// [`RiskLimits::max_market_exposure`].
+    MarketExposure {
+        market: B256,
+        exposure: Decimal,
+        limit: Decimal,
+    },
+    /// Sending the orders would exceed [`RiskLimits::max_orders_per_second`].
+    OrderRate { limit: u32 },
+    /// The order's price is further than [`RiskLimits::price_band`] from the midpoint.
+    PriceBand {
+        token_id: U256,
+        price: Decimal,
+        midpoint: Decimal,
+        band: Decimal,
+    },
+    /// The order's amounts cannot be interpreted.
+    InvalidOrder { token_id: U256 },
+}
+
+impl fmt::Display for RiskViolation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            RiskViolation::Halted => write!(f, "kill switch is engaged"),
+            RiskViolation::OrderNotional {
+                token_id,
+                notional,
+                limit,
+            } => write!(
+                f,
+                "order notional {notional} for token {token_id} exceeds limit {limit}"
+            ),
+            RiskViolation::TokenExposure {
+                token_id,
+                exposure,
+                limit,
+            } => write!(
+                f,
+                "open exposure {exposure} for token {token_id} would exceed limit {limit}"
+            ),
+            RiskViolation::MarketExposure {
+                market,
+                exposure,
+                limit,
+            } => write!(
+                f,
+                "open exposure {exposure} for market {market} would exceed limit {limit}"
+            ),
+            RiskViolation::OrderRate { limit } => {
+                write!(f, "more than {limit} orders per second")
+            }
+            RiskViolation::PriceBand {
+                token_id,
+                price,
+                midpoint,
+                band,
+            } => write!(
+                f,
+                "price {price} for token {token_id} is more than {band} away from midpoint {midpoint}"
+            ),
+            RiskViolation::InvalidOrder { token_id } => {
+                write!(f, "order amounts for token {token_id} are out of range")
+            }
+        }
+    }
+}
+
+impl StdError for RiskViolation {}
+
+impl From<RiskViolation> for Error {
+    fn from(violation: RiskViolation) -> Self {
+        Error::with_source(Kind::Validation, violation)
+    }
+}
+
+/// A snapshot of a [`RiskGuard`]'s state.
+#[non_exhaustive]
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct RiskState {
+    /// Whether the kill switch is engaged.
+    pub halted: bool,
+    /// Number of open orders the guard is tracking.
+    pub open_orders: usize,
+    /// Open notional per token.
+    pub token_exposure: HashMap<U256, Decimal>,
+    /// Open notional per registered market.
+    pub market_exposure: HashMap<B256, Decimal>,
+    /// Number of orders sent within the last second.
+    pub recent_orders: usize,
+}
+
+/// Pre-trade risk checks shared by every clone of the guard and the clients it is attached to.
+#[derive(Clone, Debug, Default)]
+pub struct RiskGuard {
+    limits: RiskLimits,
+    state: Arc<Mutex<GuardState>>,
+}
+
+impl RiskGuard {
+    /// Creates a guard enforcing `limits`.
+    #[must_use]
+    pub fn new(limits: RiskLimits) -> Self {
+        Self {
+            limits,
+            state: Arc::default(),
+        }
+    }
+
+    /// The limits this guard enforces.
+    #[must_use]
+    pub const fn limits(&self) -> &RiskLimits {
+        &self.limits
+    }
+
+    /// Engages the kill switch, rejecting every new order until [`RiskGuard::resume`] is called.
+    ///
+    /// This does not cancel resting orders; use
+    /// [`Client::kill_switch`](crate::clob::Client::kill_switch) to do both.
+    pub fn halt(&self) {
+        self.lock().halted = true;
+    }
+
+    /// Disengages the kill switch.
+    pub fn resume(&self) {
+        self.lock().halted = false;
+    }
+
+    /// Whether the kill switch is engaged.
+    #[must_use]
+    pub fn is_halted(&self) -> bool {
+        self.lock().halted
+    }
+
+    /// Records the current midpoint of a token, used for the price band check.
+    ///
+    /// When no midpoint has been recorded for a token, the client fetches it before checking the
+    /// price band.
+    pub fn set_midpoint(&self, token_id: U256, midpoint: Decimal) {
+        self.lock().midpoints.insert(token_id, midpoint);
+    }
+
+    /// The last midpoint recorded for a token.
+    #[must_use]
+    pub fn midpoint(&self, token_id: U256) -> Option<Decimal> {
+        self.lock().midpoints.get(&token_id).copied()
+    }
+
+    /// Registers the tokens of a market, so that orders in them count towards
+    /// [`RiskLimits::max_market_exposure`].
+    pub fn set_market<I: IntoIterator<Item = U256>>(&self, market: B256, token_ids: I) {
+        let mut state = self.lock();
+        for token_id in token_ids {
+            state.markets.insert(token_id, market);
+        }
+    }
+
+    /// Stops tracking an open order, releasing its exposure. Returns `false` if the order was
+    /// not tracked.
+    #[must_use]
+    pub fn release(&self, order_id: &str) -> bool {
+        let mut state = self.lock();
+        match state.orders.remove(order_id) {
+            Some(exposure) => {
+                state.remove(&exposure);
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Releases the exposure of `size` filled tokens of an open order, and stops tracking the
+    /// order once it is completely filled. Returns `false` if the order was not tracked.
+    #[must_use]
+    pub fn on_fill(&self, order_id: &str, size: Decimal) -> bool {
+        self.lock().fill(order_id, size)
+    }
+
+    /// Releases the exposure filled by a user channel trade, for the taker order and every maker
+    /// order of the trade the guard is tracking. Returns `false` if the trade filled none of
+    /// them.
+    ///
+    /// The user channel sends a message for every status a trade goes through, so fills are
+    /// applied once per trade, and failed trades are ignored.
+    #[cfg(feature = "ws")]
+    #[must_use]
+    pub fn on_trade(&self, message: &TradeMessage) -> bool {
+        let mut state = self.lock();
+        if message.status == TradeMessageStatus::Failed || state.trades.contains(&message.id) {
+            return false;
+        }
+
+        let mut filled = false;
+        if let Some(order_id) = &message.taker_order_id {
+            filled |= state.fill(order_id, message.size);
+        }
+        for maker in &message.maker_orders {
+            filled |= state.fill(&maker.order_id, maker.matched_amount);
+        }
+
+        if filled {
+            state.trades.insert(message.id.clone());
+        }
+        filled
+    }
+
+    /// Stops tracking every open order.
+    pub fn release_all(&self) {
+        let mut state = self.lock();
+        let orders: Vec<_> = state.orders.drain().map(|(_, exposure)| exposure).collect();
+        for exposure in &orders {
+            state.remove(exposure);
+        }
+    }
+
+    /// A snapshot of the guard's current state.
+    #[must_use]
+    pub fn state(&self) -> RiskState {
+        let mut state = self.lock();
+        state.prune(Instant::now());
+
+        RiskState {
+            halted: state.halted,
+            open_orders: state.orders.len(),
+            token_exposure: state.token_exposure.clone(),
+            market_exposure: state.market_exposure.clone(),
+            recent_orders: state.recent.len(),
+        }
+    }
+
+    /// Checks `orders` against the limits and reserves their exposure.
+    ///
+    /// `midpoints` supplies midpoints fetched for tokens the guard has no midpoint for.
+    pub(crate) fn reserve(
+        &self,
+        orders: &[SignedOrder],
+        midpoints: &HashMap<U256, Decimal>,
+    ) -> Result<Reservation> {
+        let mut state = self.lock();
+        if state.halted {
+            return Err(RiskViolation::Halted.into());
+        }
+
+        let now = Instant::now();
+        state.prune(now);
+        if let Some(limit) = self.limits.max_orders_per_second
+            && state.recent.len().saturating_add(orders.len()) > limit as usize
+        {
+            return Err(RiskViolation::OrderRate { limit }.into());
+        }
+
+        let mut exposures = Vec::with_capacity(orders.len());
+        let mut token_exposure = HashMap::<U256, Decimal>::new();
+        let mut market_exposure = HashMap::<B256, Decimal>::new();
+
+        for order in orders {
+            let token_id = order.order.tokenId;
+            let (notional, size, price) =
+                amounts(order).ok_or(RiskViolation::InvalidOrder { token_id })?;
+            let market = state.markets.get(&token_id).copied();
+
+            if let Some(limit) = self.limits.max_order_notional
+                && notional > limit
+            {
+                return Err(RiskViolation::OrderNotional {
+                    token_id,
+                    notional,
+                    limit,
+                }
+                .into());
+            }
+
+            if let Some(band) = self.limits.price_band
+                && let Some(midpoint) = state
+                    .midpoints
+                    .get(&token_id)
+                    .or_else(|| midpoints.get(&token_id))
+                    .copied()
+                && (price - midpoint).abs() > band
+            {
+                return Err(RiskViolation::PriceBand {
+                    token_id,
+                    price,
+                    midpoint,
+                    band,
+                }
+                .into());
+            }
+
+            if let Some(limit) = self.limits.max_token_exposure {
+                let pending = token_exposure.entry(token_id).or_default();
+                *pending += notional;
+                let exposure = state
+                    .token_exposure
+                    .get(&token_id)
+                    .copied()
+                    .unwrap_or_default()
+                    + *pending;
+                if exposure > limit {
+                    return Err(RiskViolation::TokenExposure {
+                        token_id,
+                        exposure,
+                        limit,
+                    }
+                    .into());
+                }
+            }
+
+            if let Some(limit) = self.limits.max_market_exposure
+                && let Some(market) = market
+            {
+                let pending = market_exposure.entry(market).or_default();
+                *pending += notional;
+                let exposure = state
+                    .market_exposure
+                    .get(&market)
+                    .copied()
+                    .unwrap_or_default()
+                    + *pending;
+                if exposure > limit {
+                    return Err(RiskViolation::MarketExposure {
+                        market,
+                        exposure,
+                        limit,
+                    }
+                    .into());
+                }
+            }
+
+            exposures.push(Exposure {
+                token_id,
+                market,
+                notional,
+                size,
+            });
+        }
+
+        for exposure in &exposures {
+            state.add(exposure);
+            state.recent.push_back(now);
+        }
+
+        Ok(Reservation {
+            state: Arc::clone(&self.state),
+            exposures,
+        })
+    }
+
+    /// Turns reserved exposure into open orders for the orders the server accepted, and releases
+    /// the rest.
+    pub(crate) fn confirm(&self, mut reservation: Reservation, responses: &[PostOrderResponse]) {
+        let mut state = self.lock();
+        let mut responses = responses.iter();
+
+        for exposure in reservation.exposures.drain(..) {
+            match responses.next() {
+                Some(response)
+                    if response.success && response.status != OrderStatusType::Matched =>
+                {
+                    if let Some(previous) = state.orders.insert(response.order_id.clone(), exposure)
+                    {
+                        state.remove(&previous);
+                    }
+                }
+                _ => state.remove(&exposure),
+            }
+        }
+    }
+
+    fn lock(&self) -> MutexGuard<'_, GuardState> {
+        self.state.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+}
+
+/// Exposure reserved by [`RiskGuard::reserve`] for orders that are being sent.
+///
+/// Exposure that is not confirmed with [`RiskGuard::confirm`] is released when the reservation
+/// is dropped, so orders that fail or are abandoned before reaching the server do not keep it
+/// reserved.
+#[derive(Debug)]
+pub(crate) struct Reservation {
+    state: Arc<Mutex<GuardState>>,
+    exposures: Vec<Exposure>,
+}
+
+impl Drop for Reservation {
+    fn drop(&mut self) {
+        if self.exposures.is_empty() {
+            return;
+        }
+
+        let mut state = self.state.lock().unwrap_or_else(PoisonError::into_inner);
+        for exposure in self.exposures.drain(..) {
+            state.remove(&exposure);
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug)]
+struct Exposure {
+    token_id: U256,
+    market: Option<B256>,
+    notional: Decimal,
+    /// Number of tokens the notional is for.
+    size: Decimal,
+}
+
+#[derive(Debug, Default)]
+struct GuardState {
+    halted: bool,
+    orders: HashMap<String, Exposure>,
+    /// Trades whose fills have been released.
+    #[cfg(feature = "ws")]
+    trades: HashSet<String>,
+    token_exposure: HashMap<U256, Decimal>,
+    market_exposure: HashMap<B256, Decimal>,
+    markets: HashMap<U256, B256>,
+    midpoints: HashMap<U256, Decimal>,
+    recent: VecDeque<Instant>,
+}
+
+impl GuardState {
+    fn add(&mut self, exposure: &Exposure) {
+        *self.token_exposure.entry(exposure.token_id).or_default() += exposure.notional;
+        if let Some(market) = exposure.market {
+            *self.market_exposure.entry(market).or_default() += exposure.notional;
+        }
+    }
+
+    fn remove(&mut self, exposure: &Exposure) {
+        subtract(
+            &mut self.token_exposure,
+            &exposure.token_id,
+            exposure.notional,
+        );
+        if let Some(market) = exposure.market {
+            subtract(&mut self.market_exposure, &market, exposure.notional);
+        }
+    }
+
+    /// Releases the notional of `size` filled tokens of an open order.
+    fn fill(&mut self, order_id: &str, size: Decimal) -> bool {
+        let Some(exposure) = self.orders.get_mut(order_id) else {
+            return false;
+        };
+
+        let mut filled = *exposure;
+        if size < exposure.size {
+            filled.notional = exposure.notional * size / exposure.size;
+            filled.size = size;
+            exposure.notional -= filled.notional;
+            exposure.size -= size;
+        } else {
+            self.orders.remove(order_id);
+        }
+        self.remove(&filled);
+        true
+    }
+
+    /// Forgets orders sent more than a second before `now`.
+    fn prune(&mut self, now: Instant) {
+        while self
+            .recent
+            .front()
+            .is_some_and(|sent| now.duration_since(*sent) >= Duration::from_secs(1))
+        {
+            self.recent.pop_front();
+        }
+    }
+}
+
+fn subtract<K: Eq + Hash>(map: &mut HashMap<K, Decimal>, key: &K, amount: Decimal) {
+    if let Some(value) = map.get_mut(key) {
+        *value -= amount;
+        if *value <= Decimal::ZERO {
+            map.remove(key);
+        }
+    }
+}
+
+/// The USDC notional, the number of tokens and the price of a signed order.
+fn amounts(order: &SignedOrder) -> Option<(Decimal, Decimal, Decimal)> {
+    let order = &order.order;
+    let maker = amount(order.makerAmount)?;
+    let taker = amount(order.takerAmount)?;
+
+    // Buy orders give USDC for tokens and sell orders give tokens for USDC
+    let (usdc, tokens) = match Side::try_from(order.side).ok()? {
+        Side::Buy => (maker, taker),
+        Side::Sell => (taker, maker),
+        Side::Unknown => return None,
+    };
+
+    let price = usdc.checked_div(tokens)?;
+    Some((usdc, tokens, price))
+}
+
+fn amount(value: U256) -> Option<Decimal> {
+    let value = i128::try_from(u128::try_from(value).ok()?).ok()?;
+    Decimal::try_from_i128_with_scale(value, USDC_DECIMALS).ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use rust_decimal_macros::dec;
+
+    use super::*;
+    use crate::clob::types::{Order, OrderType};
+    use crate::types::Signature;
+
+    fn order(token_id: u64, side: Side, price: Decimal, size: Decimal) -> SignedOrder {
+        let to_units = |value: Decimal| {
+            U256::from((value * dec!(1_000_000)).trunc().mantissa().unsigned_abs())
+        };
+        let (maker, taker) = match side {
+            Side::Buy => (price * size, size),
+            _ => (size, price * size),
+        };
+
+        SignedOrder::builder()
+            .order(Order {
+                tokenId: U256::from(token_id),
+                makerAmount: to_units(maker),
+                takerAmount: to_units(taker),
+                side: side as u8,
+                ..Order::default()
+            })
+            .signature(Signature::new(U256::ZERO, U256::ZERO, false))
+            .order_type(OrderType::GTC)
+            .owner(uuid::Uuid::nil())
+            .build()
+    }
+
+    fn accepted(order_id: &str) -> PostOrderResponse {
+        PostOrderResponse::builder()
+            .making_amount(Decimal::ZERO)
+            .taking_amount(Decimal::ZERO)
+            .order_id(order_id)
+            .status(OrderStatusType::Live)
+            .success(true)
+            .build()
+    }
+
+    fn violation(err: &Error) -> RiskViolation {
+        assert_eq!(err.kind(), Kind::Validation);
+        *err.downcast_ref::<RiskViolation>().unwrap()
+    }
+
+    #[test]
+    fn amounts_should_read_both_sides() {
+        assert_eq!(
+            amounts(&order(1, Side::Buy, dec!(0.4), dec!(10))),
+            Some((dec!(4), dec!(10), dec!(0.4)))
+        );
+        assert_eq!(
+            amounts(&order(1, Side::Sell, dec!(0.25), dec!(8))),
+            Some((dec!(2), dec!(8), dec!(0.25)))
+        );
+    }
+
+    #[test]
+    fn reserve_should_enforce_order_notional_and_price_band() {
+        let guard = RiskGuard::new(
+            RiskLimits::builder()
+                .max_order_notional(dec!(5))
+                .price_band(dec!(0.05))
+                .build(),
+        );
+        guard.set_midpoint(U256::from(1), dec!(0.5));
+        let midpoints = HashMap::new();
+
+        let err = guard
+            .reserve(&[order(1, Side::Buy, dec!(0.5), dec!(20))], &midpoints)
+            .unwrap_err();
+        assert!(matches!(
+            violation(&err),
+            RiskViolation::OrderNotional { notional, .. } if notional == dec!(10)
+        ));
+
+        let err = guard
+            .reserve(&[order(1, Side::Buy, dec!(0.6), dec!(5))], &midpoints)
+            .unwrap_err();
+        assert!(matches!(
+            violation(&err),
+            RiskViolation::PriceBand { midpoint, .. } if midpoint == dec!(0.5)
+        ));
+
+        guard
+            .reserve(&[order(1, Side::Buy, dec!(0.55), dec!(5))], &midpoints)
+            .unwrap();
+    }
+
+    #[test]
+    fn exposure_should_accumulate_until_released() {
+        let guard = RiskGuard::new(
+            RiskLimits::builder()
+                .max_token_exposure(dec!(10))
+                .max_market_exposure(dec!(15))
+                .build(),
+        );
+        guard.set_market(B256::ZERO, [U256::from(1), U256::from(2)]);
+        let midpoints = HashMap::new();
+
+        let reservation = guard
+            .reserve(&[order(1, Side::Buy, dec!(0.5), dec!(16))], &midpoints)
+            .unwrap();
+        guard.confirm(reservation, &[accepted("a")]);
+
+        let err = guard
+            .reserve(&[order(1, Side::Buy, dec!(0.5), dec!(6))], &midpoints)
+            .unwrap_err();
+        assert!(matches!(
+            violation(&err),
+            RiskViolation::TokenExposure { exposure, .. } if exposure == dec!(11)
+        ));
+
+        let err = guard
+            .reserve(
+                &[
+                    order(2, Side::Buy, dec!(0.5), dec!(10)),
+                    order(2, Side::Buy, dec!(0.5), dec!(10)),
+                ],
+                &midpoints,
+            )
+            .unwrap_err();
+        assert!(matches!(
+            violation(&err),
+            RiskViolation::MarketExposure { exposure, .. } if exposure == dec!(18)
+        ));
+
+        let state = guard.state();
+        assert_eq!(state.open_orders, 1);
+        assert_eq!(state.token_exposure.get(&U256::from(1)), Some(&dec!(8)));
+        assert_eq!(state.market_exposure.get(&B256::ZERO), Some(&dec!(8)));
+
+        assert!(guard.release("a"));
+        assert!(!guard.release("a"));
+        assert!(guard.state().token_exposure.is_empty());
+    }
+
+    #[test]
+    fn fills_should_free_capacity() {
+        let guard = RiskGuard::new(RiskLimits::builder().max_token_exposure(dec!(10)).build());
+        let midpoints = HashMap::new();
+
+        let reservation = guard
+            .reserve(&[order(1, Side::Buy, dec!(0.5), dec!(20))], &midpoints)
+            .unwrap();
+        guard.confirm(reservation, &[accepted("a")]);
+        let next = [order(1, Side::Buy, dec!(0.5), dec!(8))];
+        guard.reserve(&next, &midpoints).unwrap_err();
+
+        assert!(guard.on_fill("a", dec!(6)));
+        assert_eq!(
+            guard.state().token_exposure.get(&U256::from(1)),
+            Some(&dec!(7))
+        );
+        guard.reserve(&next, &midpoints).unwrap_err();
+
+        assert!(guard.on_fill("a", dec!(14)));
+        let state = guard.state();
+        assert_eq!(state.open_orders, 0);
+        assert!(state.token_exposure.is_empty());
+        assert!(!guard.on_fill("a", dec!(1)));
+        guard.reserve(&next, &midpoints).unwrap();
+    }
+
+    #[cfg(feature = "ws")]
+    #[test]
+    fn trade_messages_should_free_capacity_once() {
+        use crate::auth::ApiKey;
+        use crate::clob::ws::types::response::MakerOrder;
+
+        let guard = RiskGuard::new(RiskLimits::builder().max_token_exposure(dec!(20)).build());
+        let reservation = guard
+            .reserve(
+                &[
+                    order(1, Side::Buy, dec!(0.5), dec!(20)),
+                    order(1, Side::Sell, dec!(0.5), dec!(10)),
+                ],
+                &HashMap::new(),
+            )
+            .unwrap();
+        guard.confirm(reservation, &[accepted("taker"), accepted("maker")]);
+
+        let trade = |status| {
+            TradeMessage::builder()
+                .id("trade".to_owned())
+                .market(B256::ZERO)
+                .asset_id(U256::from(1))
+                .side(Side::Buy)
+                .size(dec!(4))
+                .price(dec!(0.5))
+                .status(status)
+                .taker_order_id("taker".to_owned())
+                .maker_orders(vec![
+                    MakerOrder::builder()
+                        .asset_id(U256::from(1))
+                        .matched_amount(dec!(10))
+                        .order_id("maker".to_owned())
+                        .outcome("Yes".to_owned())
+                        .owner(ApiKey::nil())
+                        .price(dec!(0.5))
+                        .build(),
+                ])
+                .build()
+        };
+
+        assert!(!guard.on_trade(&trade(TradeMessageStatus::Failed)));
+        assert!(guard.on_trade(&trade(TradeMessageStatus::Matched)));
+        assert!(!guard.on_trade(&trade(TradeMessageStatus::Confirmed)));
+
+        let state = guard.state();
+        assert_eq!(state.open_orders, 1);
+        assert_eq!(state.token_exposure.get(&U256::from(1)), Some(&dec!(8)));
+    }
+
+    #[test]
+    fn rejected_orders_should_release_exposure() {
+        let guard = RiskGuard::new(RiskLimits::builder().max_token_exposure(dec!(10)).build());
+
+        let reservation = guard
+            .reserve(&[order(1, Side::Buy, dec!(0.5), dec!(10))], &HashMap::new())
+            .unwrap();
+        let mut rejected = accepted("a");
+        rejected.success = false;
+        guard.confirm(reservation, &[rejected]);
+
+        assert_eq!(
+            guard.state(),
+            RiskState {
+                recent_orders: 1,
+                ..RiskState::default()
+            }
+        );
+    }
+
+    #[test]
+    fn dropped_reservation_should_release_exposure() {
+        let guard = RiskGuard::new(RiskLimits::builder().max_token_exposure(dec!(10)).build());
+        let orders = [order(1, Side::Buy, dec!(0.5), dec!(20))];
+
+        let reservation = guard.reserve(&orders, &HashMap::new()).unwrap();
+        assert_eq!(
+            guard.state().token_exposure.get(&U256::from(1)),
+            Some(&dec!(10))
+        );
+
+        drop(reservation);
+        assert!(guard.state().token_exposure.is_empty());
+        guard.reserve(&orders, &HashMap::new()).unwrap();
+    }
+
+    #[test]
+    fn reserve_should_limit_orders_per_second_and_halt() {
+        let guard = RiskGuard::new(RiskLimits::builder().max_orders_per_second(2).build());
+        let midpoints = HashMap::new();
+        let orders = [
+            order(1, Side::Buy, dec!(0.5), dec!(1)),
+            order(1, Side::Buy, dec!(0.5), dec!(1)),
+        ];
+
+        drop(guard.reserve(&orders, &midpoints).unwrap());
+        let err = guard.reserve(&orders[..1], &midpoints).unwrap_err();
+        assert_eq!(violation(&err), RiskViolation::OrderRate { limit: 2 });
+
+        // Orders sent over a second ago no longer count
+        for sent in &mut guard.lock().recent {
+            *sent -= Duration::from_secs(1);
+        }
+        drop(guard.reserve(&orders[..1], &midpoints).unwrap());
+
+        guard.halt();
+        let err = guard.reserve(&orders[..1], &midpoints).unwrap_err();
+        assert_eq!(violation(&err), RiskViolation::Halted);
+        assert!(guard.state().halted);
+
+        guard.resume();
+        assert!(!guard.is_halted());
+    }
+}
@@ -0,0 +1,152 @@
+//! Salt generation for orders.
+//!
+//! Every order carries a salt so that otherwise identical orders hash, and therefore sign, to
+//! different values. The CLOB parses the salt as an IEEE 754 double, so only its lowest 53 bits
+//! are kept, see [`SALT_MASK`].
+//!
+//! A [`SaltGenerator`] is set on the
+//! [`AuthenticationBuilder`](crate::clob::client::AuthenticationBuilder) or on a single order
+//! builder. Three strategies are provided, and any `Fn() -> u64` closure or function pointer can
+//! be used as well:
+//!
+//! - [`UuidSalt`], the default, derives each salt from a random UUID.
+//! - [`CounterSalt`] counts up from a starting value, so salts never repeat within a process.
+//! - [`SeededSalt`] draws salts from a seeded RNG, so that a sequence of orders can be
+//!   reproduced.
+
+#![expect(
+    clippy::module_name_repetitions,
+    reason = "Strategies are named after the salt they generate, e.g. `CounterSalt`"
+)]
+
+use std::fmt;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Mutex, PoisonError};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use rand::rngs::StdRng;
+use rand::{Rng as _, SeedableRng as _};
+use uuid::Uuid;
+
+/// Mask keeping the lowest 53 bits of a salt, the largest integer an IEEE 754 double represents
+/// exactly.
+pub const SALT_MASK: u64 = (1 << 53) - 1;
+
+/// Generates the salt of each order. Only the lowest 53 bits of a salt are used.
+pub trait SaltGenerator: Send + Sync {
+    /// Returns the salt for the next order.
+    fn next_salt(&self) -> u64;
+}
+
+impl<F: Fn() -> u64 + Send + Sync> SaltGenerator for F {
+    fn next_salt(&self) -> u64 {
+        self()
+    }
+}
+
+impl fmt::Debug for dyn SaltGenerator {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("SaltGenerator")
+    }
+}
+
+/// Derives each salt from the random bits of a version 4 UUID. This is the default strategy.
+#[non_exhaustive]
+#[derive(Clone, Copy, Debug, Default)]
+pub struct UuidSalt;
+
+impl SaltGenerator for UuidSalt {
+    fn next_salt(&self) -> u64 {
+        // The lowest 62 bits of a version 4 UUID are random
+        Uuid::new_v4().as_u64_pair().1 & SALT_MASK
+    }
+}
+
+/// Counts up from a starting value, wrapping within [`SALT_MASK`].
+///
+/// The default starts at the current Unix time in microseconds, so that a restarted process does
+/// not reuse the salts of the previous one unless it signed more than a million orders a second.
+#[derive(Debug)]
+pub struct CounterSalt(AtomicU64);
+
+impl CounterSalt {
+    /// Creates a counter whose first salt is `start`.
+    #[must_use]
+    pub const fn new(start: u64) -> Self {
+        Self(AtomicU64::new(start))
+    }
+}
+
+impl Default for CounterSalt {
+    fn default() -> Self {
+        let now = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .expect("time went backwards");
+
+        Self::new(u64::try_from(now.as_micros()).unwrap_or(u64::MAX) & SALT_MASK)
+    }
+}
+
+impl SaltGenerator for CounterSalt {
+    fn next_salt(&self) -> u64 {
+        self.0.fetch_add(1, Ordering::Relaxed) & SALT_MASK
+    }
+}
+
+/// Draws salts from an RNG seeded with a fixed value, producing the same sequence of salts for
+/// the same seed.
+#[derive(Debug)]
+pub struct SeededSalt(Mutex<StdRng>);
+
+impl SeededSalt {
+    /// Creates a generator seeded with `seed`.
+    #[must_use]
+    pub fn new(seed: u64) -> Self {
+        Self(Mutex::new(StdRng::seed_from_u64(seed)))
+    }
+}
+
+impl SaltGenerator for SeededSalt {
+    fn next_salt(&self) -> u64 {
+        self.0
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .random::<u64>()
+            & SALT_MASK
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn counter_should_count_up_within_mask() {
+        let counter = CounterSalt::new(SALT_MASK);
+
+        assert_eq!(counter.next_salt(), SALT_MASK);
+        assert_eq!(counter.next_salt(), 0);
+        assert_eq!(counter.next_salt(), 1);
+    }
+
+    #[test]
+    fn seeded_should_be_reproducible() {
+        let first = SeededSalt::new(42);
+        let second = SeededSalt::new(42);
+
+        for _ in 0..10 {
+            let salt = first.next_salt();
+            assert_eq!(salt, second.next_salt());
+            assert!(salt <= SALT_MASK);
+        }
+    }
+
+    #[test]
+    fn closures_and_uuids_should_generate_salts() {
+        let generators: [&dyn SaltGenerator; 2] = [&|| 7, &UuidSalt];
+
+        assert_eq!(generators[0].next_salt(), 7);
+        assert!(generators[1].next_salt() <= SALT_MASK);
+        assert_ne!(generators[1].next_salt(), generators[1].next_salt());
+    }
+}
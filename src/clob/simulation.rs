@@ -0,0 +1,257 @@
+//! Order book simulation for market orders.
+//!
+//! [`simulate_market_order`] walks an [`OrderBookSummaryResponse`] the way the matching engine
+//! fills a market order: from the best opposing price outwards, one level at a time, until the
+//! requested [`Amount`] is exhausted. The resulting [`MarketSimulation`] reports every level
+//! touched, the volume-weighted average price, the worst price reached, the slippage against the
+//! book's midpoint and the estimated fees.
+//!
+//! [`OrderBuilder<Market, K>::build`](crate::clob::order_builder::OrderBuilder) prices market
+//! orders from the same simulation, so a preview and the order built from the same book always
+//! agree on the worst price.
+//!
+//! Fees are estimated per level as `fee_rate_bps / 10_000 * min(price, 1 - price) * size`, in
+//! USDC, matching the [`ledger`](crate::clob::ledger).
+
+#![expect(
+    clippy::module_name_repetitions,
+    reason = "`MarketSimulation` reads better than `simulation::Market` at call sites"
+)]
+
+use crate::Result;
+use crate::clob::ledger::fee;
+use crate::clob::types::response::{OrderBookSummaryResponse, OrderSummary};
+use crate::clob::types::{Amount, AmountInner, Side};
+use crate::error::Error;
+use crate::types::Decimal;
+
+/// The portion of a simulated market order filled at a single price level.
+#[non_exhaustive]
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct LevelFill {
+    /// Price of the level.
+    pub price: Decimal,
+    /// Number of shares filled at this level.
+    pub size: Decimal,
+    /// USDC exchanged at this level, `price * size`.
+    pub notional: Decimal,
+    /// Estimated fee for this level, in USDC.
+    pub fee: Decimal,
+}
+
+/// Outcome of walking an order book with a market order.
+#[non_exhaustive]
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct MarketSimulation {
+    /// Side of the simulated order.
+    pub side: Side,
+    /// Levels the order fills against, from the best price to the worst.
+    pub fills: Vec<LevelFill>,
+    /// Total number of shares filled.
+    pub filled_size: Decimal,
+    /// Total USDC exchanged.
+    pub filled_notional: Decimal,
+    /// Volume-weighted average fill price, or `None` if nothing fills.
+    pub average_price: Option<Decimal>,
+    /// Price of the last level the order reaches, or `None` if nothing fills. This is the price
+    /// a market order built from the same book is signed with.
+    pub worst_price: Option<Decimal>,
+    /// Midpoint of the best bid and best ask, or `None` if either side of the book is empty.
+    pub midpoint: Option<Decimal>,
+    /// Estimated fees across all fills, in USDC.
+    pub fee: Decimal,
+    /// Part of the requested amount that the book cannot fill, in the same unit as the
+    /// requested [`Amount`]. A FAK order leaves this unfilled, while a FOK order is rejected
+    /// unless it is zero.
+    pub unfilled: Decimal,
+}
+
+impl MarketSimulation {
+    /// Returns `true` if the book can fill the whole amount, so that a FOK order would be
+    /// accepted.
+    #[must_use]
+    pub fn is_fully_filled(&self) -> bool {
+        self.unfilled.is_zero()
+    }
+
+    /// Difference between the average fill price and the midpoint, signed so that a positive
+    /// value is a cost: paying above the midpoint when buying, or receiving below it when
+    /// selling.
+    #[must_use]
+    pub fn slippage(&self) -> Option<Decimal> {
+        let average_price = self.average_price?;
+        let midpoint = self.midpoint?;
+
+        match self.side {
+            Side::Sell => Some(midpoint - average_price),
+            _ => Some(average_price - midpoint),
+        }
+    }
+}
+
+/// Simulates filling a market order for `amount` against `book`.
+///
+/// Buys fill against the asks and sells against the bids. Levels are expected in the order the
+/// CLOB returns them, with the best price last. Buys may be sized in USDC or shares, while sells
+/// must be sized in shares.
+///
+/// # Errors
+///
+/// Returns a validation error if `side` is not a buy or a sell, or if a sell is sized in USDC.
+pub fn simulate_market_order(
+    book: &OrderBookSummaryResponse,
+    side: Side,
+    amount: Amount,
+    fee_rate_bps: u32,
+) -> Result<MarketSimulation> {
+    let levels = match (side, amount.0) {
+        (Side::Buy, _) => &book.asks,
+        (Side::Sell, AmountInner::Shares(_)) => &book.bids,
+        (Side::Sell, AmountInner::Usdc(_)) => {
+            return Err(Error::validation(
+                "Sell Orders must specify their `amount`s in shares",
+            ));
+        }
+        (side, _) => return Err(Error::validation(format!("Invalid side: {side}"))),
+    };
+
+    let fee_rate_bps = Decimal::from(fee_rate_bps);
+    let mut remaining = amount.as_inner();
+    let mut fills = Vec::new();
+
+    for level in levels.iter().rev() {
+        if remaining <= Decimal::ZERO {
+            break;
+        }
+
+        let (size, notional) = match amount.0 {
+            AmountInner::Usdc(_) => {
+                let notional = remaining.min(level.size * level.price);
+                remaining -= notional;
+                (notional / level.price, notional)
+            }
+            AmountInner::Shares(_) => {
+                let size = remaining.min(level.size);
+                remaining -= size;
+                (size, size * level.price)
+            }
+        };
+
+        fills.push(LevelFill {
+            price: level.price,
+            size,
+            notional,
+            fee: fee(fee_rate_bps, level.price, size),
+        });
+    }
+
+    let filled_size: Decimal = fills.iter().map(|fill| fill.size).sum();
+    let filled_notional: Decimal = fills.iter().map(|fill| fill.notional).sum();
+
+    Ok(MarketSimulation {
+        side,
+        average_price: (!filled_size.is_zero()).then(|| filled_notional / filled_size),
+        worst_price: fills.last().map(|fill| fill.price),
+        midpoint: midpoint(book),
+        fee: fills.iter().map(|fill| fill.fee).sum(),
+        unfilled: remaining.max(Decimal::ZERO),
+        filled_size,
+        filled_notional,
+        fills,
+    })
+}
+
+fn midpoint(book: &OrderBookSummaryResponse) -> Option<Decimal> {
+    let best = |levels: &[OrderSummary]| levels.last().map(|level| level.price);
+
+    Some((best(&book.bids)? + best(&book.asks)?) / Decimal::TWO)
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::Utc;
+
+    use super::*;
+    use crate::clob::types::TickSize;
+    use crate::types::{B256, U256, dec};
+
+    fn book() -> OrderBookSummaryResponse {
+        let level = |price, size| OrderSummary::builder().price(price).size(size).build();
+
+        OrderBookSummaryResponse::builder()
+            .market(B256::ZERO)
+            .asset_id(U256::from(1))
+            .timestamp(Utc::now())
+            .bids(vec![
+                level(dec!(0.40), dec!(100)),
+                level(dec!(0.45), dec!(50)),
+            ])
+            .asks(vec![
+                level(dec!(0.60), dec!(100)),
+                level(dec!(0.55), dec!(50)),
+            ])
+            .min_order_size(dec!(5))
+            .neg_risk(false)
+            .tick_size(TickSize::Hundredth)
+            .build()
+    }
+
+    #[test]
+    fn buy_should_walk_asks_from_best_price() -> Result<()> {
+        let simulation = simulate_market_order(&book(), Side::Buy, Amount::shares(dec!(80))?, 100)?;
+
+        assert_eq!(simulation.fills.len(), 2);
+        assert_eq!(simulation.fills[0].price, dec!(0.55));
+        assert_eq!(simulation.fills[0].size, dec!(50));
+        assert_eq!(simulation.fills[1].price, dec!(0.60));
+        assert_eq!(simulation.fills[1].size, dec!(30));
+        assert_eq!(simulation.filled_notional, dec!(45.5));
+        assert_eq!(simulation.worst_price, Some(dec!(0.60)));
+        assert_eq!(simulation.midpoint, Some(dec!(0.50)));
+        assert_eq!(
+            simulation.average_price.map(|price| price.round_dp(4)),
+            Some(dec!(0.5688))
+        );
+        assert_eq!(
+            simulation.slippage().map(|price| price.round_dp(4)),
+            Some(dec!(0.0688))
+        );
+        // 1% of min(p, 1 - p): 0.45 * 50 + 0.40 * 30 = 34.5
+        assert_eq!(simulation.fee, dec!(0.345));
+        assert!(simulation.is_fully_filled());
+
+        Ok(())
+    }
+
+    #[test]
+    fn usdc_buy_should_report_unfilled_amount() -> Result<()> {
+        let simulation = simulate_market_order(&book(), Side::Buy, Amount::usdc(dec!(100))?, 0)?;
+
+        // 27.5 USDC at 0.55 and 60 USDC at 0.60 leave 12.5 USDC unfilled
+        assert_eq!(simulation.filled_notional, dec!(87.5));
+        assert_eq!(simulation.filled_size, dec!(150));
+        assert_eq!(simulation.unfilled, dec!(12.5));
+        assert_eq!(simulation.worst_price, Some(dec!(0.60)));
+        assert!(!simulation.is_fully_filled());
+
+        Ok(())
+    }
+
+    #[test]
+    fn sell_should_walk_bids_and_require_shares() -> Result<()> {
+        let simulation = simulate_market_order(&book(), Side::Sell, Amount::shares(dec!(50))?, 0)?;
+
+        assert_eq!(simulation.fills.len(), 1);
+        assert_eq!(simulation.worst_price, Some(dec!(0.45)));
+        assert_eq!(simulation.slippage(), Some(dec!(0.05)));
+
+        let err =
+            simulate_market_order(&book(), Side::Sell, Amount::usdc(dec!(50))?, 0).unwrap_err();
+        assert!(
+            err.to_string()
+                .contains("must specify their `amount`s in shares")
+        );
+
+        Ok(())
+    }
+}
@@ -1,7 +1,8 @@
 use std::fmt;
 
 use alloy::core::sol;
-use alloy::primitives::{Signature, U256};
+use alloy::primitives::{B256, ChainId, Signature, U256};
+use alloy::sol_types::SolStruct as _;
 use bon::Builder;
 use rust_decimal_macros::dec;
 use serde::ser::{Error as _, SerializeStruct as _};
@@ -10,11 +11,12 @@ use serde_repr::Serialize_repr;
 use serde_with::{DisplayFromStr, serde_as};
 use strum_macros::Display;
 
-use crate::Result;
 use crate::auth::ApiKey;
-use crate::clob::order_builder::{LOT_SIZE_SCALE, USDC_DECIMALS};
+use crate::clob::describe::{OrderDescription, OrderIssue, OrderTerms, describe, exchange};
+use crate::clob::order_builder::{LOT_SIZE_SCALE, USDC_DECIMALS, order_domain};
 use crate::error::Error;
 use crate::types::Decimal;
+use crate::{Result, derive_proxy_wallet, derive_safe_wallet};
 
 pub mod request;
 pub mod response;
@@ -349,6 +351,106 @@ pub enum TraderSide {
     Unknown(String),
 }
 
+/// Reason given by the CLOB for rejecting an order or request.
+///
+/// Decoded from the message of a non-successful response (see
+/// [`Status::api_error`](crate::error::Status::api_error)) or from
+/// [`PostOrderResponse::error_msg`](response::PostOrderResponse::error_msg), so that callers can
+/// `match` on the reason rather than inspect the server's wording.
+#[non_exhaustive]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum ApiError {
+    /// The maker does not have enough balance or allowance to cover the order.
+    InsufficientBalance,
+    /// The order price is not a multiple of the market's tick size.
+    InvalidTickSize,
+    /// The order size is below the market's minimum order size.
+    OrderTooSmall,
+    /// A post-only order would have crossed the book and matched immediately.
+    CrossesBook,
+    /// The market is closed or no longer accepting orders.
+    MarketClosed,
+    /// The order nonce is lower than the maker's current nonce on the exchange.
+    InvalidNonce,
+    /// An identical order has already been submitted.
+    DuplicateOrder,
+    /// A fill-or-kill order could not be filled in full.
+    NotFullyFilled,
+    /// A fill-and-kill or market order found no resting orders to match against.
+    NoMatch,
+    /// The order does not exist, or was already filled or cancelled.
+    OrderNotFound,
+    /// Any other reason, holding the message as sent by the server.
+    Unknown(String),
+}
+
+impl ApiError {
+    /// Decodes a rejection reason from an error message sent by the server.
+    ///
+    /// The message can either be the plain error text, or a JSON body with the text in its
+    /// `error` or `errorMsg` field.
+    #[must_use]
+    pub fn from_message(message: &str) -> Self {
+        let message = serde_json::from_str::<serde_json::Value>(message)
+            .ok()
+            .and_then(|body| {
+                ["error", "errorMsg", "error_msg"]
+                    .into_iter()
+                    .find_map(|field| body.get(field)?.as_str().map(str::to_owned))
+            })
+            .unwrap_or_else(|| message.to_owned());
+        let lowercase = message.to_lowercase();
+        let contains = |pattern: &str| lowercase.contains(pattern);
+
+        // Only the server's own wording is matched, so that unrelated messages mentioning a
+        // balance, a nonce or a missing resource are left as `Unknown`
+        if contains("not enough balance / allowance") {
+            Self::InsufficientBalance
+        } else if contains("breaks minimum tick size rule") {
+            Self::InvalidTickSize
+        } else if contains("lower than the minimum") {
+            Self::OrderTooSmall
+        } else if contains("invalid post-only order") || contains("order crosses book") {
+            Self::CrossesBook
+        } else if contains("market is closed")
+            || contains("market closed")
+            || contains("not accepting orders")
+        {
+            Self::MarketClosed
+        } else if contains("invalid nonce") {
+            Self::InvalidNonce
+        } else if contains("duplicated") {
+            Self::DuplicateOrder
+        } else if contains("couldn't be fully filled") {
+            Self::NotFullyFilled
+        } else if contains("no orders found to match") {
+            Self::NoMatch
+        } else if contains("order can't be found") || contains("order not found or already") {
+            Self::OrderNotFound
+        } else {
+            Self::Unknown(message)
+        }
+    }
+}
+
+impl fmt::Display for ApiError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::InsufficientBalance => write!(f, "insufficient balance or allowance"),
+            Self::InvalidTickSize => write!(f, "price does not match the tick size"),
+            Self::OrderTooSmall => write!(f, "size is below the minimum order size"),
+            Self::CrossesBook => write!(f, "post-only order crosses the book"),
+            Self::MarketClosed => write!(f, "market is closed"),
+            Self::InvalidNonce => write!(f, "invalid nonce"),
+            Self::DuplicateOrder => write!(f, "duplicate order"),
+            Self::NotFullyFilled => write!(f, "fill-or-kill order could not be fully filled"),
+            Self::NoMatch => write!(f, "no orders to match against"),
+            Self::OrderNotFound => write!(f, "order not found, filled or already canceled"),
+            Self::Unknown(message) => write!(f, "{message}"),
+        }
+    }
+}
+
 /// Represents the maximum number of decimal places for an order's price field
 #[non_exhaustive]
 #[derive(Debug, Clone, Copy)]
@@ -455,7 +557,7 @@ sol! {
 }
 
 // CLOB expects salt as a JSON number. U256 as an integer will not fit as a JSON number. Since
-// we generated the salt as a u64 originally (see `salt_generator`), we can be very confident that
+// we generated the salt as a u64 originally (see `SaltGenerator`), we can be very confident that
 // we can invert the conversion to U256 and return a u64 when serializing.
 fn ser_salt<S: Serializer>(value: &U256, serializer: S) -> std::result::Result<S::Ok, S::Error> {
     let v: u64 = value
@@ -473,6 +575,44 @@ pub struct SignableOrder {
     pub post_only: Option<bool>,
 }
 
+impl Order {
+    /// Decodes the order's amounts into a price, size and notional, see [`OrderDescription`].
+    #[must_use]
+    pub fn describe(&self) -> OrderDescription {
+        describe(&OrderTerms::from(self))
+    }
+}
+
+impl fmt::Display for Order {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.describe().fmt(f)
+    }
+}
+
+impl SignableOrder {
+    /// Decodes the order, see [`Order::describe`].
+    #[must_use]
+    pub fn describe(&self) -> OrderDescription {
+        self.order.describe()
+    }
+
+    /// Returns the EIP-712 hash of the order, as signed for the exchange on `chain_id`.
+    ///
+    /// This is the order ID the CLOB assigns once the order is posted, so the order can be
+    /// tracked before [`Client::post_order`](crate::clob::Client::post_order) returns. Its
+    /// [`Display`](fmt::Display) form matches `order_id` on the
+    /// [`PostOrderResponse`](response::PostOrderResponse).
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if there is no exchange contract for `chain_id` and `neg_risk`.
+    pub fn order_hash(&self, chain_id: ChainId, neg_risk: bool) -> Result<B256> {
+        Ok(self
+            .order
+            .eip712_signing_hash(&order_domain(chain_id, neg_risk)?))
+    }
+}
+
 #[non_exhaustive]
 #[derive(Debug, Builder, PartialEq)]
 pub struct SignedOrder {
@@ -483,6 +623,115 @@ pub struct SignedOrder {
     pub post_only: Option<bool>,
 }
 
+impl SignedOrder {
+    /// Returns the EIP-712 hash of the order, which is also its order ID. See
+    /// [`SignableOrder::order_hash`].
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if there is no exchange contract for `chain_id` and `neg_risk`.
+    pub fn order_hash(&self, chain_id: ChainId, neg_risk: bool) -> Result<B256> {
+        Ok(self
+            .order
+            .eip712_signing_hash(&order_domain(chain_id, neg_risk)?))
+    }
+
+    /// Decodes the order, see [`Order::describe`], and names the [`Exchange`] its signature
+    /// recovers to the signer for. If there is none, [`OrderIssue::UnknownExchange`] is flagged.
+    ///
+    /// [`Exchange`]: crate::clob::describe::Exchange
+    /// [`OrderIssue::UnknownExchange`]: crate::clob::describe::OrderIssue::UnknownExchange
+    #[must_use]
+    pub fn describe(&self) -> OrderDescription {
+        let mut description = self.order.describe();
+        description.exchange = exchange(self);
+        if description.exchange.is_none() {
+            description.issues.push(OrderIssue::UnknownExchange);
+        }
+
+        description
+    }
+
+    /// Verifies the order's signature locally, without contacting the CLOB.
+    ///
+    /// The signature must recover to `order.signer` over the EIP-712 hash of the order, as signed
+    /// for the standard or, with `neg_risk`, the neg risk exchange on `chain_id`, so an order
+    /// signed for the other exchange is rejected just like [`Client::post_order`] would. The
+    /// maker must then match the order's [`SignatureType`]: the signer itself for
+    /// [`SignatureType::Eoa`], or the signer's proxy or Safe wallet, derived with
+    /// [`derive_proxy_wallet`] and [`derive_safe_wallet`], for [`SignatureType::Proxy`] and
+    /// [`SignatureType::GnosisSafe`].
+    ///
+    /// # Errors
+    ///
+    /// Returns a validation error if the signature was not produced by `order.signer` for the
+    /// exchange, if the maker does not belong to the signer, or if the signature type is unknown
+    /// or cannot be derived on `chain_id`.
+    ///
+    /// [`Client::post_order`]: crate::clob::Client::post_order
+    pub fn verify(&self, chain_id: ChainId, neg_risk: bool) -> Result<()> {
+        let signer = self.order.signer;
+        let hash = self.order_hash(chain_id, neg_risk)?;
+        let signed = self
+            .signature
+            .recover_address_from_prehash(&hash)
+            .is_ok_and(|recovered| recovered == signer);
+
+        if !signed {
+            let exchange = if neg_risk { "neg risk" } else { "standard" };
+            return Err(Error::validation(format!(
+                "Order signature was not produced by its signer {signer} for the {exchange} \
+                exchange on chain {chain_id}"
+            )));
+        }
+
+        let (signature_type, expected) = match self.order.signatureType {
+            t if t == SignatureType::Eoa as u8 => (SignatureType::Eoa, Some(signer)),
+            t if t == SignatureType::Proxy as u8 => {
+                (SignatureType::Proxy, derive_proxy_wallet(signer, chain_id))
+            }
+            t if t == SignatureType::GnosisSafe as u8 => (
+                SignatureType::GnosisSafe,
+                derive_safe_wallet(signer, chain_id),
+            ),
+            t => {
+                return Err(Error::validation(format!(
+                    "Unable to verify order with unknown signature type {t}"
+                )));
+            }
+        };
+
+        let Some(expected) = expected else {
+            return Err(Error::validation(format!(
+                "Unable to verify {signature_type} order: wallet derivation is not supported on \
+                chain {chain_id}"
+            )));
+        };
+
+        let maker = self.order.maker;
+        if maker != expected {
+            return Err(Error::validation(format!(
+                "Maker {maker} of {signature_type} order does not belong to signer {signer}, \
+                expected {expected}"
+            )));
+        }
+
+        Ok(())
+    }
+}
+
+impl fmt::Display for SignableOrder {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} {}", self.order_type, self.describe())
+    }
+}
+
+impl fmt::Display for SignedOrder {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} {}", self.order_type, self.describe())
+    }
+}
+
 /// Helper struct for serializing Order with signature injected.
 /// This avoids the overhead of `serde_json::to_value()` followed by mutation.
 #[serde_as]
@@ -554,6 +803,81 @@ impl Serialize for SignedOrder {
     }
 }
 
+/// Owned counterpart of [`OrderWithSignature`], used to read back a serialized [`SignedOrder`].
+#[serde_as]
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct OwnedOrderWithSignature {
+    salt: u64,
+    maker: alloy::primitives::Address,
+    signer: alloy::primitives::Address,
+    taker: alloy::primitives::Address,
+    #[serde_as(as = "DisplayFromStr")]
+    token_id: U256,
+    #[serde_as(as = "DisplayFromStr")]
+    maker_amount: U256,
+    #[serde_as(as = "DisplayFromStr")]
+    taker_amount: U256,
+    #[serde_as(as = "DisplayFromStr")]
+    expiration: U256,
+    #[serde_as(as = "DisplayFromStr")]
+    nonce: U256,
+    #[serde_as(as = "DisplayFromStr")]
+    fee_rate_bps: U256,
+    side: Side,
+    signature_type: u8,
+    #[serde_as(as = "DisplayFromStr")]
+    signature: Signature,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct SignedOrderFields {
+    order: OwnedOrderWithSignature,
+    order_type: OrderType,
+    owner: ApiKey,
+    #[serde(default)]
+    post_only: Option<bool>,
+}
+
+// Inverse of the `Serialize` impl, so that orders signed offline can be stored as JSON and
+// posted later by a client that never saw the signing key
+impl<'de> Deserialize<'de> for SignedOrder {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> std::result::Result<Self, D::Error> {
+        let SignedOrderFields {
+            order,
+            order_type,
+            owner,
+            post_only,
+        } = SignedOrderFields::deserialize(deserializer)?;
+
+        if order.side == Side::Unknown {
+            return Err(de::Error::custom("order side must be BUY or SELL"));
+        }
+
+        Ok(SignedOrder {
+            order: Order {
+                salt: U256::from(order.salt),
+                maker: order.maker,
+                signer: order.signer,
+                taker: order.taker,
+                tokenId: order.token_id,
+                makerAmount: order.maker_amount,
+                takerAmount: order.taker_amount,
+                expiration: order.expiration,
+                nonce: order.nonce,
+                feeRateBps: order.fee_rate_bps,
+                side: order.side as u8,
+                signatureType: order.signature_type,
+            },
+            signature: order.signature,
+            order_type,
+            owner,
+            post_only,
+        })
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use serde_json::to_value;
@@ -725,4 +1049,111 @@ mod tests {
 
         assert!(!object.contains_key("postOnly"));
     }
+
+    #[test]
+    fn signed_order_should_round_trip_through_json() {
+        let signed_order = SignedOrder {
+            order: Order {
+                salt: U256::from(42),
+                tokenId: U256::from(1234),
+                makerAmount: U256::from(5_000_000),
+                takerAmount: U256::from(10_000_000),
+                side: Side::Sell as u8,
+                feeRateBps: U256::from(100),
+                signatureType: SignatureType::Proxy as u8,
+                ..Order::default()
+            },
+            signature: Signature::new(U256::from(1), U256::from(2), true),
+            order_type: OrderType::GTD,
+            owner: ApiKey::nil(),
+            post_only: Some(true),
+        };
+
+        let json = serde_json::to_string(&signed_order).expect("serialize SignedOrder");
+        let decoded: SignedOrder = serde_json::from_str(&json).expect("deserialize SignedOrder");
+
+        assert_eq!(decoded, signed_order);
+    }
+
+    #[test]
+    fn api_error_should_decode_known_messages() {
+        let cases = [
+            (
+                "not enough balance / allowance",
+                ApiError::InsufficientBalance,
+            ),
+            (
+                "order 0xabc is invalid. Price (0.555) breaks minimum tick size rule: 0.01",
+                ApiError::InvalidTickSize,
+            ),
+            (
+                "order 0xabc is invalid. Size (1) lower than the minimum: 5",
+                ApiError::OrderTooSmall,
+            ),
+            (
+                "invalid post-only order: order crosses book",
+                ApiError::CrossesBook,
+            ),
+            ("market is closed", ApiError::MarketClosed),
+            ("invalid nonce", ApiError::InvalidNonce),
+            (
+                "order 0xabc is invalid. Duplicated.",
+                ApiError::DuplicateOrder,
+            ),
+            (
+                "order couldn't be fully filled. FOK orders are fully filled or killed.",
+                ApiError::NotFullyFilled,
+            ),
+            (
+                "no orders found to match with FAK order. FAK orders are partially filled or killed if no match is found.",
+                ApiError::NoMatch,
+            ),
+            (
+                "order can't be found - already canceled or matched",
+                ApiError::OrderNotFound,
+            ),
+            (
+                "Order not found or already canceled",
+                ApiError::OrderNotFound,
+            ),
+        ];
+
+        for (message, expected) in cases {
+            assert_eq!(ApiError::from_message(message), expected, "{message}");
+        }
+    }
+
+    #[test]
+    fn api_error_should_decode_json_bodies() {
+        assert_eq!(
+            ApiError::from_message(r#"{"error":"not enough balance / allowance"}"#),
+            ApiError::InsufficientBalance
+        );
+        assert_eq!(
+            ApiError::from_message(r#"{"error":"something else"}"#),
+            ApiError::Unknown("something else".to_owned())
+        );
+        assert_eq!(
+            ApiError::from_message("Bad Gateway"),
+            ApiError::Unknown("Bad Gateway".to_owned())
+        );
+    }
+
+    #[test]
+    fn api_error_should_not_decode_unrelated_messages() {
+        for message in [
+            "Unauthorized/Invalid api key",
+            "insufficient permissions for this endpoint",
+            "could not fetch the allowance, try again later",
+            "L1 auth nonce already used",
+            "market not found",
+            "API key not found",
+        ] {
+            assert_eq!(
+                ApiError::from_message(message),
+                ApiError::Unknown(message.to_owned()),
+                "{message}"
+            );
+        }
+    }
 }
@@ -11,6 +11,8 @@ use serde_with::{
 };
 #[cfg(feature = "rfq")]
 use {
+    crate::clob::describe::{OrderDescription, OrderTerms, describe},
+    crate::clob::order_builder::USDC_DECIMALS,
     crate::clob::types::{RfqSortBy, RfqSortDir, RfqState},
     crate::{Timestamp, auth::ApiKey, types::Decimal},
 };
@@ -420,12 +422,85 @@ pub struct ApproveRfqOrderRequest {
     pub owner: ApiKey,
 }
 
+#[cfg(feature = "rfq")]
+impl ApproveRfqOrderRequest {
+    /// Decodes the order being approved into a price, size and notional, see
+    /// [`OrderDescription`].
+    #[must_use]
+    pub fn describe(&self) -> OrderDescription {
+        let amount = |amount: Decimal| {
+            amount
+                .checked_mul(Decimal::new(1, USDC_DECIMALS))
+                .map(|amount| amount.normalize())
+        };
+
+        describe(&OrderTerms {
+            token_id: self.token_id,
+            side: self.side as u8,
+            maker_amount: amount(self.maker_amount),
+            taker_amount: amount(self.taker_amount),
+            fee_rate_bps: U256::from(self.fee_rate_bps),
+            expiration: u64::try_from(self.expiration).map_or(U256::MAX, U256::from),
+            signature_type: None,
+            maker: self.maker,
+            signer: self.signer,
+            taker: self.taker,
+            nonce: U256::from(self.nonce),
+        })
+    }
+}
+
+#[cfg(feature = "rfq")]
+impl std::fmt::Display for ApproveRfqOrderRequest {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "approval of quote {} for request {}: {}",
+            self.quote_id,
+            self.request_id,
+            self.describe()
+        )
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
     use crate::ToQueryParams as _;
     use crate::types::b256;
 
+    #[cfg(feature = "rfq")]
+    #[test]
+    fn approve_rfq_order_request_should_describe_order() {
+        let request = ApproveRfqOrderRequest::builder()
+            .request_id("request")
+            .quote_id("quote")
+            .maker_amount(Decimal::from(50_000_000))
+            .taker_amount(Decimal::from(20_000_000))
+            .token_id(U256::from(7))
+            .maker(Address::ZERO)
+            .signer(Address::ZERO)
+            .taker(Address::ZERO)
+            .nonce(0)
+            .expiration(0)
+            .side(Side::Sell)
+            .fee_rate_bps(0)
+            .signature("0x")
+            .salt("1")
+            .owner(ApiKey::nil())
+            .build();
+
+        let description = request.describe();
+        assert_eq!(description.price, Some(Decimal::new(4, 1)));
+        assert_eq!(description.size, Some(Decimal::from(50)));
+        assert!(description.issues.is_empty());
+        assert!(
+            request
+                .to_string()
+                .starts_with("approval of quote quote for request request: SELL 50 shares")
+        );
+    }
+
     #[test]
     fn trades_request_as_params_should_succeed() {
         let market = b256!("0000000000000000000000000000000000000000000000000000000000010000");
@@ -17,7 +17,10 @@ use uuid::Uuid;
 
 use crate::Result;
 use crate::auth::ApiKey;
-use crate::clob::types::{OrderStatusType, OrderType, Side, TickSize, TradeStatusType, TraderSide};
+use crate::clob::book_hash;
+use crate::clob::types::{
+    ApiError, OrderStatusType, OrderType, Side, TickSize, TradeStatusType, TraderSide,
+};
 use crate::serde_helpers::StringFromAny;
 use crate::types::{Address, B256, Decimal, U256};
 
@@ -138,6 +141,10 @@ pub struct OrderBookSummaryResponse {
 }
 
 impl OrderBookSummaryResponse {
+    /// Returns the hex-encoded SHA-256 digest of this response's JSON serialization.
+    ///
+    /// This is not the hash the server sends in [`Self::hash`], see [`Self::compute_hash`] for
+    /// that one.
     pub fn hash(&self) -> Result<String> {
         let json = serde_json::to_string(&self)?;
 
@@ -147,6 +154,31 @@ impl OrderBookSummaryResponse {
 
         Ok(format!("{result:x}"))
     }
+
+    /// Computes the server-compatible hash of this order book.
+    ///
+    /// See [`book_hash`] for how the hash is derived.
+    #[must_use]
+    pub fn compute_hash(&self) -> String {
+        let payload = book_hash::canonical_payload(
+            self.market,
+            self.asset_id,
+            self.timestamp.timestamp_millis(),
+            self.bids.iter().map(|level| (level.price, level.size)),
+            self.asks.iter().map(|level| (level.price, level.size)),
+        );
+
+        book_hash::digest(&payload)
+    }
+
+    /// Returns `true` if the `hash` sent by the server matches the contents of this order book,
+    /// and `false` if they differ or the server did not send a hash.
+    #[must_use]
+    pub fn verify_hash(&self) -> bool {
+        self.hash
+            .as_deref()
+            .is_some_and(|expected| book_hash::matches(&self.compute_hash(), expected))
+    }
 }
 
 #[non_exhaustive]
@@ -305,6 +337,17 @@ pub struct PostOrderResponse {
     pub trade_ids: Vec<String>,
 }
 
+impl PostOrderResponse {
+    /// Decodes [`Self::error_msg`] into the reason the order was rejected, if any.
+    #[must_use]
+    pub fn api_error(&self) -> Option<ApiError> {
+        self.error_msg
+            .as_deref()
+            .filter(|message| !message.is_empty())
+            .map(ApiError::from_message)
+    }
+}
+
 pub fn empty_string_as_zero<'de, D>(deserializer: D) -> std::result::Result<Decimal, D::Error>
 where
     D: Deserializer<'de>,
@@ -361,6 +404,69 @@ pub struct CancelOrdersResponse {
     pub not_canceled: HashMap<String, String>,
 }
 
+/// Outcome of [`Client::replace_order`](crate::clob::Client::replace_order).
+///
+/// The cancellation of the old order and the placement of the new one are sent together, and
+/// either can fail on its own, so both results are reported.
+#[non_exhaustive]
+#[derive(Debug)]
+pub struct ReplaceOrderResponse {
+    /// The ID of the order being replaced.
+    pub old_order_id: String,
+    /// What happened to the old order.
+    pub cancel: CancelOutcome,
+    /// The result of posting the new order.
+    pub post: Result<PostOrderResponse>,
+}
+
+impl ReplaceOrderResponse {
+    /// Whether the old order was cancelled.
+    #[must_use]
+    pub const fn is_canceled(&self) -> bool {
+        matches!(self.cancel, CancelOutcome::Canceled)
+    }
+
+    /// Whether the new order was accepted by the server.
+    #[must_use]
+    pub fn is_placed(&self) -> bool {
+        self.post.as_ref().is_ok_and(|response| response.success)
+    }
+
+    /// Whether the old order was cancelled and the new order accepted.
+    #[must_use]
+    pub fn is_replaced(&self) -> bool {
+        self.is_canceled() && self.is_placed()
+    }
+}
+
+/// What happened to the old order in a [`ReplaceOrderResponse`].
+#[non_exhaustive]
+#[derive(Debug)]
+pub enum CancelOutcome {
+    /// The order was cancelled.
+    Canceled,
+    /// The server declined to cancel the order, typically with [`ApiError::OrderNotFound`]
+    /// because it was already filled or cancelled.
+    NotCanceled(ApiError),
+    /// The cancel request itself failed, so the state of the order is unknown.
+    Failed(crate::error::Error),
+}
+
+impl CancelOutcome {
+    pub(crate) fn from_response(order_id: &str, response: Result<CancelOrdersResponse>) -> Self {
+        match response {
+            Ok(response) if response.canceled.iter().any(|id| id == order_id) => Self::Canceled,
+            Ok(response) => Self::NotCanceled(ApiError::from_message(
+                response
+                    .not_canceled
+                    .get(order_id)
+                    .map_or("order was not canceled", String::as_str),
+            )),
+            Err(err) => Self::Failed(err),
+        }
+    }
+}
+
 #[non_exhaustive]
 #[serde_as]
 #[derive(Debug, Clone, Deserialize, Builder, PartialEq)]
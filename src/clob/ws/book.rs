@@ -0,0 +1,387 @@
+//! Local order book maintained from market channel snapshots and deltas.
+//!
+//! The market channel sends a full [`BookUpdate`] snapshot when an asset is first subscribed
+//! (and after trades), followed by [`PriceChange`] deltas that set the aggregate size of
+//! individual price levels. [`LocalOrderBook`] folds those deltas into a per-asset book and
+//! checks every result against the hash the server attaches to each delta, so callers can
+//! detect a divergence and re-seed from a fresh snapshot.
+
+use std::collections::BTreeMap;
+
+use super::types::response::{BookUpdate, OrderBookLevel, PriceChange};
+use crate::clob::book_hash;
+use crate::clob::types::Side;
+use crate::clob::types::response::OrderBookSummaryResponse;
+use crate::types::{B256, Decimal, U256};
+
+/// An in-memory order book for a single asset.
+///
+/// Price levels are kept sorted so the best bid and best ask can be read without scanning.
+/// Levels with a size of zero are never stored.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LocalOrderBook {
+    asset_id: U256,
+    market: B256,
+    timestamp: i64,
+    bids: BTreeMap<Decimal, Decimal>,
+    asks: BTreeMap<Decimal, Decimal>,
+    hash: Option<String>,
+}
+
+impl LocalOrderBook {
+    /// Seeds a book from a market channel `book` snapshot.
+    #[must_use]
+    pub fn from_book_update(update: &BookUpdate) -> Self {
+        Self {
+            asset_id: update.asset_id,
+            market: update.market,
+            timestamp: update.timestamp,
+            bids: levels(update.bids.iter().map(|level| (level.price, level.size))),
+            asks: levels(update.asks.iter().map(|level| (level.price, level.size))),
+            hash: update.hash.clone(),
+        }
+    }
+
+    /// Seeds a book from a REST order book snapshot.
+    #[must_use]
+    pub fn from_summary(summary: &OrderBookSummaryResponse) -> Self {
+        Self {
+            asset_id: summary.asset_id,
+            market: summary.market,
+            timestamp: summary.timestamp.timestamp_millis(),
+            bids: levels(summary.bids.iter().map(|level| (level.price, level.size))),
+            asks: levels(summary.asks.iter().map(|level| (level.price, level.size))),
+            hash: summary.hash.clone(),
+        }
+    }
+
+    /// Applies the entries of a `price_change` event that belong to this book's asset.
+    ///
+    /// Each entry replaces the aggregate size resting at its price level, and a size of zero
+    /// removes the level. Entries for other assets are ignored.
+    ///
+    /// Returns `false` if the delta cannot be applied consistently: an entry without a size,
+    /// or a resulting book whose hash or best prices disagree with the values sent by the
+    /// server. The book should then be discarded and re-seeded from a fresh snapshot.
+    pub fn apply_price_change(&mut self, change: &PriceChange) -> bool {
+        let mut consistent = true;
+
+        for entry in change
+            .price_changes
+            .iter()
+            .filter(|entry| entry.asset_id == self.asset_id)
+        {
+            let Some(size) = entry.size else {
+                consistent = false;
+                continue;
+            };
+
+            let side = match entry.side {
+                Side::Buy => &mut self.bids,
+                Side::Sell => &mut self.asks,
+                _ => {
+                    consistent = false;
+                    continue;
+                }
+            };
+
+            if size.is_zero() {
+                side.remove(&entry.price);
+            } else {
+                side.insert(entry.price, size);
+            }
+
+            self.timestamp = change.timestamp;
+            self.hash.clone_from(&entry.hash);
+
+            if entry.hash.is_some() {
+                consistent &= self.verify_hash();
+            }
+            // The server reports an empty side as a best bid of 0 and a best ask of 1
+            if let Some(best_bid) = entry.best_bid {
+                consistent &= self.best_bid().unwrap_or(Decimal::ZERO) == best_bid;
+            }
+            if let Some(best_ask) = entry.best_ask {
+                consistent &= self.best_ask().unwrap_or(Decimal::ONE) == best_ask;
+            }
+        }
+
+        consistent
+    }
+
+    /// Computes the server-compatible hash of the current book contents.
+    ///
+    /// See [`book_hash`] for how the hash is derived.
+    #[must_use]
+    pub fn compute_hash(&self) -> String {
+        // Levels are hashed in the order the server emits them: bids from the lowest price up
+        // and asks from the highest price down.
+        let payload = book_hash::canonical_payload(
+            self.market,
+            self.asset_id,
+            self.timestamp,
+            self.bids.iter().map(|(price, size)| (*price, *size)),
+            self.asks.iter().rev().map(|(price, size)| (*price, *size)),
+        );
+
+        book_hash::digest(&payload)
+    }
+
+    /// Returns `true` if the last hash received from the server matches the current book
+    /// contents, and `false` if they differ or no hash has been received.
+    #[must_use]
+    pub fn verify_hash(&self) -> bool {
+        self.hash
+            .as_deref()
+            .is_some_and(|expected| book_hash::matches(&self.compute_hash(), expected))
+    }
+
+    /// The asset this book belongs to.
+    #[must_use]
+    pub const fn asset_id(&self) -> U256 {
+        self.asset_id
+    }
+
+    /// The market condition ID of the asset.
+    #[must_use]
+    pub const fn market(&self) -> B256 {
+        self.market
+    }
+
+    /// Unix timestamp in milliseconds of the last snapshot or delta applied.
+    #[must_use]
+    pub const fn timestamp(&self) -> i64 {
+        self.timestamp
+    }
+
+    /// The last hash received from the server for this book, if any.
+    #[must_use]
+    pub fn hash(&self) -> Option<&str> {
+        self.hash.as_deref()
+    }
+
+    /// Bid levels ordered from the best (highest) price down.
+    pub fn bids(&self) -> impl Iterator<Item = OrderBookLevel> + '_ {
+        self.bids
+            .iter()
+            .rev()
+            .map(|(price, size)| OrderBookLevel::builder().price(*price).size(*size).build())
+    }
+
+    /// Ask levels ordered from the best (lowest) price up.
+    pub fn asks(&self) -> impl Iterator<Item = OrderBookLevel> + '_ {
+        self.asks
+            .iter()
+            .map(|(price, size)| OrderBookLevel::builder().price(*price).size(*size).build())
+    }
+
+    /// The highest bid price, if there are any bids.
+    #[must_use]
+    pub fn best_bid(&self) -> Option<Decimal> {
+        self.bids.last_key_value().map(|(price, _)| *price)
+    }
+
+    /// The lowest ask price, if there are any asks.
+    #[must_use]
+    pub fn best_ask(&self) -> Option<Decimal> {
+        self.asks.first_key_value().map(|(price, _)| *price)
+    }
+
+    /// The average of the best bid and best ask, if both sides are populated.
+    #[must_use]
+    pub fn midpoint(&self) -> Option<Decimal> {
+        Some((self.best_bid()? + self.best_ask()?) / Decimal::TWO)
+    }
+
+    /// The difference between the best ask and best bid, if both sides are populated.
+    #[must_use]
+    pub fn spread(&self) -> Option<Decimal> {
+        Some(self.best_ask()? - self.best_bid()?)
+    }
+}
+
+impl From<&BookUpdate> for LocalOrderBook {
+    fn from(update: &BookUpdate) -> Self {
+        Self::from_book_update(update)
+    }
+}
+
+impl From<&OrderBookSummaryResponse> for LocalOrderBook {
+    fn from(summary: &OrderBookSummaryResponse) -> Self {
+        Self::from_summary(summary)
+    }
+}
+
+fn levels(levels: impl Iterator<Item = (Decimal, Decimal)>) -> BTreeMap<Decimal, Decimal> {
+    levels.filter(|(_, size)| !size.is_zero()).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use rust_decimal_macros::dec;
+
+    use super::*;
+    use crate::clob::ws::types::response::PriceChangeBatchEntry;
+    use crate::types::b256;
+
+    const MARKET: B256 = b256!("0000000000000000000000000000000000000000000000000000000000000001");
+
+    fn level(price: Decimal, size: Decimal) -> OrderBookLevel {
+        OrderBookLevel::builder().price(price).size(size).build()
+    }
+
+    fn book() -> LocalOrderBook {
+        let update = BookUpdate::builder()
+            .asset_id(U256::from(1))
+            .market(MARKET)
+            .timestamp(1_000)
+            .bids(vec![
+                level(dec!(0.48), dec!(30)),
+                level(dec!(0.49), dec!(20)),
+            ])
+            .asks(vec![
+                level(dec!(0.52), dec!(25)),
+                level(dec!(0.53), dec!(60)),
+            ])
+            .build();
+
+        LocalOrderBook::from_book_update(&update)
+    }
+
+    fn change(entries: Vec<PriceChangeBatchEntry>) -> PriceChange {
+        PriceChange::builder()
+            .market(MARKET)
+            .timestamp(2_000)
+            .price_changes(entries)
+            .build()
+    }
+
+    #[test]
+    fn seeds_sorted_levels() {
+        let book = book();
+
+        assert_eq!(book.best_bid(), Some(dec!(0.49)));
+        assert_eq!(book.best_ask(), Some(dec!(0.52)));
+        assert_eq!(book.midpoint(), Some(dec!(0.505)));
+        assert_eq!(book.spread(), Some(dec!(0.03)));
+
+        let bids: Vec<_> = book.bids().map(|level| level.price).collect();
+        assert_eq!(bids, vec![dec!(0.49), dec!(0.48)]);
+        let asks: Vec<_> = book.asks().map(|level| level.price).collect();
+        assert_eq!(asks, vec![dec!(0.52), dec!(0.53)]);
+    }
+
+    #[test]
+    fn applies_and_removes_levels() {
+        let mut book = book();
+
+        let delta = change(vec![
+            PriceChangeBatchEntry::builder()
+                .asset_id(U256::from(1))
+                .price(dec!(0.50))
+                .size(dec!(10))
+                .side(Side::Buy)
+                .best_bid(dec!(0.50))
+                .best_ask(dec!(0.52))
+                .build(),
+            PriceChangeBatchEntry::builder()
+                .asset_id(U256::from(1))
+                .price(dec!(0.52))
+                .size(Decimal::ZERO)
+                .side(Side::Sell)
+                .best_bid(dec!(0.50))
+                .best_ask(dec!(0.53))
+                .build(),
+        ]);
+
+        assert!(book.apply_price_change(&delta));
+        assert_eq!(book.best_bid(), Some(dec!(0.50)));
+        assert_eq!(book.best_ask(), Some(dec!(0.53)));
+        assert_eq!(book.asks().count(), 1);
+        assert_eq!(book.timestamp(), 2_000);
+    }
+
+    #[test]
+    fn ignores_other_assets() {
+        let mut book = book();
+        let before = book.clone();
+
+        let delta = change(vec![
+            PriceChangeBatchEntry::builder()
+                .asset_id(U256::from(2))
+                .price(dec!(0.10))
+                .size(dec!(5))
+                .side(Side::Buy)
+                .build(),
+        ]);
+
+        assert!(book.apply_price_change(&delta));
+        assert_eq!(book, before);
+    }
+
+    #[test]
+    fn detects_best_price_mismatch() {
+        let mut book = book();
+
+        let delta = change(vec![
+            PriceChangeBatchEntry::builder()
+                .asset_id(U256::from(1))
+                .price(dec!(0.47))
+                .size(dec!(5))
+                .side(Side::Buy)
+                .best_bid(dec!(0.50))
+                .build(),
+        ]);
+
+        assert!(!book.apply_price_change(&delta));
+    }
+
+    #[test]
+    fn detects_missing_size() {
+        let mut book = book();
+
+        let delta = change(vec![
+            PriceChangeBatchEntry::builder()
+                .asset_id(U256::from(1))
+                .price(dec!(0.47))
+                .side(Side::Buy)
+                .build(),
+        ]);
+
+        assert!(!book.apply_price_change(&delta));
+    }
+
+    #[test]
+    fn verifies_hash_of_resulting_book() {
+        let mut expected = book();
+        expected.bids.insert(dec!(0.47), dec!(5));
+        expected.timestamp = 2_000;
+        let hash = expected.compute_hash();
+
+        let mut book = book();
+        let delta = change(vec![
+            PriceChangeBatchEntry::builder()
+                .asset_id(U256::from(1))
+                .price(dec!(0.47))
+                .size(dec!(5))
+                .side(Side::Buy)
+                .hash(hash.clone())
+                .build(),
+        ]);
+        assert!(book.apply_price_change(&delta));
+        assert_eq!(book.hash(), Some(hash.as_str()));
+        assert!(book.verify_hash());
+
+        let mut book = self::book();
+        let delta = change(vec![
+            PriceChangeBatchEntry::builder()
+                .asset_id(U256::from(1))
+                .price(dec!(0.47))
+                .size(dec!(6))
+                .side(Side::Buy)
+                .hash(hash)
+                .build(),
+        ]);
+        assert!(!book.apply_price_change(&delta));
+    }
+}
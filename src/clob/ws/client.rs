@@ -1,13 +1,23 @@
+use std::collections::HashMap;
+use std::pin::pin;
 use std::sync::Arc;
 
-use async_stream::try_stream;
+use async_stream::{stream, try_stream};
+use backoff::ExponentialBackoff;
+use backoff::backoff::Backoff as _;
 use dashmap::mapref::one::{Ref, RefMut};
 use dashmap::{DashMap, Entry};
 use futures::Stream;
 use futures::StreamExt as _;
+use futures::stream::select_all;
+use tokio::sync::broadcast::error::RecvError;
+use tokio::time::{Instant, sleep_until};
 
+use super::book::LocalOrderBook;
 use super::interest::InterestTracker;
-use super::subscription::{ChannelType, SubscriptionManager};
+use super::shard::MarketShards;
+use super::subscription::{ChannelType, SubscriptionManager, WsEvent, market_messages};
+use super::tracker::{OrderTracker, Transition};
 use super::types::response::{
     BestBidAsk, BookUpdate, LastTradePrice, MarketResolved, MidpointUpdate, NewMarket,
     OrderMessage, PriceChange, TickSizeChange, TradeMessage, WsMessage,
@@ -15,11 +25,13 @@ use super::types::response::{
 use crate::Result;
 use crate::auth::state::{Authenticated, State, Unauthenticated};
 use crate::auth::{Credentials, Kind as AuthKind, Normal};
+use crate::clob::Client as RestClient;
+use crate::clob::types::request::OrderBookSummaryRequest;
 use crate::error::Error;
 use crate::types::{Address, B256, Decimal, U256};
 use crate::ws::ConnectionManager;
 use crate::ws::config::Config;
-use crate::ws::connection::ConnectionState;
+use crate::ws::connection::{ConnectionEvent, ConnectionState};
 
 /// WebSocket client for real-time market data and user updates.
 ///
@@ -58,11 +70,8 @@ pub struct Client<S: State = Unauthenticated> {
 
 impl Default for Client<Unauthenticated> {
     fn default() -> Self {
-        Self::new(
-            "wss://ws-subscriptions-clob.kuest.com",
-            Config::default(),
-        )
-        .expect("WebSocket client with default endpoint should succeed")
+        Self::new("wss://ws-subscriptions-clob.kuest.com", Config::default())
+            .expect("WebSocket client with default endpoint should succeed")
     }
 }
 
@@ -73,8 +82,10 @@ struct ClientInner<S: State> {
     config: Config,
     /// Base endpoint without channel suffix (e.g. `wss://...`)
     base_endpoint: String,
-    /// Resources for each WebSocket channel (lazily initialized)
+    /// Resources for the user channel (lazily initialized)
     channels: DashMap<ChannelType, ChannelResources>,
+    /// Connections of the market channel (lazily initialized)
+    market: MarketShards,
 }
 
 impl Client<Unauthenticated> {
@@ -86,6 +97,10 @@ impl Client<Unauthenticated> {
     /// The WebSocket connection is established lazily upon the first subscription.
     pub fn new(endpoint: &str, config: Config) -> Result<Self> {
         let base_endpoint = normalize_base_endpoint(endpoint);
+        let market = MarketShards::new(
+            channel_endpoint(&base_endpoint, ChannelType::Market),
+            config.clone(),
+        );
 
         Ok(Self {
             inner: Arc::new(ClientInner {
@@ -93,6 +108,7 @@ impl Client<Unauthenticated> {
                 config,
                 base_endpoint,
                 channels: DashMap::new(),
+                market,
             }),
         })
     }
@@ -116,6 +132,7 @@ impl Client<Unauthenticated> {
             config,
             base_endpoint,
             channels,
+            market,
             ..
         } = inner;
 
@@ -129,6 +146,7 @@ impl Client<Unauthenticated> {
                 config,
                 base_endpoint,
                 channels,
+                market,
             }),
         })
     }
@@ -154,8 +172,7 @@ impl<S: State> Client<S> {
         &self,
         asset_ids: Vec<U256>,
     ) -> Result<impl Stream<Item = Result<BookUpdate>>> {
-        let resources = self.inner.get_or_create_channel(ChannelType::Market)?;
-        let stream = resources.subscriptions.subscribe_market(asset_ids)?;
+        let stream = market_messages(self.inner.market.subscribe(asset_ids, false)?);
 
         Ok(stream.filter_map(|msg_result| async move {
             match msg_result {
@@ -166,6 +183,174 @@ impl<S: State> Client<S> {
         }))
     }
 
+    /// Subscribes to a locally maintained orderbook for specified market assets.
+    ///
+    /// Each asset's [`LocalOrderBook`] is seeded from the `book` snapshots sent by the server and
+    /// kept current by applying `price_change` deltas level by level. Whenever a delta arrives for
+    /// an asset that has not been seeded yet, or leaves the book inconsistent with the hash and
+    /// best prices reported by the server, a fresh snapshot is fetched through `rest`.
+    ///
+    /// The stream yields the full book of an asset every time it changes, so each item is a
+    /// consistent view that can be used directly for pricing. When the subscription lags or the
+    /// connection is re-established, deltas may have been missed, so a fresh snapshot of every
+    /// asset is fetched through `rest` and yielded.
+    ///
+    /// A failed snapshot request is yielded as an error and does not end the stream: the book is
+    /// held back until a snapshot is fetched, which is retried with the backoff of
+    /// [`Config::reconnect`], or until the server sends a `book` snapshot for the asset.
+    ///
+    /// # Arguments
+    ///
+    /// * `asset_ids` - List of asset/token IDs to monitor
+    /// * `rest` - CLOB client used to fetch order book snapshots when re-seeding
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the subscription cannot be created or the WebSocket
+    /// connection is not established. The stream yields an error if a snapshot
+    /// request fails.
+    pub fn subscribe_local_orderbook<R: State>(
+        &self,
+        asset_ids: Vec<U256>,
+        rest: RestClient<R>,
+    ) -> Result<impl Stream<Item = Result<LocalOrderBook>>> {
+        let stream = self.inner.market.subscribe(asset_ids.clone(), false)?;
+        let reconnect = self.inner.config.reconnect.clone();
+
+        Ok(stream! {
+            let mut stream = pin!(stream);
+            let mut books: HashMap<U256, LocalOrderBook> = HashMap::new();
+            // Assets whose book must be re-seeded from a snapshot, in the order they went stale
+            let mut stale: Vec<U256> = Vec::new();
+            let max_backoff = reconnect.max_backoff;
+            let mut backoff: ExponentialBackoff = reconnect.into();
+            let mut retry_at: Option<Instant> = None;
+
+            loop {
+                if !stale.is_empty() && retry_at.is_none_or(|at| at <= Instant::now()) {
+                    while let Some(&asset_id) = stale.first() {
+                        let request = OrderBookSummaryRequest::builder()
+                            .token_id(asset_id)
+                            .build();
+                        match rest.order_book(&request).await {
+                            Ok(snapshot) => {
+                                stale.remove(0);
+                                let book = LocalOrderBook::from_summary(&snapshot);
+                                books.insert(asset_id, book.clone());
+                                yield Ok(book);
+                            }
+                            Err(e) => {
+                                let delay = backoff.next_backoff().unwrap_or(max_backoff);
+                                retry_at = Some(Instant::now() + delay);
+                                yield Err(e);
+                                break;
+                            }
+                        }
+                    }
+
+                    if stale.is_empty() {
+                        backoff.reset();
+                        retry_at = None;
+                    }
+                }
+
+                let event = match retry_at.filter(|_| !stale.is_empty()) {
+                    Some(at) => tokio::select! {
+                        event = stream.next() => event,
+                        () = sleep_until(at) => continue,
+                    },
+                    None => stream.next().await,
+                };
+                let Some(event) = event else {
+                    break;
+                };
+
+                let message = match event {
+                    WsEvent::Message(message) => message,
+                    WsEvent::Lagged { .. } | WsEvent::Reconnected => {
+                        #[cfg(feature = "tracing")]
+                        tracing::debug!("Local orderbooks may be stale, fetching snapshots");
+
+                        for &asset_id in &asset_ids {
+                            books.remove(&asset_id);
+                            if !stale.contains(&asset_id) {
+                                stale.push(asset_id);
+                            }
+                        }
+                        continue;
+                    }
+                };
+
+                match message {
+                    WsMessage::Book(update) => {
+                        let book = LocalOrderBook::from_book_update(&update);
+                        stale.retain(|asset_id| *asset_id != book.asset_id());
+                        books.insert(book.asset_id(), book.clone());
+                        yield Ok(book);
+                    }
+                    WsMessage::PriceChange(change) => {
+                        // Assets touched by this delta, in the order they first appear
+                        let mut changed: Vec<U256> = Vec::new();
+                        for entry in &change.price_changes {
+                            if asset_ids.contains(&entry.asset_id)
+                                && !changed.contains(&entry.asset_id)
+                                && !stale.contains(&entry.asset_id)
+                            {
+                                changed.push(entry.asset_id);
+                            }
+                        }
+
+                        for asset_id in changed {
+                            let consistent = books
+                                .get_mut(&asset_id)
+                                .is_some_and(|book| book.apply_price_change(&change));
+
+                            if consistent {
+                                if let Some(book) = books.get(&asset_id) {
+                                    yield Ok(book.clone());
+                                }
+                            } else {
+                                #[cfg(feature = "tracing")]
+                                tracing::debug!(
+                                    %asset_id,
+                                    "Local orderbook out of sync, fetching snapshot"
+                                );
+
+                                books.remove(&asset_id);
+                                stale.push(asset_id);
+                            }
+                        }
+                    }
+                    _ => {}
+                }
+            }
+        })
+    }
+
+    /// Subscribes to every market channel message for specified assets, along with a
+    /// [`WsEvent`] for each gap in the stream.
+    ///
+    /// Unlike the typed subscriptions, which report a lagging stream as an error item, this
+    /// stream reports [`WsEvent::Lagged`] when messages were dropped because the consumer fell
+    /// behind, and [`WsEvent::Reconnected`] when the connection was re-established. In both cases
+    /// the subscription is re-sent, so fresh `book` snapshots follow, unless
+    /// [`Config::resync_after_lag`] is disabled for lags.
+    ///
+    /// # Arguments
+    ///
+    /// * `asset_ids` - List of asset/token IDs to monitor
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the subscription cannot be created or the WebSocket
+    /// connection is not established.
+    pub fn subscribe_market_events(
+        &self,
+        asset_ids: Vec<U256>,
+    ) -> Result<impl Stream<Item = WsEvent>> {
+        self.inner.market.subscribe(asset_ids, false)
+    }
+
     /// Subscribes to real-time last trade price updates for specified assets.
     ///
     /// Returns a stream of the most recent executed trade price for each asset.
@@ -183,8 +368,7 @@ impl<S: State> Client<S> {
         &self,
         asset_ids: Vec<U256>,
     ) -> Result<impl Stream<Item = Result<LastTradePrice>>> {
-        let resources = self.inner.get_or_create_channel(ChannelType::Market)?;
-        let stream = resources.subscriptions.subscribe_market(asset_ids)?;
+        let stream = market_messages(self.inner.market.subscribe(asset_ids, false)?);
 
         Ok(stream.filter_map(|msg_result| async move {
             match msg_result {
@@ -213,8 +397,7 @@ impl<S: State> Client<S> {
         &self,
         asset_ids: Vec<U256>,
     ) -> Result<impl Stream<Item = Result<PriceChange>>> {
-        let resources = self.inner.get_or_create_channel(ChannelType::Market)?;
-        let stream = resources.subscriptions.subscribe_market(asset_ids)?;
+        let stream = market_messages(self.inner.market.subscribe(asset_ids, false)?);
 
         Ok(stream.filter_map(|msg_result| async move {
             match msg_result {
@@ -242,8 +425,7 @@ impl<S: State> Client<S> {
         &self,
         asset_ids: Vec<U256>,
     ) -> Result<impl Stream<Item = Result<TickSizeChange>>> {
-        let resources = self.inner.get_or_create_channel(ChannelType::Market)?;
-        let stream = resources.subscriptions.subscribe_market(asset_ids)?;
+        let stream = market_messages(self.inner.market.subscribe(asset_ids, false)?);
 
         Ok(stream.filter_map(|msg_result| async move {
             match msg_result {
@@ -299,11 +481,7 @@ impl<S: State> Client<S> {
         &self,
         asset_ids: Vec<U256>,
     ) -> Result<impl Stream<Item = Result<BestBidAsk>>> {
-        let stream = self
-            .inner
-            .get_or_create_channel(ChannelType::Market)?
-            .subscriptions
-            .subscribe_market_with_options(asset_ids, true)?;
+        let stream = market_messages(self.inner.market.subscribe(asset_ids, true)?);
 
         Ok(stream.filter_map(|msg_result| async move {
             match msg_result {
@@ -321,11 +499,7 @@ impl<S: State> Client<S> {
         &self,
         asset_ids: Vec<U256>,
     ) -> Result<impl Stream<Item = Result<NewMarket>>> {
-        let stream = self
-            .inner
-            .get_or_create_channel(ChannelType::Market)?
-            .subscriptions
-            .subscribe_market_with_options(asset_ids, true)?;
+        let stream = market_messages(self.inner.market.subscribe(asset_ids, true)?);
 
         Ok(stream.filter_map(|msg_result| async move {
             match msg_result {
@@ -343,11 +517,7 @@ impl<S: State> Client<S> {
         &self,
         asset_ids: Vec<U256>,
     ) -> Result<impl Stream<Item = Result<MarketResolved>>> {
-        let stream = self
-            .inner
-            .get_or_create_channel(ChannelType::Market)?
-            .subscriptions
-            .subscribe_market_with_options(asset_ids, true)?;
+        let stream = market_messages(self.inner.market.subscribe(asset_ids, true)?);
 
         Ok(stream.filter_map(|msg_result| async move {
             match msg_result {
@@ -361,13 +531,61 @@ impl<S: State> Client<S> {
     /// Get the current connection state for a specific channel.
     ///
     /// Returns [`ConnectionState::Disconnected`] if the channel has not been
-    /// initialized yet (no subscriptions have been made).
+    /// initialized yet (no subscriptions have been made). When the market channel is sharded
+    /// across several connections, returns the state of the first one that is not connected.
     #[must_use]
     pub fn connection_state(&self, channel_type: ChannelType) -> ConnectionState {
-        self.inner.channel(channel_type).as_deref().map_or(
-            ConnectionState::Disconnected,
-            ChannelResources::connection_state,
-        )
+        match channel_type {
+            ChannelType::Market => self.inner.market.connection_state(),
+            ChannelType::User => self.inner.channel(channel_type).as_deref().map_or(
+                ConnectionState::Disconnected,
+                ChannelResources::connection_state,
+            ),
+        }
+    }
+
+    /// Subscribes to the lifecycle events of a specific channel's connection.
+    ///
+    /// Yields [`ConnectionEvent::Connected`] when the connection is established,
+    /// [`ConnectionEvent::Disconnected`] with the reason when it drops,
+    /// [`ConnectionEvent::Reconnecting`] before each new attempt and
+    /// [`ConnectionEvent::Resubscribed`] once subscriptions have been re-sent, so that quotes can
+    /// be pulled while the feed is down. The channel is connected if it was not already.
+    ///
+    /// Events are only buffered for a short while, so a consumer that falls behind skips the
+    /// oldest ones. When the market channel is sharded across several connections, the events of
+    /// every connection open at the time of the call are merged.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the channel cannot be created.
+    pub fn connection_events(
+        &self,
+        channel_type: ChannelType,
+    ) -> Result<impl Stream<Item = ConnectionEvent>> {
+        let receivers = match channel_type {
+            ChannelType::Market => self.inner.market.events()?,
+            ChannelType::User => vec![
+                self.inner
+                    .get_or_create_channel(channel_type)?
+                    .connection
+                    .events(),
+            ],
+        };
+
+        let streams = receivers.into_iter().map(|mut rx| {
+            Box::pin(stream! {
+                loop {
+                    match rx.recv().await {
+                        Ok(event) => yield event,
+                        Err(RecvError::Lagged(_)) => {}
+                        Err(RecvError::Closed) => break,
+                    }
+                }
+            })
+        });
+
+        Ok(select_all(streams))
     }
 
     /// Check if the WebSocket connection is established for a specific channel.
@@ -375,7 +593,10 @@ impl<S: State> Client<S> {
     /// Returns `false` if no subscriptions have been made yet for this channel.
     #[must_use]
     pub fn is_connected(&self, channel_type: ChannelType) -> bool {
-        self.inner.channel(channel_type).is_some()
+        match channel_type {
+            ChannelType::Market => self.inner.market.connection_count() > 0,
+            ChannelType::User => self.inner.channel(channel_type).is_some(),
+        }
     }
 
     /// Get the number of active subscriptions.
@@ -385,7 +606,8 @@ impl<S: State> Client<S> {
             .channels
             .iter()
             .map(|entry| entry.value().subscriptions.subscription_count())
-            .sum()
+            .sum::<usize>()
+            + self.inner.market.subscription_count()
     }
 
     /// Unsubscribe from orderbook updates for specific assets.
@@ -393,10 +615,7 @@ impl<S: State> Client<S> {
     /// This decrements the reference count for each asset. The server unsubscribe
     /// is only sent when no other subscriptions are using those assets.
     pub fn unsubscribe_orderbook(&self, asset_ids: &[U256]) -> Result<()> {
-        self.inner
-            .unsubscribe_and_cleanup(ChannelType::Market, |subs| {
-                subs.unsubscribe_market(asset_ids)
-            })
+        self.inner.market.unsubscribe(asset_ids)
     }
 
     /// Unsubscribe from price changes for specific assets.
@@ -415,6 +634,14 @@ impl<S: State> Client<S> {
         self.unsubscribe_orderbook(asset_ids)
     }
 
+    /// Unsubscribe from local orderbook updates for specific assets.
+    ///
+    /// This decrements the reference count for each asset. The server unsubscribe
+    /// is only sent when no other subscriptions are using those assets.
+    pub fn unsubscribe_local_orderbook(&self, asset_ids: &[U256]) -> Result<()> {
+        self.unsubscribe_orderbook(asset_ids)
+    }
+
     /// Unsubscribe from midpoint updates for specific assets.
     ///
     /// This decrements the reference count for each asset. The server unsubscribe
@@ -520,6 +747,103 @@ impl<K: AuthKind> Client<Authenticated<K>> {
         }))
     }
 
+    /// Follows the orders registered with `tracker` through user channel events.
+    ///
+    /// Every order and trade event for a tracked order is applied to `tracker`, and the
+    /// resulting [`Transition`]s are yielded. Whenever the user channel (re)connects, the
+    /// tracker is backfilled over REST with `rest` so that events missed while disconnected
+    /// are still reported.
+    ///
+    /// # Arguments
+    ///
+    /// * `tracker` - Tracker holding the orders to follow
+    /// * `markets` - List of market condition IDs to monitor
+    /// * `rest` - Authenticated REST client used to backfill after reconnects
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the subscription cannot be created. The stream yields an error and
+    /// ends if a message cannot be received. The requests that fail during a backfill are
+    /// yielded as errors after the transitions recovered by the others, and tracking continues;
+    /// the next reconnect backfills again, or call [`OrderTracker::backfill`] to recover the
+    /// missed events sooner.
+    pub fn track_orders<R: AuthKind>(
+        &self,
+        tracker: &OrderTracker,
+        markets: Vec<B256>,
+        rest: RestClient<Authenticated<R>>,
+    ) -> Result<impl Stream<Item = Result<Transition>>> {
+        #[expect(
+            clippy::large_enum_variant,
+            reason = "Short-lived value that is matched immediately after each select"
+        )]
+        enum Step {
+            Message(Option<Result<WsMessage>>),
+            StateChanged(bool),
+        }
+
+        let events = self.subscribe_user_events(markets)?;
+        let mut state_rx = self
+            .inner
+            .get_or_create_channel(ChannelType::User)?
+            .connection
+            .state_receiver();
+        // Only connections established after this point can have missed events
+        state_rx.mark_unchanged();
+        let tracker = tracker.clone();
+
+        Ok(stream! {
+            let mut events = Box::pin(events);
+            let mut watching_state = true;
+
+            loop {
+                let step = tokio::select! {
+                    message = events.next() => Step::Message(message),
+                    changed = state_rx.changed(), if watching_state => {
+                        Step::StateChanged(changed.is_ok())
+                    }
+                };
+
+                match step {
+                    Step::Message(None) => break,
+                    Step::Message(Some(Err(e))) => {
+                        yield Err(e);
+                        break;
+                    }
+                    Step::Message(Some(Ok(message))) => {
+                        let transitions = match message {
+                            WsMessage::Order(order) => {
+                                tracker.apply_order_message(&order).into_iter().collect()
+                            }
+                            WsMessage::Trade(trade) => tracker.apply_trade_message(&trade),
+                            _ => Vec::new(),
+                        };
+                        for transition in transitions {
+                            yield Ok(transition);
+                        }
+                    }
+                    Step::StateChanged(false) => watching_state = false,
+                    Step::StateChanged(true) => {
+                        if state_rx.borrow_and_update().is_connected() {
+                            #[cfg(feature = "tracing")]
+                            tracing::debug!("User channel connected, backfilling tracked orders");
+
+                            // A failed backfill only loses the events missed while disconnected,
+                            // so the orders keep being tracked from live events
+                            let backfill = tracker.backfill(&rest).await;
+                            for transition in backfill.transitions {
+                                yield Ok(transition);
+                            }
+                            for e in backfill.errors {
+                                yield Err(e);
+                            }
+                        }
+                    }
+                }
+            }
+        })
+    }
+
     /// Unsubscribe from user channel events for specific markets.
     ///
     /// This decrements the reference count for each market. The server unsubscribe
@@ -558,6 +882,7 @@ impl<K: AuthKind> Client<Authenticated<K>> {
             config,
             base_endpoint,
             channels,
+            market,
             ..
         } = inner;
         channels.remove(&ChannelType::User);
@@ -568,6 +893,7 @@ impl<K: AuthKind> Client<Authenticated<K>> {
                 config,
                 base_endpoint,
                 channels,
+                market,
             }),
         })
     }
@@ -619,16 +945,20 @@ impl<S: State> ClientInner<S> {
 }
 
 /// Resources for a WebSocket channel.
-struct ChannelResources {
-    connection: ConnectionManager<WsMessage, Arc<InterestTracker>>,
-    subscriptions: Arc<SubscriptionManager>,
+pub(super) struct ChannelResources {
+    pub(super) connection: ConnectionManager<WsMessage, Arc<InterestTracker>>,
+    pub(super) subscriptions: Arc<SubscriptionManager>,
 }
 
 impl ChannelResources {
-    fn new(endpoint: String, config: Config) -> Result<Self> {
+    pub(super) fn new(endpoint: String, config: Config) -> Result<Self> {
         let interest = Arc::new(InterestTracker::new());
+        let resync_after_lag = config.resync_after_lag;
         let connection = ConnectionManager::new(endpoint, config, Arc::clone(&interest))?;
-        let subscriptions = Arc::new(SubscriptionManager::new(connection.clone(), interest));
+        let subscriptions = Arc::new(
+            SubscriptionManager::new(connection.clone(), interest)
+                .resync_after_lag(resync_after_lag),
+        );
 
         subscriptions.start_reconnection_handler();
 
@@ -638,7 +968,7 @@ impl ChannelResources {
         })
     }
 
-    fn connection_state(&self) -> ConnectionState {
+    pub(super) fn connection_state(&self) -> ConnectionState {
         self.connection.state()
     }
 }
@@ -3,14 +3,19 @@
     reason = "Re-exported names intentionally match their modules for API clarity"
 )]
 
+pub mod book;
 pub mod client;
 pub mod interest;
+pub mod shard;
 pub mod subscription;
+pub mod tracker;
 pub mod types;
 
 // Re-export commonly used types
+pub use book::LocalOrderBook;
 pub use client::Client;
-pub use subscription::{ChannelType, SubscriptionInfo, SubscriptionTarget};
+pub use subscription::{ChannelType, SubscriptionInfo, SubscriptionTarget, WsEvent};
+pub use tracker::{OrderTracker, TrackedOrder, Transition};
 pub use types::request::SubscriptionRequest;
 pub use types::response::{
     BestBidAsk, BookUpdate, EventMessage, LastTradePrice, MakerOrder, MarketResolved,
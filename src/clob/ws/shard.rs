@@ -0,0 +1,706 @@
+//! Sharding of the market channel across several connections.
+//!
+//! With [`Config::max_assets_per_connection`] set, the market channel spreads its assets over up
+//! to [`Config::max_market_connections`] connections instead of a single one. Each connection
+//! keeps its own [`SubscriptionManager`], with its own reference counts, reconnection handling
+//! and lag recovery, so a connection that drops only affects the assets it carries.
+//!
+//! New assets go to the connection carrying the fewest assets, and a new connection is only
+//! opened once every existing one is full. A connection whose assets are all unsubscribed is
+//! closed, making room for the next ones. The streams of every connection an asset list spans
+//! are merged back into one.
+//!
+//! Unsubscribing can leave several partly filled connections where fewer would do, so the
+//! least loaded connection is then drained into the others whenever they have room for its
+//! assets. Each asset is subscribed on the connection it moves to before it is unsubscribed from
+//! the one it leaves, and streams switch over to the new connection at its first `book` snapshot
+//! of the asset, skipping whatever either connection sends twice in between. The drained
+//! connection is closed once every asset has switched over, or after ten seconds at most.
+//!
+//! [`Config::max_assets_per_connection`]: crate::ws::config::Config::max_assets_per_connection
+//! [`Config::max_market_connections`]: crate::ws::config::Config::max_market_connections
+//! [`SubscriptionManager`]: super::subscription::SubscriptionManager
+
+use std::collections::{HashMap, HashSet};
+use std::pin::Pin;
+use std::sync::{Arc, Mutex, MutexGuard, PoisonError};
+use std::time::Duration;
+
+use async_stream::stream;
+use futures::stream::SelectAll;
+use futures::{Stream, StreamExt as _};
+use tokio::sync::broadcast::error::RecvError;
+use tokio::sync::{broadcast, mpsc};
+use tokio::time::{Instant, timeout_at};
+
+use super::client::ChannelResources;
+use super::subscription::{ChannelType, WsEvent, message_assets};
+use super::types::response::WsMessage;
+use crate::Result;
+use crate::types::U256;
+use crate::ws::WsError;
+use crate::ws::config::Config;
+use crate::ws::connection::{ConnectionEvent, ConnectionState, Receiver};
+
+/// How long an asset moved to another connection waits for its first `book` snapshot there
+/// before the connection it leaves stops carrying it anyway.
+const HANDOVER_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// The events of one connection, tagged with the connection's identifier.
+type Listener = Pin<Box<dyn Stream<Item = (u64, WsEvent)> + Send>>;
+
+/// The connections of the market channel.
+pub(crate) struct MarketShards {
+    endpoint: String,
+    config: Config,
+    shards: Arc<Mutex<Shards>>,
+    /// The connection carrying each subscribed asset, read by the streams to pick which
+    /// connection they take an asset's messages from
+    routes: Arc<Mutex<HashMap<U256, Route>>>,
+}
+
+#[derive(Default)]
+struct Shards {
+    connections: Vec<Shard>,
+    next_id: u64,
+    /// Connections being drained into others, which take no new assets
+    draining: HashSet<u64>,
+    /// Streams reading the channel, which are handed the connections their assets move to
+    readers: Vec<Reader>,
+}
+
+struct Shard {
+    id: u64,
+    channel: ChannelResources,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+struct Route {
+    /// Connection carrying the asset
+    owner: u64,
+    /// Connection the asset is moving away from, which still carries it until the handover
+    moving_from: Option<u64>,
+}
+
+struct Reader {
+    asset_ids: Vec<U256>,
+    custom_features: bool,
+    /// Connections the stream reads from
+    shards: HashSet<u64>,
+    listeners: mpsc::UnboundedSender<Listener>,
+}
+
+impl MarketShards {
+    pub(crate) fn new(endpoint: String, config: Config) -> Self {
+        Self {
+            endpoint,
+            config,
+            shards: Arc::default(),
+            routes: Arc::default(),
+        }
+    }
+
+    /// Subscribes to `asset_ids`, opening connections as needed, and merges the events of every
+    /// connection involved.
+    pub(crate) fn subscribe(
+        &self,
+        asset_ids: Vec<U256>,
+        custom_features: bool,
+    ) -> Result<impl Stream<Item = WsEvent> + use<>> {
+        if asset_ids.is_empty() {
+            return Err(WsError::SubscriptionFailed(
+                "asset_ids cannot be empty: at least one asset ID must be provided for subscription"
+                    .to_owned(),
+            )
+            .into());
+        }
+
+        // The connections are only changed under this lock, and a poisoned lock still holds a
+        // list of valid connections
+        let mut shards = lock(&self.shards);
+        let mut routes = lock(&self.routes);
+
+        let mut loads: Vec<usize> = shards
+            .connections
+            .iter()
+            .map(|shard| {
+                if shards.draining.contains(&shard.id) {
+                    usize::MAX
+                } else {
+                    shard.channel.subscriptions.subscribed_asset_count()
+                }
+            })
+            .collect();
+        let mut groups: Vec<Vec<U256>> = vec![Vec::new(); loads.len()];
+        for &asset_id in &asset_ids {
+            let owner = routes
+                .get(&asset_id)
+                .and_then(|route| shards.position(route.owner))
+                .or_else(|| groups.iter().position(|group| group.contains(&asset_id)));
+
+            let index = match owner {
+                Some(index) => index,
+                None => place(
+                    &mut loads,
+                    self.config.max_assets_per_connection,
+                    self.config.max_market_connections,
+                )
+                .ok_or_else(|| {
+                    WsError::SubscriptionFailed(format!(
+                        "market channel is full: {} connections of {} assets each are in use",
+                        self.config.max_market_connections,
+                        self.config.max_assets_per_connection.unwrap_or(usize::MAX)
+                    ))
+                })?,
+            };
+
+            if index == groups.len() {
+                groups.push(Vec::new());
+            }
+            groups[index].push(asset_id);
+        }
+
+        while shards.connections.len() < groups.len() {
+            let channel = ChannelResources::new(self.endpoint.clone(), self.config.clone())?;
+            let id = shards.next_id;
+            shards.next_id += 1;
+            shards.connections.push(Shard { id, channel });
+        }
+
+        let mut listeners = SelectAll::new();
+        let mut subscribed: Vec<(&Shard, Vec<U256>)> = Vec::new();
+        for (shard, group) in shards.connections.iter().zip(groups) {
+            if group.is_empty() {
+                continue;
+            }
+
+            match shard
+                .channel
+                .subscriptions
+                .add_market(group.clone(), custom_features)
+            {
+                Ok(()) => {
+                    listeners.push(shard.listener(asset_ids.clone(), custom_features));
+                    subscribed.push((shard, group));
+                }
+                Err(e) => {
+                    // Release the assets already subscribed on other connections, so that the
+                    // failed subscription leaves no reference behind
+                    for (shard, group) in subscribed {
+                        _ = shard.channel.subscriptions.unsubscribe_market(&group);
+                    }
+                    shards.connections.retain(Shard::is_used);
+
+                    return Err(e);
+                }
+            }
+        }
+
+        let mut sources = HashMap::new();
+        for (shard, group) in &subscribed {
+            for &asset_id in group {
+                let route = routes.entry(asset_id).or_insert(Route {
+                    owner: shard.id,
+                    moving_from: None,
+                });
+                sources.insert(asset_id, route.owner);
+            }
+        }
+        drop(routes);
+
+        #[cfg(feature = "tracing")]
+        tracing::debug!(
+            connections = shards.connections.len(),
+            streams = listeners.len(),
+            "Subscribed to sharded market channel"
+        );
+
+        let (listeners_tx, mut listeners_rx) = mpsc::unbounded_channel();
+        let reader = Reader {
+            asset_ids,
+            custom_features,
+            shards: subscribed.iter().map(|(shard, _)| shard.id).collect(),
+            listeners: listeners_tx,
+        };
+        shards
+            .readers
+            .retain(|reader| !reader.listeners.is_closed());
+        shards.readers.push(reader);
+
+        let routes = Arc::clone(&self.routes);
+        Ok(stream! {
+            let mut switch = Switch {
+                sources,
+                books: HashMap::new(),
+            };
+
+            loop {
+                let (shard, event) = tokio::select! {
+                    // Connections are handed over before the ones they replace close
+                    biased;
+                    Some(listener) = listeners_rx.recv() => {
+                        listeners.push(listener);
+                        continue;
+                    }
+                    item = listeners.next() => match item {
+                        Some(item) => item,
+                        None => break,
+                    },
+                };
+
+                match event {
+                    WsEvent::Message(message) => {
+                        if switch.accept(&message, shard, &routes) {
+                            yield WsEvent::Message(message);
+                        }
+                    }
+                    event => yield event,
+                }
+            }
+        })
+    }
+
+    /// Unsubscribes from `asset_ids` on the connections carrying them, closes connections left
+    /// without assets, and drains partly filled connections into others that have room.
+    pub(crate) fn unsubscribe(&self, asset_ids: &[U256]) -> Result<()> {
+        if asset_ids.is_empty() {
+            return Err(WsError::SubscriptionFailed(
+                "asset_ids cannot be empty: at least one asset ID must be provided for unsubscription"
+                    .to_owned(),
+            )
+            .into());
+        }
+
+        let mut shards = lock(&self.shards);
+        let mut routes = lock(&self.routes);
+
+        for shard in &shards.connections {
+            let owned: Vec<U256> = asset_ids
+                .iter()
+                .filter(|asset_id| {
+                    routes
+                        .get(asset_id)
+                        .is_some_and(|route| route.owner == shard.id)
+                })
+                .copied()
+                .collect();
+            if !owned.is_empty() {
+                shard.channel.subscriptions.unsubscribe_market(&owned)?;
+            }
+        }
+
+        routes.retain(|asset_id, route| {
+            shards
+                .shard(route.owner)
+                .is_some_and(|shard| shard.channel.subscriptions.is_asset_subscribed(asset_id))
+        });
+        shards.connections.retain(Shard::is_used);
+
+        if let Some(max_assets) = self.config.max_assets_per_connection {
+            while let Some(source) = shards.drain_candidate(&routes, max_assets) {
+                self.drain(&mut shards, &mut routes, source, max_assets);
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Moves the assets of the connection `source` to the other connections, and closes it once
+    /// they have switched over.
+    fn drain(
+        &self,
+        shards: &mut Shards,
+        routes: &mut HashMap<U256, Route>,
+        source: u64,
+        max_assets: usize,
+    ) {
+        shards.draining.insert(source);
+        let Shards {
+            connections,
+            draining,
+            readers,
+            ..
+        } = shards;
+
+        let Some(source_shard) = connections.iter().find(|shard| shard.id == source) else {
+            return;
+        };
+        let assets: Vec<U256> = routes
+            .iter()
+            .filter(|(_, route)| route.owner == source)
+            .map(|(asset_id, _)| *asset_id)
+            .collect();
+        let targets: Vec<&Shard> = connections
+            .iter()
+            .filter(|shard| !draining.contains(&shard.id))
+            .collect();
+        let mut loads: Vec<usize> = targets
+            .iter()
+            .map(|shard| shard.channel.subscriptions.subscribed_asset_count())
+            .collect();
+        let custom_features = source_shard.channel.subscriptions.custom_features();
+
+        let mut moves = Vec::new();
+        for asset_id in assets {
+            let Some(target) = place(&mut loads, Some(max_assets), targets.len()) else {
+                break;
+            };
+            let target = targets[target];
+            let refs = source_shard.channel.subscriptions.market_refs(&asset_id);
+
+            // Read the target before subscribing, so that its first snapshot is not missed
+            let snapshots = target.channel.connection.subscribe();
+            for reader in readers.iter_mut() {
+                if reader.asset_ids.contains(&asset_id) && reader.shards.insert(target.id) {
+                    let listener =
+                        target.listener(reader.asset_ids.clone(), reader.custom_features);
+                    _ = reader.listeners.send(listener);
+                }
+            }
+
+            if let Err(e) =
+                target
+                    .channel
+                    .subscriptions
+                    .adopt_market(asset_id, refs, custom_features)
+            {
+                #[cfg(feature = "tracing")]
+                tracing::warn!(%e, %asset_id, "Failed to move market asset to another connection");
+                #[cfg(not(feature = "tracing"))]
+                let _: &_ = &e;
+
+                _ = target.channel.subscriptions.release_market(&[asset_id]);
+                continue;
+            }
+
+            routes.insert(
+                asset_id,
+                Route {
+                    owner: target.id,
+                    moving_from: Some(source),
+                },
+            );
+            moves.push((asset_id, snapshots));
+        }
+
+        #[cfg(feature = "tracing")]
+        tracing::debug!(
+            connection = source,
+            assets = moves.len(),
+            "Draining market channel connection"
+        );
+
+        tokio::spawn(handover(
+            Arc::clone(&self.shards),
+            Arc::clone(&self.routes),
+            source,
+            moves,
+        ));
+    }
+
+    /// Returns receivers for the lifecycle events of the open connections, opening one if there
+    /// is none.
+    pub(crate) fn events(&self) -> Result<Vec<broadcast::Receiver<ConnectionEvent>>> {
+        let mut shards = lock(&self.shards);
+        if shards.connections.is_empty() {
+            let channel = ChannelResources::new(self.endpoint.clone(), self.config.clone())?;
+            let id = shards.next_id;
+            shards.next_id += 1;
+            shards.connections.push(Shard { id, channel });
+        }
+
+        Ok(shards
+            .connections
+            .iter()
+            .map(|shard| shard.channel.connection.events())
+            .collect())
+    }
+
+    /// Returns the state of the first connection that is not connected, or of the first
+    /// connection if they all are.
+    pub(crate) fn connection_state(&self) -> ConnectionState {
+        let shards = lock(&self.shards);
+        let states: Vec<ConnectionState> = shards
+            .connections
+            .iter()
+            .map(|shard| shard.channel.connection_state())
+            .collect();
+
+        states
+            .iter()
+            .find(|state| !state.is_connected())
+            .or_else(|| states.first())
+            .copied()
+            .unwrap_or(ConnectionState::Disconnected)
+    }
+
+    /// Returns the number of open connections.
+    pub(crate) fn connection_count(&self) -> usize {
+        lock(&self.shards).connections.len()
+    }
+
+    /// Returns the number of active subscriptions across connections.
+    pub(crate) fn subscription_count(&self) -> usize {
+        lock(&self.shards)
+            .connections
+            .iter()
+            .map(|shard| shard.channel.subscriptions.subscription_count())
+            .sum()
+    }
+}
+
+impl Shards {
+    fn position(&self, id: u64) -> Option<usize> {
+        self.connections.iter().position(|shard| shard.id == id)
+    }
+
+    fn shard(&self, id: u64) -> Option<&Shard> {
+        self.connections.iter().find(|shard| shard.id == id)
+    }
+
+    /// Picks the least loaded connection whose assets the other connections have room for.
+    /// Connections taking part in a move are left alone until it completes.
+    fn drain_candidate(&self, routes: &HashMap<U256, Route>, max_assets: usize) -> Option<u64> {
+        let busy: HashSet<u64> = routes
+            .values()
+            .filter_map(|route| route.moving_from.map(|from| [from, route.owner]))
+            .flatten()
+            .chain(self.draining.iter().copied())
+            .collect();
+        let loads: Vec<(u64, usize)> = self
+            .connections
+            .iter()
+            .filter(|shard| !self.draining.contains(&shard.id))
+            .map(|shard| {
+                let load = shard.channel.subscriptions.subscribed_asset_count();
+                (shard.id, load)
+            })
+            .collect();
+
+        // The newest connection goes first among equally loaded ones
+        let (source, load) = loads
+            .iter()
+            .rev()
+            .filter(|(id, load)| *load > 0 && !busy.contains(id))
+            .min_by_key(|(_, load)| *load)
+            .copied()?;
+        let room: usize = loads
+            .iter()
+            .filter(|(id, _)| *id != source)
+            .map(|(_, load)| max_assets.saturating_sub(*load))
+            .sum();
+
+        (room >= load).then_some(source)
+    }
+}
+
+impl Shard {
+    fn is_used(&self) -> bool {
+        self.channel
+            .subscriptions
+            .has_subscriptions(ChannelType::Market)
+    }
+
+    fn listener(&self, asset_ids: Vec<U256>, custom_features: bool) -> Listener {
+        let id = self.id;
+        Box::pin(
+            self.channel
+                .subscriptions
+                .market_listener(asset_ids, custom_features)
+                .map(move |event| (id, event)),
+        )
+    }
+}
+
+/// Which connection a stream takes the messages of each of its assets from.
+struct Switch {
+    sources: HashMap<U256, u64>,
+    /// Timestamp and hash of the last `book` snapshot yielded for each asset
+    books: HashMap<U256, (i64, Option<String>)>,
+}
+
+impl Switch {
+    /// Whether `message`, received from the connection `shard`, is yielded.
+    ///
+    /// An asset's messages are taken from a single connection. When the asset moves, the stream
+    /// switches over at the first `book` snapshot from the new connection, or at any message once
+    /// the old connection no longer carries the asset.
+    fn accept(
+        &mut self,
+        message: &WsMessage,
+        shard: u64,
+        routes: &Mutex<HashMap<U256, Route>>,
+    ) -> bool {
+        let mut accepted = false;
+
+        for asset_id in message_assets(message) {
+            let Some(source) = self.sources.get_mut(&asset_id) else {
+                continue;
+            };
+
+            if *source != shard {
+                let route = lock(routes).get(&asset_id).copied();
+                let switches = route.is_some_and(|route| {
+                    route.owner == shard
+                        && (route.moving_from.is_none() || matches!(message, WsMessage::Book(_)))
+                });
+                if !switches {
+                    continue;
+                }
+                *source = shard;
+
+                // Both connections send the snapshot that completes a switch when the asset was
+                // already subscribed on the new one
+                if let WsMessage::Book(book) = message
+                    && self.books.get(&asset_id) == Some(&(book.timestamp, book.hash.clone()))
+                {
+                    continue;
+                }
+            }
+
+            if let WsMessage::Book(book) = message {
+                self.books
+                    .insert(asset_id, (book.timestamp, book.hash.clone()));
+            }
+            accepted = true;
+        }
+
+        accepted
+    }
+}
+
+/// Waits for the first `book` snapshot of each moved asset on the connection it moved to, then
+/// unsubscribes the assets from the drained connection `source` and closes it if it is empty.
+async fn handover(
+    shards: Arc<Mutex<Shards>>,
+    routes: Arc<Mutex<HashMap<U256, Route>>>,
+    source: u64,
+    moves: Vec<(U256, Receiver<WsMessage>)>,
+) {
+    let deadline = Instant::now() + HANDOVER_TIMEOUT;
+    let mut moved = Vec::with_capacity(moves.len());
+    for (asset_id, mut snapshots) in moves {
+        moved.push(asset_id);
+        _ = timeout_at(deadline, async {
+            loop {
+                match snapshots.recv().await {
+                    Ok(WsMessage::Book(book)) if book.asset_id == asset_id => break,
+                    Ok(_) | Err(RecvError::Lagged(_)) => {}
+                    Err(RecvError::Closed) => break,
+                }
+            }
+        })
+        .await;
+    }
+
+    let mut shards = lock(&shards);
+    let mut routes = lock(&routes);
+
+    for asset_id in &moved {
+        if let Some(route) = routes.get_mut(asset_id)
+            && route.moving_from == Some(source)
+        {
+            route.moving_from = None;
+        }
+    }
+
+    if let Some(shard) = shards.shard(source)
+        && !moved.is_empty()
+    {
+        match shard.channel.subscriptions.release_market(&moved) {
+            Ok(subscriptions) => {
+                for (id, info) in subscriptions {
+                    let owner = moved
+                        .iter()
+                        .find_map(|asset_id| routes.get(asset_id))
+                        .and_then(|route| shards.shard(route.owner));
+                    if let Some(owner) = owner {
+                        owner
+                            .channel
+                            .subscriptions
+                            .adopt_subscriptions(vec![(id, info)]);
+                    }
+                }
+            }
+            Err(e) => {
+                #[cfg(feature = "tracing")]
+                tracing::warn!(%e, "Failed to unsubscribe moved assets from drained connection");
+                #[cfg(not(feature = "tracing"))]
+                let _: &_ = &e;
+            }
+        }
+    }
+
+    shards.draining.remove(&source);
+    shards.connections.retain(Shard::is_used);
+}
+
+fn lock<T>(mutex: &Mutex<T>) -> MutexGuard<'_, T> {
+    mutex.lock().unwrap_or_else(PoisonError::into_inner)
+}
+
+/// Picks the connection for a new asset given the number of assets each connection carries,
+/// and counts the asset in. Returns `loads.len()` when a new connection must be opened, or
+/// `None` when every connection is full and no more can be opened.
+fn place(
+    loads: &mut Vec<usize>,
+    max_assets: Option<usize>,
+    max_connections: usize,
+) -> Option<usize> {
+    let Some(max_assets) = max_assets else {
+        // Without a limit, every asset shares a single connection
+        if loads.is_empty() {
+            loads.push(0);
+        }
+        loads[0] += 1;
+        return Some(0);
+    };
+
+    let least_loaded = loads
+        .iter()
+        .enumerate()
+        .filter(|(_, load)| **load < max_assets)
+        .min_by_key(|(_, load)| **load)
+        .map(|(index, _)| index);
+
+    let index = match least_loaded {
+        Some(index) => index,
+        None if loads.len() < max_connections.max(1) => {
+            loads.push(0);
+            loads.len() - 1
+        }
+        None => return None,
+    };
+    loads[index] += 1;
+
+    Some(index)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn place_should_fill_least_loaded_connection_first() {
+        let mut loads = vec![2, 1];
+        assert_eq!(place(&mut loads, Some(3), 3), Some(1));
+        assert_eq!(place(&mut loads, Some(3), 3), Some(0));
+        assert_eq!(place(&mut loads, Some(3), 3), Some(1));
+        assert_eq!(loads, vec![3, 3]);
+
+        // Every connection is full, so a new one is opened until the pool is exhausted
+        assert_eq!(place(&mut loads, Some(3), 3), Some(2));
+        assert_eq!(place(&mut loads, Some(3), 3), Some(2));
+        assert_eq!(place(&mut loads, Some(3), 3), Some(2));
+        assert_eq!(place(&mut loads, Some(3), 3), None);
+    }
+
+    #[test]
+    fn place_without_limit_should_use_one_connection() {
+        let mut loads = Vec::new();
+        for _ in 0..5 {
+            assert_eq!(place(&mut loads, None, 3), Some(0));
+        }
+        assert_eq!(loads, vec![5]);
+    }
+}
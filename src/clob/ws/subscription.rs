@@ -5,23 +5,26 @@
 
 use std::collections::{HashMap, HashSet};
 use std::sync::atomic::{AtomicBool, Ordering};
-use std::sync::{Arc, PoisonError, RwLock};
-use std::time::Instant;
+use std::sync::{Arc, Mutex, PoisonError, RwLock};
+use std::time::{Duration, Instant};
 
-use async_stream::try_stream;
+use async_stream::stream;
 use dashmap::{DashMap, Entry};
-use futures::Stream;
+use futures::{Stream, StreamExt as _};
 use tokio::sync::broadcast::error::RecvError;
 
 use super::interest::{InterestTracker, MessageInterest};
 use super::types::request::SubscriptionRequest;
-use super::types::response::WsMessage;
+use super::types::response::{PriceChangeBatchEntry, WsMessage};
 use crate::Result;
 use crate::auth::Credentials;
 use crate::types::{B256, U256};
 use crate::ws::ConnectionManager;
 use crate::ws::WsError;
-use crate::ws::connection::ConnectionState;
+use crate::ws::connection::{ConnectionEvent, ConnectionState};
+
+/// Shortest time between two subscriptions re-sent for the same asset after streams lagged.
+const LAG_RESYNC_INTERVAL: Duration = Duration::from_secs(1);
 
 /// What a subscription is targeting.
 #[non_exhaustive]
@@ -62,6 +65,29 @@ impl SubscriptionInfo {
     }
 }
 
+/// An item of a market subscription: either a message, or a notice that earlier messages may no
+/// longer reflect the server's state.
+#[non_exhaustive]
+#[derive(Debug, Clone)]
+#[expect(
+    clippy::large_enum_variant,
+    reason = "Messages are the common case, boxing them would cost an allocation per message"
+)]
+pub enum WsEvent {
+    /// A message for one of the subscribed assets.
+    Message(WsMessage),
+    /// The subscriber fell behind and `skipped` messages were dropped. Unless
+    /// [`Config::resync_after_lag`](crate::ws::config::Config::resync_after_lag) is disabled, the
+    /// subscription has been re-sent and fresh `book` snapshots follow.
+    Lagged {
+        /// Number of messages dropped, for any asset.
+        skipped: u64,
+    },
+    /// The connection was re-established after a disconnect. Messages sent in between were
+    /// missed, and the subscription has been re-sent so that fresh `book` snapshots follow.
+    Reconnected,
+}
+
 #[non_exhaustive]
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 pub enum ChannelType {
@@ -77,13 +103,17 @@ pub struct SubscriptionManager {
     active_subs: DashMap<String, SubscriptionInfo>,
     interest: Arc<InterestTracker>,
     /// Subscribed assets with reference counts (for multiplexing)
-    subscribed_assets: DashMap<U256, usize>,
+    subscribed_assets: Arc<DashMap<U256, usize>>,
     /// Subscribed markets with reference counts (for multiplexing)
     subscribed_markets: DashMap<B256, usize>,
     last_auth: Arc<RwLock<Option<Credentials>>>,
     /// Track if custom features were enabled for any market subscription
     /// (enables `best_bid_ask`, `new_market`, `market_resolved` messages)
     custom_features_enabled: AtomicBool,
+    /// Whether market subscribers that lag re-send their subscription
+    resync_after_lag: bool,
+    /// When each asset was last re-sent after a lag, shared by the streams of this connection
+    lag_resyncs: Arc<Mutex<HashMap<U256, Instant>>>,
 }
 
 impl SubscriptionManager {
@@ -97,13 +127,23 @@ impl SubscriptionManager {
             connection,
             active_subs: DashMap::new(),
             interest,
-            subscribed_assets: DashMap::new(),
+            subscribed_assets: Arc::default(),
             subscribed_markets: DashMap::new(),
             last_auth: Arc::new(RwLock::new(None)),
             custom_features_enabled: AtomicBool::new(false),
+            resync_after_lag: true,
+            lag_resyncs: Arc::default(),
         }
     }
 
+    /// Sets whether market subscribers that lag re-send their subscription to get fresh `book`
+    /// snapshots. Enabled by default.
+    #[must_use]
+    pub fn resync_after_lag(mut self, resync: bool) -> Self {
+        self.resync_after_lag = resync;
+        self
+    }
+
     /// Start the reconnection handler that re-subscribes on connection recovery.
     pub fn start_reconnection_handler(self: &Arc<Self>) {
         let this = Arc::clone(self);
@@ -128,6 +168,7 @@ impl SubscriptionManager {
                             #[cfg(feature = "tracing")]
                             tracing::debug!("WebSocket reconnected, re-establishing subscriptions");
                             this.resubscribe_all();
+                            this.connection.emit(ConnectionEvent::Resubscribed);
                         }
                         was_connected = true;
                     }
@@ -208,12 +249,31 @@ impl SubscriptionManager {
     /// When `custom_features` is true, enables receiving additional message types:
     /// `best_bid_ask`, `new_market`, `market_resolved`.
     ///
+    /// If the stream falls behind, it yields a [`WsError::Lagged`] error and carries on.
+    ///
     /// This will fail if `asset_ids` is empty.
     pub fn subscribe_market_with_options(
         &self,
         asset_ids: Vec<U256>,
         custom_features: bool,
     ) -> Result<impl Stream<Item = Result<WsMessage>> + use<>> {
+        let events = self.subscribe_market_events(asset_ids, custom_features)?;
+
+        Ok(market_messages(events))
+    }
+
+    /// Subscribe to public market data channel, yielding a [`WsEvent`] for every message and
+    /// every gap in the stream.
+    ///
+    /// When `custom_features` is true, enables receiving additional message types:
+    /// `best_bid_ask`, `new_market`, `market_resolved`.
+    ///
+    /// This will fail if `asset_ids` is empty.
+    pub fn subscribe_market_events(
+        &self,
+        asset_ids: Vec<U256>,
+        custom_features: bool,
+    ) -> Result<impl Stream<Item = WsEvent> + use<>> {
         if asset_ids.is_empty() {
             return Err(WsError::SubscriptionFailed(
                 "asset_ids cannot be empty: at least one asset ID must be provided for subscription"
@@ -222,6 +282,14 @@ impl SubscriptionManager {
             .into());
         }
 
+        self.add_market(asset_ids.clone(), custom_features)?;
+
+        Ok(self.market_listener(asset_ids, custom_features))
+    }
+
+    /// Takes a reference to each of `asset_ids`, subscribing to the new ones, and registers the
+    /// subscription.
+    pub(crate) fn add_market(&self, asset_ids: Vec<U256>, custom_features: bool) -> Result<()> {
         self.interest.add(MessageInterest::MARKET);
 
         // Track if custom features are enabled (for re-subscription on reconnect)
@@ -275,58 +343,110 @@ impl SubscriptionManager {
         self.active_subs.insert(
             sub_id,
             SubscriptionInfo {
-                target: SubscriptionTarget::Assets(asset_ids.clone()),
+                target: SubscriptionTarget::Assets(asset_ids),
                 created_at: Instant::now(),
             },
         );
 
-        // Create filtered stream with its own receiver
+        Ok(())
+    }
+
+    /// Yields the events of this connection for `asset_ids`, without subscribing to them.
+    ///
+    /// Messages are yielded for any of `asset_ids`, but gaps in the stream are only reported,
+    /// and lags only re-sent, for the assets this connection carries at the time.
+    pub(crate) fn market_listener(
+        &self,
+        asset_ids: Vec<U256>,
+        custom_features: bool,
+    ) -> impl Stream<Item = WsEvent> + use<> {
         let mut rx = self.connection.subscribe();
-        let asset_ids_set: HashSet<U256> = asset_ids.into_iter().collect();
+        // State changes can be coalesced while the stream is not polled, so reconnections are
+        // told apart by counting connections instead
+        let mut state_rx = self.connection.state_receiver();
+        state_rx.mark_unchanged();
+        let mut connections = self.connection.connection_count();
+        let connection = self.connection.clone();
+        let resync_after_lag = self.resync_after_lag;
+        let subscribed_assets = Arc::clone(&self.subscribed_assets);
+        let lag_resyncs = Arc::clone(&self.lag_resyncs);
+        let asset_ids_set: HashSet<U256> = asset_ids.iter().copied().collect();
+
+        stream! {
+            let mut watching_state = true;
 
-        Ok(try_stream! {
             loop {
-                match rx.recv().await {
-                    Ok(msg) => {
-                        // Filter messages by asset_id
-                        let should_yield = match &msg {
-                            WsMessage::Book(book) => asset_ids_set.contains(&book.asset_id),
-                            WsMessage::PriceChange(price) => {
-                                price
-                                    .price_changes
-                                    .iter()
-                                    .any(|pc| asset_ids_set.contains(&pc.asset_id))
-                            },
-                            WsMessage::LastTradePrice(ltp) => asset_ids_set.contains(&ltp.asset_id),
-                            WsMessage::TickSizeChange(tsc) => asset_ids_set.contains(&tsc.asset_id),
-                            WsMessage::BestBidAsk(bba) => asset_ids_set.contains(&bba.asset_id),
-                            WsMessage::NewMarket(nm) => {
-                                nm.asset_ids.iter().any(|id| asset_ids_set.contains(id))
-                            },
-                            WsMessage::MarketResolved(mr) => {
-                                mr.asset_ids.iter().any(|id| asset_ids_set.contains(id))
-                            },
-                            _ => false,
-                        };
+                let received = tokio::select! {
+                    received = rx.recv() => received,
+                    changed = state_rx.changed(), if watching_state => {
+                        if changed.is_err() {
+                            watching_state = false;
+                        } else if state_rx.borrow_and_update().is_connected() {
+                            let count = connection.connection_count();
+                            let carried = asset_ids
+                                .iter()
+                                .any(|asset_id| subscribed_assets.contains_key(asset_id));
+                            if count > connections.max(1) && carried {
+                                yield WsEvent::Reconnected;
+                            }
+                            connections = count;
+                        }
+                        continue;
+                    }
+                };
 
-                        if should_yield {
-                            yield msg
+                match received {
+                    Ok(msg) => {
+                        if is_for_assets(&msg, &asset_ids_set) {
+                            yield WsEvent::Message(msg);
                         }
                     }
                     Err(RecvError::Lagged(n)) => {
+                        let carried: Vec<U256> = asset_ids
+                            .iter()
+                            .filter(|asset_id| subscribed_assets.contains_key(asset_id))
+                            .copied()
+                            .collect();
+                        if carried.is_empty() {
+                            continue;
+                        }
+
                         #[cfg(feature = "tracing")]
                         tracing::warn!("Subscription lagged, missed {n} messages");
-                        Err(WsError::Lagged { count: n })?;
+
+                        // Streams of the same assets tend to lag together, so the assets re-sent
+                        // shortly before by another stream are left out
+                        let due = if resync_after_lag {
+                            due_for_resync(&lag_resyncs, carried)
+                        } else {
+                            Vec::new()
+                        };
+                        if !due.is_empty() {
+                            let mut request = SubscriptionRequest::market(due);
+                            if custom_features {
+                                request = request.with_custom_features(true);
+                            }
+                            if let Err(e) = connection.send(&request) {
+                                #[cfg(feature = "tracing")]
+                                tracing::warn!(%e, "Failed to resync lagged market subscription");
+                                #[cfg(not(feature = "tracing"))]
+                                let _: &_ = &e;
+                            }
+                        }
+
+                        yield WsEvent::Lagged { skipped: n };
                     }
                     Err(RecvError::Closed) => {
                         break;
                     }
                 }
             }
-        })
+        }
     }
 
     /// Subscribe to authenticated user channel.
+    ///
+    /// If the stream falls behind, it yields a [`WsError::Lagged`] error and carries on.
     pub fn subscribe_user(
         &self,
         markets: Vec<B256>,
@@ -391,18 +511,18 @@ impl SubscriptionManager {
         // Create stream for user messages
         let mut rx = self.connection.subscribe();
 
-        Ok(try_stream! {
+        Ok(stream! {
             loop {
                 match rx.recv().await {
                     Ok(msg) => {
                         if msg.is_user() {
-                            yield msg;
+                            yield Ok(msg);
                         }
                     }
                     Err(RecvError::Lagged(n)) => {
                         #[cfg(feature = "tracing")]
                         tracing::warn!("Subscription lagged, missed {n} messages");
-                        Err(WsError::Lagged { count: n })?;
+                        yield Err(WsError::Lagged { count: n }.into());
                     }
                     Err(RecvError::Closed) => {
                         break;
@@ -440,6 +560,95 @@ impl SubscriptionManager {
         }
     }
 
+    /// Check if a market data stream is using `asset_id`.
+    #[must_use]
+    pub fn is_asset_subscribed(&self, asset_id: &U256) -> bool {
+        self.subscribed_assets.contains_key(asset_id)
+    }
+
+    /// Get the number of distinct assets subscribed to on the market channel.
+    #[must_use]
+    pub fn subscribed_asset_count(&self) -> usize {
+        self.subscribed_assets.len()
+    }
+
+    /// Takes over `asset_id` from another connection with its `refs` references, subscribing to
+    /// it unless this connection already carries it.
+    pub(crate) fn adopt_market(
+        &self,
+        asset_id: U256,
+        refs: usize,
+        custom_features: bool,
+    ) -> Result<()> {
+        self.interest.add(MessageInterest::MARKET);
+        if custom_features {
+            self.custom_features_enabled.store(true, Ordering::Relaxed);
+        }
+
+        match self.subscribed_assets.entry(asset_id) {
+            Entry::Occupied(mut o) => {
+                *o.get_mut() += refs;
+                Ok(())
+            }
+            Entry::Vacant(v) => {
+                v.insert(refs);
+
+                let mut request = SubscriptionRequest::market(vec![asset_id]);
+                if custom_features {
+                    request = request.with_custom_features(true);
+                }
+                self.connection.send(&request)
+            }
+        }
+    }
+
+    /// Hands `asset_ids` over to another connection: drops every reference to them and
+    /// unsubscribes, returning the subscriptions left without assets on this connection.
+    pub(crate) fn release_market(
+        &self,
+        asset_ids: &[U256],
+    ) -> Result<Vec<(String, SubscriptionInfo)>> {
+        let released: Vec<U256> = asset_ids
+            .iter()
+            .filter(|asset_id| self.subscribed_assets.remove(asset_id).is_some())
+            .copied()
+            .collect();
+
+        let mut moved = Vec::new();
+        self.active_subs.retain(|id, info| {
+            let emptied = matches!(&info.target, SubscriptionTarget::Assets(assets)
+                if !assets.iter().any(|a| self.subscribed_assets.contains_key(a)));
+            if emptied {
+                moved.push((id.clone(), info.clone()));
+            }
+            !emptied
+        });
+
+        if !released.is_empty() {
+            let request = SubscriptionRequest::market_unsubscribe(released);
+            self.connection.send(&request)?;
+        }
+
+        Ok(moved)
+    }
+
+    /// Registers subscriptions moved over from another connection.
+    pub(crate) fn adopt_subscriptions(&self, subscriptions: Vec<(String, SubscriptionInfo)>) {
+        for (id, info) in subscriptions {
+            self.active_subs.insert(id, info);
+        }
+    }
+
+    /// Number of streams using `asset_id` on this connection.
+    pub(crate) fn market_refs(&self, asset_id: &U256) -> usize {
+        self.subscribed_assets.get(asset_id).map_or(0, |refs| *refs)
+    }
+
+    /// Whether custom features were enabled for any market subscription.
+    pub(crate) fn custom_features(&self) -> bool {
+        self.custom_features_enabled.load(Ordering::Relaxed)
+    }
+
     /// Unsubscribe from market data for specific assets.
     ///
     /// This decrements the reference count for each asset. Only sends an unsubscribe
@@ -561,3 +770,57 @@ impl SubscriptionManager {
         Ok(())
     }
 }
+
+/// Keeps the assets of `asset_ids` not re-sent within [`LAG_RESYNC_INTERVAL`], and records them
+/// as re-sent now.
+fn due_for_resync(
+    lag_resyncs: &Mutex<HashMap<U256, Instant>>,
+    mut asset_ids: Vec<U256>,
+) -> Vec<U256> {
+    let now = Instant::now();
+    let mut lag_resyncs = lag_resyncs.lock().unwrap_or_else(PoisonError::into_inner);
+    lag_resyncs.retain(|_, resynced| now.duration_since(*resynced) < LAG_RESYNC_INTERVAL);
+
+    asset_ids.retain(|asset_id| !lag_resyncs.contains_key(asset_id));
+    for asset_id in &asset_ids {
+        lag_resyncs.insert(*asset_id, now);
+    }
+    asset_ids
+}
+
+/// Returns `true` if `msg` concerns any of `asset_ids`.
+fn is_for_assets(msg: &WsMessage, asset_ids: &HashSet<U256>) -> bool {
+    message_assets(msg).any(|id| asset_ids.contains(&id))
+}
+
+/// The assets a market channel message concerns.
+pub(crate) fn message_assets(msg: &WsMessage) -> impl Iterator<Item = U256> + '_ {
+    let (single, many, changes): (Option<U256>, &[U256], &[PriceChangeBatchEntry]) = match msg {
+        WsMessage::Book(book) => (Some(book.asset_id), &[], &[]),
+        WsMessage::PriceChange(price) => (None, &[], &price.price_changes),
+        WsMessage::LastTradePrice(ltp) => (Some(ltp.asset_id), &[], &[]),
+        WsMessage::TickSizeChange(tsc) => (Some(tsc.asset_id), &[], &[]),
+        WsMessage::BestBidAsk(bba) => (Some(bba.asset_id), &[], &[]),
+        WsMessage::NewMarket(nm) => (None, &nm.asset_ids, &[]),
+        WsMessage::MarketResolved(mr) => (None, &mr.asset_ids, &[]),
+        _ => (None, &[], &[]),
+    };
+
+    single
+        .into_iter()
+        .chain(many.iter().copied())
+        .chain(changes.iter().map(|pc| pc.asset_id))
+}
+
+/// Maps market `events` to messages, reporting a lag as a [`WsError::Lagged`] error.
+pub(crate) fn market_messages(
+    events: impl Stream<Item = WsEvent>,
+) -> impl Stream<Item = Result<WsMessage>> {
+    events.filter_map(|event| async move {
+        match event {
+            WsEvent::Message(msg) => Some(Ok(msg)),
+            WsEvent::Lagged { skipped } => Some(Err(WsError::Lagged { count: skipped }.into())),
+            WsEvent::Reconnected => None,
+        }
+    })
+}
@@ -0,0 +1,705 @@
+//! Order lifecycle tracking across REST responses and user channel events.
+//!
+//! [`OrderTracker`] follows orders from the [`PostOrderResponse`] returned when they are posted,
+//! through the `PLACEMENT`, `UPDATE` and `CANCELLATION` order events and the
+//! `MATCHED`/`MINED`/`CONFIRMED`/`FAILED` trade events of the user channel. Each change is
+//! reported as a [`Transition`], and the tracker keeps the latest [`TrackedOrder`] per order ID
+//! so that state, fill quantity and average fill price can be read at any time.
+//!
+//! Events sent while the user channel is reconnecting are never replayed by the server.
+//! [`OrderTracker::backfill`] re-reads tracked orders and their trades over REST to recover
+//! them, and [`Client::track_orders`](super::Client::track_orders) calls it automatically after
+//! every reconnect.
+
+use std::sync::Arc;
+
+use dashmap::DashMap;
+
+use super::types::response::{OrderMessage, OrderMessageType, TradeMessage, TradeMessageStatus};
+use crate::auth::Kind as AuthKind;
+use crate::auth::state::Authenticated;
+use crate::clob::Client as RestClient;
+use crate::clob::types::request::TradesRequest;
+use crate::clob::types::response::{OpenOrderResponse, PostOrderResponse, TradeResponse};
+use crate::clob::types::{OrderStatusType, Side, TradeStatusType};
+use crate::error::Error;
+use crate::types::{B256, Decimal, U256};
+
+/// Lifecycle state of a tracked order.
+#[non_exhaustive]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum OrderState {
+    /// The order is resting on the book without any fills.
+    Open,
+    /// The order was accepted, but its placement is delayed by the exchange.
+    Delayed,
+    /// Part of the order has been matched and the rest is still resting on the book.
+    PartiallyFilled,
+    /// The order has been matched in full.
+    Filled,
+    /// The order was cancelled, possibly after being partially filled.
+    Canceled,
+}
+
+impl OrderState {
+    /// Whether the order can no longer change state.
+    #[must_use]
+    pub const fn is_terminal(self) -> bool {
+        matches!(self, Self::Filled | Self::Canceled)
+    }
+}
+
+/// A single trade that matched (part of) a tracked order.
+#[non_exhaustive]
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Fill {
+    /// Trade identifier
+    pub trade_id: String,
+    /// Size of the tracked order matched by this trade
+    pub size: Decimal,
+    /// Price at which the tracked order was matched
+    pub price: Decimal,
+    /// Settlement status of the trade
+    pub status: TradeStatusType,
+}
+
+/// The latest known state of an order followed by an [`OrderTracker`].
+#[non_exhaustive]
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct TrackedOrder {
+    /// Order identifier
+    pub order_id: String,
+    /// Current lifecycle state
+    pub state: OrderState,
+    /// Market condition ID, once reported by the server
+    pub market: Option<B256>,
+    /// Asset/token identifier, once reported by the server
+    pub asset_id: Option<U256>,
+    /// Side of the order, once reported by the server
+    pub side: Option<Side>,
+    /// Limit price, once reported by the server
+    pub price: Option<Decimal>,
+    /// Original order size, once reported by the server
+    pub original_size: Option<Decimal>,
+    /// Size matched so far, as reported by order events
+    pub size_matched: Decimal,
+    /// Trades that matched this order, in the order they were first seen
+    pub fills: Vec<Fill>,
+}
+
+impl TrackedOrder {
+    fn new(order_id: String, state: OrderState) -> Self {
+        Self {
+            order_id,
+            state,
+            market: None,
+            asset_id: None,
+            side: None,
+            price: None,
+            original_size: None,
+            size_matched: Decimal::ZERO,
+            fills: Vec::new(),
+        }
+    }
+
+    /// Total filled size, excluding trades that failed to settle.
+    ///
+    /// This is the larger of the size reported by order events and the sum of the fills seen,
+    /// since either kind of event can arrive first.
+    #[must_use]
+    pub fn filled_size(&self) -> Decimal {
+        self.settled_fills()
+            .map(|fill| fill.size)
+            .sum::<Decimal>()
+            .max(self.size_matched)
+    }
+
+    /// Size-weighted average price of the fills seen, excluding trades that failed to settle.
+    #[must_use]
+    pub fn average_fill_price(&self) -> Option<Decimal> {
+        let size: Decimal = self.settled_fills().map(|fill| fill.size).sum();
+        if size.is_zero() {
+            return None;
+        }
+
+        let notional: Decimal = self
+            .settled_fills()
+            .map(|fill| fill.size * fill.price)
+            .sum();
+        Some(notional / size)
+    }
+
+    fn settled_fills(&self) -> impl Iterator<Item = &Fill> {
+        self.fills
+            .iter()
+            .filter(|fill| fill.status != TradeStatusType::Failed)
+    }
+
+    /// Moves to `state` and raises `size_matched`, returning a transition if either changed.
+    ///
+    /// Terminal states are never left, so late or replayed events cannot revive an order.
+    fn update(&mut self, state: OrderState, size_matched: Option<Decimal>) -> Option<Transition> {
+        let from = self.state;
+        let previous_size = self.size_matched;
+
+        if let Some(size_matched) = size_matched {
+            self.size_matched = self.size_matched.max(size_matched);
+        }
+        if !from.is_terminal() {
+            self.state = state;
+        }
+        // Orders that are open with fills are partially filled
+        if self.state == OrderState::Open && !self.size_matched.is_zero() {
+            self.state = OrderState::PartiallyFilled;
+        }
+
+        (self.state != from || self.size_matched != previous_size).then(|| Transition::Order {
+            order_id: self.order_id.clone(),
+            from,
+            to: self.state,
+            size_matched: self.size_matched,
+        })
+    }
+
+    /// Records a fill from trade `trade_id`, returning a transition if it is new or its status
+    /// changed.
+    fn fill(
+        &mut self,
+        trade_id: &str,
+        size: Decimal,
+        price: Decimal,
+        status: TradeStatusType,
+    ) -> Option<Transition> {
+        let from = match self.fills.iter_mut().find(|fill| fill.trade_id == trade_id) {
+            Some(fill) if fill.status == status => return None,
+            Some(fill) => Some(std::mem::replace(&mut fill.status, status.clone())),
+            None => {
+                self.fills.push(Fill {
+                    trade_id: trade_id.to_owned(),
+                    size,
+                    price,
+                    status: status.clone(),
+                });
+                None
+            }
+        };
+
+        Some(Transition::Trade {
+            order_id: self.order_id.clone(),
+            trade_id: trade_id.to_owned(),
+            from,
+            to: status,
+            size,
+            price,
+        })
+    }
+
+    fn set_details(&mut self, market: B256, asset_id: U256, side: Side, price: Decimal) {
+        self.market = Some(market);
+        self.asset_id = Some(asset_id);
+        self.side = Some(side);
+        self.price = Some(price);
+    }
+}
+
+/// A change to a tracked order.
+#[non_exhaustive]
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Transition {
+    /// The order changed state or was matched further.
+    Order {
+        /// Order identifier
+        order_id: String,
+        /// State before the event
+        from: OrderState,
+        /// State after the event
+        to: OrderState,
+        /// Size matched after the event
+        size_matched: Decimal,
+    },
+    /// A trade matching the order was first seen or changed settlement status.
+    Trade {
+        /// Order identifier
+        order_id: String,
+        /// Trade identifier
+        trade_id: String,
+        /// Settlement status before the event, or `None` for a new trade
+        from: Option<TradeStatusType>,
+        /// Settlement status after the event
+        to: TradeStatusType,
+        /// Size of the order matched by the trade
+        size: Decimal,
+        /// Price at which the order was matched
+        price: Decimal,
+    },
+}
+
+/// The outcome of [`OrderTracker::backfill`].
+#[non_exhaustive]
+#[derive(Debug, Default)]
+pub struct Backfill {
+    /// Transitions recovered from the orders and trades that were read
+    pub transitions: Vec<Transition>,
+    /// Errors of the requests that failed
+    pub errors: Vec<Error>,
+}
+
+/// Follows posted orders through their lifecycle.
+///
+/// The tracker is a cheap handle to shared state: clones observe and update the same orders.
+/// Only registered orders are tracked, and events for any other order are ignored.
+///
+/// # Example
+///
+/// ```rust, no_run
+/// use futures::StreamExt as _;
+/// use kuest_client_sdk::clob::ws::OrderTracker;
+/// # async fn example(
+/// #     rest: kuest_client_sdk::clob::Client<kuest_client_sdk::auth::state::Authenticated<kuest_client_sdk::auth::Normal>>,
+/// #     ws: kuest_client_sdk::clob::ws::Client<kuest_client_sdk::auth::state::Authenticated<kuest_client_sdk::auth::Normal>>,
+/// #     order: kuest_client_sdk::clob::types::SignedOrder,
+/// #     market: kuest_client_sdk::types::B256,
+/// # ) -> anyhow::Result<()> {
+///
+/// let tracker = OrderTracker::new();
+/// let mut transitions = Box::pin(ws.track_orders(&tracker, vec![market], rest.clone())?);
+///
+/// let response = rest.post_order(order).await?;
+/// if !tracker.register(&response) {
+///     println!("Order rejected: {:?}", response.api_error());
+/// }
+///
+/// while let Some(transition) = transitions.next().await {
+///     println!("{:?}", transition?);
+///     println!("{:?}", tracker.order(&response.order_id));
+/// }
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Clone, Debug, Default)]
+pub struct OrderTracker {
+    orders: Arc<DashMap<String, TrackedOrder>>,
+}
+
+impl OrderTracker {
+    /// Creates an empty tracker.
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Starts tracking the order posted with `response`.
+    ///
+    /// Returns `false`, without tracking anything, if the order was rejected.
+    #[must_use]
+    pub fn register(&self, response: &PostOrderResponse) -> bool {
+        if !response.success || response.order_id.is_empty() {
+            return false;
+        }
+
+        let mut order = self
+            .orders
+            .entry(response.order_id.clone())
+            .or_insert_with(|| TrackedOrder::new(response.order_id.clone(), OrderState::Open));
+        order.update(order_state(&response.status), None);
+        true
+    }
+
+    /// Stops tracking `order_id`, returning its last known state.
+    #[must_use]
+    pub fn remove(&self, order_id: &str) -> Option<TrackedOrder> {
+        self.orders.remove(order_id).map(|(_, order)| order)
+    }
+
+    /// Returns the latest known state of `order_id`, if it is tracked.
+    #[must_use]
+    pub fn order(&self, order_id: &str) -> Option<TrackedOrder> {
+        self.orders.get(order_id).map(|order| order.clone())
+    }
+
+    /// Returns the latest known state of every tracked order.
+    #[must_use]
+    pub fn orders(&self) -> Vec<TrackedOrder> {
+        self.orders.iter().map(|order| order.clone()).collect()
+    }
+
+    /// Applies a user channel order event.
+    #[must_use]
+    pub fn apply_order_message(&self, message: &OrderMessage) -> Option<Transition> {
+        let mut order = self.orders.get_mut(&message.id)?;
+
+        order.set_details(
+            message.market,
+            message.asset_id,
+            message.side,
+            message.price,
+        );
+        if message.original_size.is_some() {
+            order.original_size = message.original_size;
+        }
+
+        let state = match message.msg_type {
+            Some(OrderMessageType::Cancellation) => OrderState::Canceled,
+            _ => match (message.size_matched, message.original_size) {
+                (Some(matched), Some(original)) if !original.is_zero() && matched >= original => {
+                    OrderState::Filled
+                }
+                _ => OrderState::Open,
+            },
+        };
+
+        order.update(state, message.size_matched)
+    }
+
+    /// Applies a user channel trade event to every tracked order it matched.
+    #[must_use]
+    pub fn apply_trade_message(&self, message: &TradeMessage) -> Vec<Transition> {
+        let status = match &message.status {
+            TradeMessageStatus::Matched => TradeStatusType::Matched,
+            TradeMessageStatus::Mined => TradeStatusType::Mined,
+            TradeMessageStatus::Confirmed => TradeStatusType::Confirmed,
+            TradeMessageStatus::Retrying => TradeStatusType::Retrying,
+            TradeMessageStatus::Failed => TradeStatusType::Failed,
+            TradeMessageStatus::Unknown(status) => TradeStatusType::Unknown(status.clone()),
+        };
+        let makers = message
+            .maker_orders
+            .iter()
+            .map(|maker| (maker.order_id.as_str(), maker.matched_amount, maker.price));
+
+        self.apply_fills(
+            &message.id,
+            message.taker_order_id.as_deref(),
+            message.size,
+            message.price,
+            makers,
+            &status,
+        )
+    }
+
+    /// Applies an order read over REST with [`Client::order`](crate::clob::Client::order).
+    #[must_use]
+    pub fn apply_open_order(&self, response: &OpenOrderResponse) -> Option<Transition> {
+        let mut order = self.orders.get_mut(&response.id)?;
+
+        order.set_details(
+            response.market,
+            response.asset_id,
+            response.side,
+            response.price,
+        );
+        order.original_size = Some(response.original_size);
+
+        order.update(order_state(&response.status), Some(response.size_matched))
+    }
+
+    /// Applies a trade read over REST with [`Client::trades`](crate::clob::Client::trades).
+    #[must_use]
+    pub fn apply_trade(&self, response: &TradeResponse) -> Vec<Transition> {
+        let makers = response
+            .maker_orders
+            .iter()
+            .map(|maker| (maker.order_id.as_str(), maker.matched_amount, maker.price));
+
+        self.apply_fills(
+            &response.id,
+            Some(response.taker_order_id.as_str()),
+            response.size,
+            response.price,
+            makers,
+            &response.status,
+        )
+    }
+
+    /// Re-reads every order that is not yet settled, and the trades it is associated with, to
+    /// recover events missed while the user channel was disconnected.
+    ///
+    /// A failed request only skips the order or trade it was for: the transitions of everything
+    /// else are still applied and returned, along with the errors.
+    pub async fn backfill<K: AuthKind>(&self, client: &RestClient<Authenticated<K>>) -> Backfill {
+        let pending: Vec<String> = self
+            .orders
+            .iter()
+            .filter(|order| !order.state.is_terminal() || has_unsettled_fills(order))
+            .map(|order| order.order_id.clone())
+            .collect();
+
+        let mut backfill = Backfill::default();
+        for order_id in pending {
+            let response = match client.order(&order_id).await {
+                Ok(response) => response,
+                Err(e) => {
+                    backfill.errors.push(e);
+                    continue;
+                }
+            };
+            backfill
+                .transitions
+                .extend(self.apply_open_order(&response));
+
+            let settled = |trade_id: &String| {
+                self.orders.get(&order_id).is_some_and(|order| {
+                    order
+                        .fills
+                        .iter()
+                        .any(|fill| fill.trade_id == *trade_id && is_settled(&fill.status))
+                })
+            };
+            let trade_ids: Vec<&String> = response
+                .associate_trades
+                .iter()
+                .filter(|trade_id| !settled(trade_id))
+                .collect();
+
+            for trade_id in trade_ids {
+                let request = TradesRequest::builder().id(trade_id.as_str()).build();
+                match client.trades(&request, None).await {
+                    Ok(page) => {
+                        for trade in &page.data {
+                            backfill.transitions.extend(self.apply_trade(trade));
+                        }
+                    }
+                    Err(e) => backfill.errors.push(e),
+                }
+            }
+        }
+
+        backfill
+    }
+
+    fn apply_fills<'maker, I>(
+        &self,
+        trade_id: &str,
+        taker_order_id: Option<&str>,
+        size: Decimal,
+        price: Decimal,
+        makers: I,
+        status: &TradeStatusType,
+    ) -> Vec<Transition>
+    where
+        I: Iterator<Item = (&'maker str, Decimal, Decimal)>,
+    {
+        let mut transitions = Vec::new();
+
+        if let Some(taker_order_id) = taker_order_id
+            && let Some(mut order) = self.orders.get_mut(taker_order_id)
+        {
+            transitions.extend(order.fill(trade_id, size, price, status.clone()));
+        }
+
+        for (order_id, matched_amount, price) in makers {
+            if let Some(mut order) = self.orders.get_mut(order_id) {
+                transitions.extend(order.fill(trade_id, matched_amount, price, status.clone()));
+            }
+        }
+
+        transitions
+    }
+}
+
+fn order_state(status: &OrderStatusType) -> OrderState {
+    match status {
+        OrderStatusType::Matched => OrderState::Filled,
+        OrderStatusType::Canceled => OrderState::Canceled,
+        OrderStatusType::Delayed => OrderState::Delayed,
+        _ => OrderState::Open,
+    }
+}
+
+fn is_settled(status: &TradeStatusType) -> bool {
+    matches!(status, TradeStatusType::Confirmed | TradeStatusType::Failed)
+}
+
+fn has_unsettled_fills(order: &TrackedOrder) -> bool {
+    order.fills.iter().any(|fill| !is_settled(&fill.status))
+}
+
+#[cfg(test)]
+mod tests {
+    use rust_decimal_macros::dec;
+
+    use super::*;
+    use crate::auth::ApiKey;
+    use crate::clob::ws::types::response::MakerOrder;
+
+    const ORDER_ID: &str = "0xorder";
+
+    fn tracker() -> OrderTracker {
+        let tracker = OrderTracker::new();
+        let response = PostOrderResponse::builder()
+            .making_amount(Decimal::ZERO)
+            .taking_amount(Decimal::ZERO)
+            .order_id(ORDER_ID)
+            .status(OrderStatusType::Live)
+            .success(true)
+            .build();
+
+        assert!(tracker.register(&response));
+        tracker
+    }
+
+    fn order_message(msg_type: OrderMessageType, size_matched: Decimal) -> OrderMessage {
+        OrderMessage::builder()
+            .id(ORDER_ID.to_owned())
+            .market(B256::ZERO)
+            .asset_id(U256::from(1))
+            .side(Side::Buy)
+            .price(dec!(0.5))
+            .msg_type(msg_type)
+            .original_size(dec!(10))
+            .size_matched(size_matched)
+            .build()
+    }
+
+    fn trade_message(id: &str, status: TradeMessageStatus, price: Decimal) -> TradeMessage {
+        TradeMessage::builder()
+            .id(id.to_owned())
+            .market(B256::ZERO)
+            .asset_id(U256::from(1))
+            .side(Side::Sell)
+            .size(dec!(100))
+            .price(dec!(0.45))
+            .status(status)
+            .taker_order_id("0xtaker".to_owned())
+            .maker_orders(vec![
+                MakerOrder::builder()
+                    .asset_id(U256::from(1))
+                    .matched_amount(dec!(4))
+                    .order_id(ORDER_ID.to_owned())
+                    .outcome("Yes".to_owned())
+                    .owner(ApiKey::nil())
+                    .price(price)
+                    .build(),
+            ])
+            .build()
+    }
+
+    #[test]
+    fn register_should_ignore_rejected_orders() {
+        let tracker = OrderTracker::new();
+        let response = PostOrderResponse::builder()
+            .error_msg("not enough balance / allowance")
+            .making_amount(Decimal::ZERO)
+            .taking_amount(Decimal::ZERO)
+            .order_id("")
+            .status(OrderStatusType::Unmatched)
+            .success(false)
+            .build();
+
+        assert!(!tracker.register(&response));
+        assert!(tracker.orders().is_empty());
+    }
+
+    #[test]
+    fn order_messages_should_drive_state() {
+        let tracker = tracker();
+
+        assert_eq!(
+            tracker.apply_order_message(&order_message(OrderMessageType::Placement, dec!(0))),
+            None
+        );
+        assert_eq!(
+            tracker.apply_order_message(&order_message(OrderMessageType::Update, dec!(4))),
+            Some(Transition::Order {
+                order_id: ORDER_ID.to_owned(),
+                from: OrderState::Open,
+                to: OrderState::PartiallyFilled,
+                size_matched: dec!(4),
+            })
+        );
+        assert_eq!(
+            tracker.apply_order_message(&order_message(OrderMessageType::Cancellation, dec!(4))),
+            Some(Transition::Order {
+                order_id: ORDER_ID.to_owned(),
+                from: OrderState::PartiallyFilled,
+                to: OrderState::Canceled,
+                size_matched: dec!(4),
+            })
+        );
+
+        // A late update cannot reopen a cancelled order
+        _ = tracker.apply_order_message(&order_message(OrderMessageType::Update, dec!(4)));
+        let order = tracker.order(ORDER_ID).unwrap();
+        assert_eq!(order.state, OrderState::Canceled);
+        assert_eq!(order.original_size, Some(dec!(10)));
+    }
+
+    #[test]
+    fn order_message_should_fill_when_fully_matched() {
+        let tracker = tracker();
+
+        _ = tracker.apply_order_message(&order_message(OrderMessageType::Update, dec!(10)));
+
+        assert_eq!(tracker.order(ORDER_ID).unwrap().state, OrderState::Filled);
+    }
+
+    #[test]
+    fn trade_messages_should_track_fills() {
+        let tracker = tracker();
+
+        let transitions = tracker.apply_trade_message(&trade_message(
+            "t1",
+            TradeMessageStatus::Matched,
+            dec!(0.5),
+        ));
+        assert_eq!(
+            transitions,
+            vec![Transition::Trade {
+                order_id: ORDER_ID.to_owned(),
+                trade_id: "t1".to_owned(),
+                from: None,
+                to: TradeStatusType::Matched,
+                size: dec!(4),
+                price: dec!(0.5),
+            }]
+        );
+
+        // Repeated statuses are not reported again
+        assert!(
+            tracker
+                .apply_trade_message(&trade_message("t1", TradeMessageStatus::Matched, dec!(0.5)))
+                .is_empty()
+        );
+
+        _ = tracker.apply_trade_message(&trade_message(
+            "t2",
+            TradeMessageStatus::Matched,
+            dec!(0.6),
+        ));
+        _ = tracker.apply_trade_message(&trade_message(
+            "t1",
+            TradeMessageStatus::Confirmed,
+            dec!(0.5),
+        ));
+
+        let order = tracker.order(ORDER_ID).unwrap();
+        assert_eq!(order.filled_size(), dec!(8));
+        assert_eq!(order.average_fill_price(), Some(dec!(0.55)));
+        assert_eq!(order.fills[0].status, TradeStatusType::Confirmed);
+
+        _ = tracker.apply_trade_message(&trade_message(
+            "t2",
+            TradeMessageStatus::Failed,
+            dec!(0.6),
+        ));
+
+        let order = tracker.order(ORDER_ID).unwrap();
+        assert_eq!(order.filled_size(), dec!(4));
+        assert_eq!(order.average_fill_price(), Some(dec!(0.5)));
+    }
+
+    #[test]
+    fn untracked_orders_should_be_ignored() {
+        let tracker = OrderTracker::new();
+
+        assert_eq!(
+            tracker.apply_order_message(&order_message(OrderMessageType::Update, dec!(4))),
+            None
+        );
+        assert!(
+            tracker
+                .apply_trade_message(&trade_message("t1", TradeMessageStatus::Matched, dec!(0.5)))
+                .is_empty()
+        );
+    }
+}
@@ -1,14 +1,18 @@
+use std::borrow::Cow;
+
 use bon::Builder;
 use serde::Deserialize;
-use serde_json::Value;
+use serde::de::IgnoredAny;
+use serde_json::value::RawValue;
 use serde_with::{DisplayFromStr, NoneAsEmptyString, serde_as};
 #[cfg(feature = "tracing")]
 use tracing::warn;
 
 use crate::auth::ApiKey;
+use crate::clob::book_hash;
 use crate::clob::types::{Side, TraderSide};
 use crate::clob::ws::interest::MessageInterest;
-use crate::error::Kind;
+use crate::error::{Error, Kind};
 use crate::types::{B256, Decimal, U256};
 
 /// Top-level WebSocket message wrapper.
@@ -59,6 +63,27 @@ impl WsMessage {
     pub const fn is_market(&self) -> bool {
         !self.is_user()
     }
+
+    /// Deserializes the message in `json` as the type matching `event_type`, or returns `None`
+    /// for an unknown `event_type`.
+    ///
+    /// This skips the buffering that deserializing an internally tagged [`WsMessage`] requires.
+    fn from_event(event_type: &str, json: &[u8]) -> serde_json::Result<Option<Self>> {
+        let msg = match event_type {
+            "book" => WsMessage::Book(serde_json::from_slice(json)?),
+            "price_change" => WsMessage::PriceChange(serde_json::from_slice(json)?),
+            "tick_size_change" => WsMessage::TickSizeChange(serde_json::from_slice(json)?),
+            "last_trade_price" => WsMessage::LastTradePrice(serde_json::from_slice(json)?),
+            "best_bid_ask" => WsMessage::BestBidAsk(serde_json::from_slice(json)?),
+            "new_market" => WsMessage::NewMarket(serde_json::from_slice(json)?),
+            "market_resolved" => WsMessage::MarketResolved(serde_json::from_slice(json)?),
+            "trade" => WsMessage::Trade(serde_json::from_slice(json)?),
+            "order" => WsMessage::Order(serde_json::from_slice(json)?),
+            _ => return Ok(None),
+        };
+
+        Ok(Some(msg))
+    }
 }
 
 /// Orderbook update message (full snapshot or delta).
@@ -86,6 +111,33 @@ pub struct BookUpdate {
     pub hash: Option<String>,
 }
 
+impl BookUpdate {
+    /// Computes the server-compatible hash of this orderbook snapshot.
+    ///
+    /// See [`book_hash`] for how the hash is derived.
+    #[must_use]
+    pub fn compute_hash(&self) -> String {
+        let payload = book_hash::canonical_payload(
+            self.market,
+            self.asset_id,
+            self.timestamp,
+            self.bids.iter().map(|level| (level.price, level.size)),
+            self.asks.iter().map(|level| (level.price, level.size)),
+        );
+
+        book_hash::digest(&payload)
+    }
+
+    /// Returns `true` if the `hash` sent by the server matches the contents of this snapshot,
+    /// and `false` if they differ or the server did not send a hash.
+    #[must_use]
+    pub fn verify_hash(&self) -> bool {
+        self.hash
+            .as_deref()
+            .is_some_and(|expected| book_hash::matches(&self.compute_hash(), expected))
+    }
+}
+
 /// Individual price level in an orderbook.
 #[non_exhaustive]
 #[derive(Debug, Clone, Deserialize, Builder)]
@@ -312,6 +364,10 @@ pub enum TradeMessageStatus {
     Mined,
     #[serde(alias = "confirmed", alias = "CONFIRMED")]
     Confirmed,
+    #[serde(alias = "retrying", alias = "RETRYING")]
+    Retrying,
+    #[serde(alias = "failed", alias = "FAILED")]
+    Failed,
     #[serde(untagged)]
     Unknown(String),
 }
@@ -474,9 +530,9 @@ pub struct MidpointUpdate {
 
 /// Deserialize messages from the byte slice, filtering by interest.
 ///
-/// For single objects, the JSON is parsed once into a `Value`, then the `event_type` is
-/// extracted to check interest before final deserialization via `from_value()`.
-/// This avoids re-parsing the JSON text twice.
+/// The `event_type` of each message is peeked from the raw JSON, without building a `Value` or
+/// copying any field, and only messages of interest are then deserialized straight from their
+/// slice of the frame into the type matching their `event_type`.
 ///
 /// For arrays, messages are processed one-by-one with tolerant parsing: unknown or invalid
 /// event types are skipped rather than causing the entire batch to fail.
@@ -484,51 +540,72 @@ pub fn parse_if_interested(
     bytes: &[u8],
     interest: &MessageInterest,
 ) -> crate::Result<Vec<WsMessage>> {
-    // Parse JSON once into Value
-    let value: Value = serde_json::from_slice(bytes)
-        .map_err(|err| crate::error::Error::with_source(Kind::Internal, Box::new(err)))?;
+    let invalid = |err: serde_json::Error| Error::with_source(Kind::Internal, Box::new(err));
 
-    match &value {
-        Value::Object(map) => {
+    match bytes.iter().find(|byte| !byte.is_ascii_whitespace()) {
+        Some(b'{') => {
             // Single message: check event_type before full deserialization
-            let event_type = map.get("event_type").and_then(Value::as_str);
+            let event_type = match serde_json::from_slice::<EventTag<'_>>(bytes) {
+                Ok(tag) => tag.event_type,
+                Err(err) if err.is_data() => None,
+                Err(err) => return Err(invalid(err)),
+            };
 
             match event_type {
-                None => Ok(vec![]),
-                Some(event_type) if !interest.is_interested_in_event(event_type) => Ok(vec![]),
-                Some(_) => {
-                    // Interested: deserialize from cached Value (no re-parsing)
-                    let msg: WsMessage = serde_json::from_value(value)?;
-                    Ok(vec![msg])
+                Some(event_type) if interest.is_interested_in_event(&event_type) => {
+                    Ok(WsMessage::from_event(&event_type, bytes)?
+                        .into_iter()
+                        .collect())
                 }
+                _ => Ok(vec![]),
             }
         }
-        Value::Array(arr) => Ok(arr
-            .iter()
-            .filter_map(|elem| {
-                let obj = elem.as_object()?;
-                let event_type = obj.get("event_type").and_then(Value::as_str)?;
-
-                if !interest.is_interested_in_event(event_type) {
-                    return None;
-                }
-
-                serde_json::from_value(elem.clone())
-                    .inspect_err(|err| {
-                        #[cfg(feature = "tracing")]
-                        warn!(
-                            event_type = %event_type,
-                            error = %err,
-                            "Skipping unknown/invalid WS event in batch"
-                        );
-                    })
-                    .ok()
-            })
-            .collect()),
-        _ => Ok(vec![]),
+        Some(b'[') => {
+            let elems: Vec<&RawValue> = serde_json::from_slice(bytes).map_err(invalid)?;
+
+            Ok(elems
+                .iter()
+                .filter_map(|elem| {
+                    let json = elem.get().as_bytes();
+                    let event_type = serde_json::from_slice::<EventTag<'_>>(json)
+                        .ok()?
+                        .event_type?;
+
+                    if !interest.is_interested_in_event(&event_type) {
+                        return None;
+                    }
+
+                    WsMessage::from_event(&event_type, json)
+                        .inspect_err(|err| {
+                            #[cfg(feature = "tracing")]
+                            warn!(
+                                event_type = %event_type,
+                                error = %err,
+                                "Skipping unknown/invalid WS event in batch"
+                            );
+                            #[cfg(not(feature = "tracing"))]
+                            let _: &_ = &err;
+                        })
+                        .ok()
+                        .flatten()
+                })
+                .collect())
+        }
+        _ => {
+            // Anything else is ignored, as long as it is valid JSON
+            serde_json::from_slice::<IgnoredAny>(bytes).map_err(invalid)?;
+            Ok(vec![])
+        }
     }
 }
 
+/// The `event_type` of a message, borrowed from the frame when it holds no escapes.
+#[derive(Deserialize)]
+struct EventTag<'frame> {
+    #[serde(borrow, default)]
+    event_type: Option<Cow<'frame, str>>,
+}
+
 #[cfg(test)]
 mod tests {
     use std::str::FromStr as _;
@@ -656,6 +733,32 @@ mod tests {
         }
     }
 
+    #[test]
+    fn book_verify_hash() {
+        let json = r#"{
+            "event_type": "book",
+            "asset_id": "42",
+            "market": "0x0000000000000000000000000000000000000000000000000000000000000001",
+            "timestamp": "1000",
+            "bids": [{"price": "0.48", "size": "30"}, {"price": "0.49", "size": "20"}],
+            "asks": [{"price": "0.53", "size": "60"}],
+            "hash": "0581ca6d0f8178f30feff289b6cb7f318f4e2a3e"
+        }"#;
+
+        let mut book: BookUpdate = serde_json::from_str(json).unwrap();
+        assert_eq!(
+            book.compute_hash(),
+            "0581ca6d0f8178f30feff289b6cb7f318f4e2a3e"
+        );
+        assert!(book.verify_hash());
+
+        book.bids[0].size = dec!(31);
+        assert!(!book.verify_hash());
+
+        book.hash = None;
+        assert!(!book.verify_hash());
+    }
+
     #[test]
     fn parse_batch_messages() {
         let json = r#"[
@@ -1053,6 +1156,70 @@ mod tests {
         assert!(msgs.is_empty());
     }
 
+    #[test]
+    fn parse_if_interested_matches_tagged_deserialization() {
+        let market =
+            r#""market": "0x0000000000000000000000000000000000000000000000000000000000000001""#;
+        let messages = [
+            format!(
+                r#"{{"event_type": "book", "asset_id": "1", {market}, "timestamp": "1", "bids": [{{"price": "0.5", "size": "10"}}], "asks": []}}"#
+            ),
+            format!(
+                r#"{{"event_type": "price_change", {market}, "timestamp": "1", "price_changes": [{{"asset_id": "1", "price": "0.5", "side": "BUY"}}]}}"#
+            ),
+            format!(
+                r#"{{"event_type": "tick_size_change", "asset_id": "1", {market}, "old_tick_size": "0.01", "new_tick_size": "0.001", "timestamp": "1"}}"#
+            ),
+            format!(
+                r#"{{"event_type": "last_trade_price", "asset_id": "1", {market}, "timestamp": "1", "price": "0.5", "side": "BUY"}}"#
+            ),
+            format!(
+                r#"{{"event_type": "best_bid_ask", "asset_id": "1", {market}, "timestamp": "1", "best_bid": "0.49", "best_ask": "0.51", "spread": "0.02"}}"#
+            ),
+            format!(
+                r#"{{"event_type": "new_market", "id": "1", "question": "?", {market}, "slug": "s", "description": "d", "assets_ids": ["1"], "outcomes": ["Yes"], "timestamp": "1"}}"#
+            ),
+            format!(
+                r#"{{"event_type": "market_resolved", "id": "1", "question": "?", {market}, "slug": "s", "description": "d", "assets_ids": ["1"], "outcomes": ["Yes"], "winning_asset_id": "1", "winning_outcome": "Yes", "timestamp": "1"}}"#
+            ),
+            format!(
+                r#"{{"event_type": "trade", "id": "t", {market}, "asset_id": "1", "side": "BUY", "size": "1", "price": "0.5", "status": "MATCHED"}}"#
+            ),
+            format!(
+                r#"{{"event_type": "order", "id": "o", {market}, "asset_id": "1", "side": "BUY", "price": "0.5"}}"#
+            ),
+        ];
+
+        for json in &messages {
+            let tagged: WsMessage = serde_json::from_str(json).unwrap();
+            let msgs = parse_if_interested(json.as_bytes(), &MessageInterest::ALL).unwrap();
+            assert_eq!(msgs.len(), 1, "{json}");
+            assert_eq!(format!("{:?}", msgs[0]), format!("{tagged:?}"));
+        }
+
+        let batch = format!("[{}]", messages.join(","));
+        let msgs = parse_if_interested(batch.as_bytes(), &MessageInterest::ALL).unwrap();
+        assert_eq!(msgs.len(), messages.len());
+    }
+
+    #[test]
+    fn parse_if_interested_tolerates_odd_event_types() {
+        // An escaped event type is still recognized
+        let json = r#"{"event_type": "tick_size_\u0063hange", "asset_id": "1", "market": "0x0000000000000000000000000000000000000000000000000000000000000001", "old_tick_size": "0.01", "new_tick_size": "0.001", "timestamp": "1"}"#;
+        let msgs = parse_if_interested(json.as_bytes(), &MessageInterest::ALL).unwrap();
+        assert!(matches!(&msgs[..], [WsMessage::TickSizeChange(_)]));
+
+        // A non-string event type is skipped, but invalid JSON is still an error
+        let msgs = parse_if_interested(br#"{"event_type": 1}"#, &MessageInterest::ALL).unwrap();
+        assert!(msgs.is_empty());
+        let msgs =
+            parse_if_interested(br#"[{"event_type": 1}, 2]"#, &MessageInterest::ALL).unwrap();
+        assert!(msgs.is_empty());
+        parse_if_interested(br#"{"event_type": "book""#, &MessageInterest::ALL).unwrap_err();
+        parse_if_interested(b"[1,", &MessageInterest::ALL).unwrap_err();
+        parse_if_interested(b"  ", &MessageInterest::ALL).unwrap_err();
+    }
+
     // New test: Batch with mixed known + unknown event_type
     #[test]
     fn parse_batch_with_unknown_event_type() {
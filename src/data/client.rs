@@ -41,6 +41,7 @@ use super::types::response::{
     Activity, BuilderLeaderboardEntry, BuilderVolumeEntry, ClosedPosition, Health, LiveVolume,
     MetaHolder, OpenInterest, Position, Trade, Traded, TraderLeaderboardEntry, Value,
 };
+use crate::retry::RetryPolicy;
 use crate::{Result, ToQueryParams as _};
 
 /// HTTP client for the Kuest Data API.
@@ -64,9 +65,14 @@ use crate::{Result, ToQueryParams as _};
 /// let client = Client::new("https://custom-api.example.com").unwrap();
 /// ```
 #[derive(Clone, Debug)]
+#[expect(
+    clippy::struct_field_names,
+    reason = "`client` is the inner HTTP client, named like in the other API clients"
+)]
 pub struct Client {
     host: Url,
     client: ReqwestClient,
+    retry_policy: Option<RetryPolicy>,
 }
 
 impl Default for Client {
@@ -98,9 +104,20 @@ impl Client {
         Ok(Self {
             host: Url::parse(host)?,
             client,
+            retry_policy: None,
         })
     }
 
+    /// Sets the [`RetryPolicy`] applied to requests made by this client.
+    ///
+    /// By default, failed requests are not retried.
+    #[cfg(feature = "retry")]
+    #[must_use]
+    pub fn with_retry_policy(mut self, retry_policy: RetryPolicy) -> Self {
+        self.retry_policy = Some(retry_policy);
+        self
+    }
+
     /// Returns the base URL of the API.
     #[must_use]
     pub fn host(&self) -> &Url {
@@ -117,7 +134,7 @@ impl Client {
             .client
             .request(Method::GET, format!("{}{path}{query}", self.host))
             .build()?;
-        crate::request(&self.client, request, None).await
+        crate::request(&self.client, request, None, self.retry_policy.as_ref()).await
     }
 
     /// Performs a health check on the API.
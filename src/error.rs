@@ -26,6 +26,8 @@ pub enum Kind {
     WebSocket,
     /// Error related to geographic restrictions blocking access
     Geoblock,
+    /// Error related to a client-side rate limit being exceeded
+    RateLimited,
 }
 
 #[derive(Debug)]
@@ -83,6 +85,13 @@ impl Error {
         .into()
     }
 
+    /// Returns the CLOB's reason for rejecting the request, if this is a [`Kind::Status`] error.
+    #[cfg(feature = "clob")]
+    #[must_use]
+    pub fn api_error(&self) -> Option<crate::clob::types::ApiError> {
+        self.downcast_ref::<Status>().map(Status::api_error)
+    }
+
     #[must_use]
     pub fn missing_contract_config(chain_id: ChainId, neg_risk: bool) -> Self {
         MissingContractConfig { chain_id, neg_risk }.into()
@@ -127,6 +136,15 @@ impl fmt::Display for Status {
 
 impl StdError for Status {}
 
+#[cfg(feature = "clob")]
+impl Status {
+    /// Decodes the CLOB's reason for rejecting the request from the response body.
+    #[must_use]
+    pub fn api_error(&self) -> crate::clob::types::ApiError {
+        crate::clob::types::ApiError::from_message(&self.message)
+    }
+}
+
 #[non_exhaustive]
 #[derive(Debug)]
 pub struct Validation {
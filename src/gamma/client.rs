@@ -51,6 +51,7 @@ use super::types::response::{
     SportsMarketTypesResponse, SportsMetadata, Tag, Team,
 };
 use crate::error::Error;
+use crate::retry::RetryPolicy;
 use crate::{Result, ToQueryParams as _};
 
 const MAX_LIMIT: i32 = 500;
@@ -80,6 +81,7 @@ pub struct Client {
     host: Url,
     client: ReqwestClient,
     disabled: bool,
+    retry_policy: Option<RetryPolicy>,
 }
 
 const DEFAULT_HOST: &str = "https://gamma-api.kuest.com/#disabled";
@@ -121,9 +123,20 @@ impl Client {
             host,
             client,
             disabled,
+            retry_policy: None,
         })
     }
 
+    /// Sets the [`RetryPolicy`] applied to requests made by this client.
+    ///
+    /// By default, failed requests are not retried.
+    #[cfg(feature = "retry")]
+    #[must_use]
+    pub fn with_retry_policy(mut self, retry_policy: RetryPolicy) -> Self {
+        self.retry_policy = Some(retry_policy);
+        self
+    }
+
     /// Returns the base URL of the API.
     #[must_use]
     pub fn host(&self) -> &Url {
@@ -144,7 +157,7 @@ impl Client {
             .client
             .request(Method::GET, format!("{}{path}{query}", self.host))
             .build()?;
-        crate::request(&self.client, request, None).await
+        crate::request(&self.client, request, None, self.retry_policy.as_ref()).await
     }
 
     /// Performs a health check on the Gamma API.
@@ -161,7 +174,8 @@ impl Client {
             .request(Method::GET, format!("{}status", self.host))
             .build()?;
 
-        let response = self.client.execute(request).await?;
+        let response =
+            crate::retry::execute(&self.client, request, self.retry_policy.as_ref()).await?;
         let status_code = response.status();
 
         if !status_code.is_success() {
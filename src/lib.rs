@@ -12,6 +12,43 @@ pub mod data;
 pub mod error;
 #[cfg(feature = "gamma")]
 pub mod gamma;
+#[cfg(all(
+    feature = "retry",
+    any(
+        feature = "bridge",
+        feature = "clob",
+        feature = "data",
+        feature = "gamma"
+    )
+))]
+pub mod retry;
+/// Stand-in for the [`retry`] module without the `retry` feature, under which no retry policy can
+/// be set and requests are sent once.
+#[cfg(all(
+    not(feature = "retry"),
+    any(
+        feature = "bridge",
+        feature = "clob",
+        feature = "data",
+        feature = "gamma"
+    )
+))]
+mod retry {
+    use reqwest::{Client, Request, Response};
+
+    /// A retry policy that cannot be created.
+    #[derive(Clone, Copy, Debug, PartialEq, Eq)]
+    pub(crate) enum RetryPolicy {}
+
+    /// Executes `request` once.
+    pub(crate) async fn execute(
+        client: &Client,
+        request: Request,
+        _policy: Option<&RetryPolicy>,
+    ) -> reqwest::Result<Response> {
+        client.execute(request).await
+    }
+}
 #[cfg(feature = "rtds")]
 pub mod rtds;
 pub(crate) mod serde_helpers;
@@ -241,7 +278,7 @@ impl<T: Serialize> ToQueryParams for T {}
     feature = "tracing",
     tracing::instrument(
         level = "debug",
-        skip(client, request, headers),
+        skip(client, request, headers, retry_policy),
         fields(
             method = %request.method(),
             path = request.url().path(),
@@ -253,6 +290,7 @@ async fn request<Response: DeserializeOwned>(
     client: &reqwest::Client,
     mut request: Request,
     headers: Option<HeaderMap>,
+    retry_policy: Option<&retry::RetryPolicy>,
 ) -> Result<Response> {
     let method = request.method().clone();
     let path = request.url().path().to_owned();
@@ -261,7 +299,7 @@ async fn request<Response: DeserializeOwned>(
         *request.headers_mut() = h;
     }
 
-    let response = client.execute(request).await?;
+    let response = retry::execute(client, request, retry_policy).await?;
     let status_code = response.status();
 
     #[cfg(feature = "tracing")]
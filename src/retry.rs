@@ -0,0 +1,237 @@
+//! Automatic retries for REST requests, available with the `retry` feature.
+//!
+//! A [`RetryPolicy`] can be attached to the CLOB client through its
+//! [`Config`](crate::clob::Config), and to the other HTTP clients through their
+//! `with_retry_policy` methods. Requests that fail with a connection error, a timeout,
+//! `429 Too Many Requests` or a transient `5xx` response are retried with exponential backoff,
+//! honouring the server's `Retry-After` header when present, up to [`RetryPolicy::max_backoff`].
+//!
+//! Only idempotent `GET` and `HEAD` requests are retried. Posting an order can additionally be
+//! opted into with [`RetryPolicy::retry_post_order`], since a retried order with the same salt
+//! and signature is rejected by the server as a duplicate rather than placed twice.
+
+#![expect(
+    clippy::module_name_repetitions,
+    reason = "`RetryPolicy` reads better than `retry::Policy` where it is configured"
+)]
+
+use std::time::Duration;
+
+use bon::Builder;
+use chrono::{DateTime, Utc};
+use reqwest::header::{HeaderMap, RETRY_AFTER};
+use reqwest::{Client, Method, Request, Response, StatusCode};
+
+/// Retry behaviour for failed REST requests.
+///
+/// # Example
+///
+/// ```
+/// use std::time::Duration;
+///
+/// use kuest_client_sdk::retry::RetryPolicy;
+///
+/// let policy = RetryPolicy::builder()
+///     .max_retries(5)
+///     .initial_backoff(Duration::from_millis(100))
+///     .build();
+/// ```
+#[non_exhaustive]
+#[derive(Clone, Copy, Debug, Builder, PartialEq, Eq)]
+pub struct RetryPolicy {
+    /// Maximum number of retries after the initial attempt.
+    #[builder(default = 3)]
+    pub max_retries: u32,
+    /// Delay before the first retry. Each subsequent retry doubles the delay.
+    #[builder(default = Duration::from_millis(250))]
+    pub initial_backoff: Duration,
+    /// Upper bound for the delay before a retry, whether it comes from the exponential backoff
+    /// or from a `Retry-After` header sent by the server.
+    #[builder(default = Duration::from_secs(10))]
+    pub max_backoff: Duration,
+    /// Whether to randomize the second half of each backoff delay, so that clients throttled at
+    /// the same time do not retry in lockstep.
+    #[builder(default = true)]
+    pub jitter: bool,
+    /// Whether `post_order` requests are retried as well. Only enable this when orders carry a
+    /// client-generated salt, so that a retry of an order the server already accepted is
+    /// rejected as a duplicate.
+    #[builder(default)]
+    pub retry_post_order: bool,
+    #[builder(skip)]
+    non_idempotent: bool,
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        Self::builder().build()
+    }
+}
+
+impl RetryPolicy {
+    /// Returns a copy of this policy that also retries non-idempotent requests.
+    #[cfg(feature = "clob")]
+    pub(crate) const fn including_non_idempotent(mut self) -> Self {
+        self.non_idempotent = true;
+        self
+    }
+
+    fn permits(&self, method: &Method) -> bool {
+        self.non_idempotent || matches!(*method, Method::GET | Method::HEAD)
+    }
+
+    /// Backoff delay before retry number `retry`, starting from zero.
+    fn backoff(&self, retry: u32) -> Duration {
+        let delay = self
+            .initial_backoff
+            .saturating_mul(2_u32.saturating_pow(retry))
+            .min(self.max_backoff);
+
+        if !self.jitter {
+            return delay;
+        }
+
+        let half = delay / 2;
+        let jitter_millis = u64::try_from(half.as_millis()).unwrap_or(u64::MAX);
+        half.saturating_add(Duration::from_millis(rand::random_range(0..=jitter_millis)))
+    }
+}
+
+/// Executes `request`, retrying transient failures according to `policy`.
+///
+/// When retries are exhausted the last response is returned as-is, so that callers can report
+/// its status and body.
+pub(crate) async fn execute(
+    client: &Client,
+    mut request: Request,
+    policy: Option<&RetryPolicy>,
+) -> reqwest::Result<Response> {
+    let Some(policy) = policy.filter(|policy| policy.permits(request.method())) else {
+        return client.execute(request).await;
+    };
+
+    let mut retry = 0;
+    loop {
+        // Requests with a streaming body cannot be cloned, and are therefore never retried
+        let next = if retry < policy.max_retries {
+            request.try_clone()
+        } else {
+            None
+        };
+        let Some(next) = next else {
+            return client.execute(request).await;
+        };
+
+        let delay = match client.execute(request).await {
+            Ok(response) if is_retryable(response.status()) => {
+                retry_after(response.headers())
+                    .map_or_else(|| policy.backoff(retry), |delay| delay.min(policy.max_backoff))
+            }
+            Err(e) if e.is_connect() || e.is_timeout() => policy.backoff(retry),
+            result => return result,
+        };
+
+        #[cfg(feature = "tracing")]
+        tracing::debug!(
+            method = %next.method(),
+            path = next.url().path(),
+            attempt = retry + 1,
+            ?delay,
+            "Retrying request"
+        );
+
+        tokio::time::sleep(delay).await;
+        request = next;
+        retry += 1;
+    }
+}
+
+const fn is_retryable(status: StatusCode) -> bool {
+    matches!(
+        status,
+        StatusCode::TOO_MANY_REQUESTS
+            | StatusCode::INTERNAL_SERVER_ERROR
+            | StatusCode::BAD_GATEWAY
+            | StatusCode::SERVICE_UNAVAILABLE
+            | StatusCode::GATEWAY_TIMEOUT
+    )
+}
+
+/// Parses a `Retry-After` header given either as a number of seconds or as an HTTP date.
+fn retry_after(headers: &HeaderMap) -> Option<Duration> {
+    let value = headers.get(RETRY_AFTER)?.to_str().ok()?.trim();
+
+    if let Ok(seconds) = value.parse::<u64>() {
+        return Some(Duration::from_secs(seconds));
+    }
+
+    let date = DateTime::parse_from_rfc2822(value).ok()?;
+    Some(
+        (date.with_timezone(&Utc) - Utc::now())
+            .to_std()
+            .unwrap_or_default(),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use reqwest::header::HeaderValue;
+
+    use super::*;
+
+    #[test]
+    fn backoff_should_double_up_to_max() {
+        let policy = RetryPolicy::builder()
+            .initial_backoff(Duration::from_millis(100))
+            .max_backoff(Duration::from_millis(350))
+            .jitter(false)
+            .build();
+
+        assert_eq!(policy.backoff(0), Duration::from_millis(100));
+        assert_eq!(policy.backoff(1), Duration::from_millis(200));
+        assert_eq!(policy.backoff(2), Duration::from_millis(350));
+        assert_eq!(policy.backoff(40), Duration::from_millis(350));
+    }
+
+    #[test]
+    fn backoff_with_jitter_should_stay_within_bounds() {
+        let policy = RetryPolicy::builder()
+            .initial_backoff(Duration::from_millis(100))
+            .build();
+
+        for _ in 0..100 {
+            let delay = policy.backoff(1);
+            assert!(delay >= Duration::from_millis(100));
+            assert!(delay <= Duration::from_millis(200));
+        }
+    }
+
+    #[test]
+    fn permits_should_only_allow_idempotent_methods_by_default() {
+        let policy = RetryPolicy::default();
+
+        assert!(policy.permits(&Method::GET));
+        assert!(!policy.permits(&Method::POST));
+        assert!(!policy.permits(&Method::DELETE));
+        #[cfg(feature = "clob")]
+        assert!(policy.including_non_idempotent().permits(&Method::POST));
+    }
+
+    #[test]
+    fn retry_after_should_parse_seconds_and_dates() {
+        let mut headers = HeaderMap::new();
+        assert_eq!(retry_after(&headers), None);
+
+        headers.insert(RETRY_AFTER, HeaderValue::from_static("3"));
+        assert_eq!(retry_after(&headers), Some(Duration::from_secs(3)));
+
+        headers.insert(
+            RETRY_AFTER,
+            HeaderValue::from_static("Wed, 21 Oct 2015 07:28:00 GMT"),
+        );
+        assert_eq!(retry_after(&headers), Some(Duration::ZERO));
+
+        headers.insert(RETRY_AFTER, HeaderValue::from_static("soon"));
+        assert_eq!(retry_after(&headers), None);
+    }
+}
@@ -17,7 +17,7 @@ use super::types::response::{RtdsMessage, parse_messages};
 use crate::Result;
 use crate::auth::Credentials;
 use crate::ws::ConnectionManager;
-use crate::ws::connection::ConnectionState;
+use crate::ws::connection::{ConnectionEvent, ConnectionState};
 
 #[non_exhaustive]
 #[derive(Clone)]
@@ -106,6 +106,7 @@ impl SubscriptionManager {
                             #[cfg(feature = "tracing")]
                             tracing::debug!("RTDS reconnected, re-establishing subscriptions");
                             this.resubscribe_all();
+                            this.connection.emit(ConnectionEvent::Resubscribed);
                         }
                         was_connected = true;
                     }
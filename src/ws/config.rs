@@ -7,11 +7,15 @@ use std::time::Duration;
 
 use backoff::{ExponentialBackoff, ExponentialBackoffBuilder};
 
+use super::record::{Recorder, Replay};
+
 const DEFAULT_HEARTBEAT_INTERVAL_DURATION: Duration = Duration::from_secs(5);
 const DEFAULT_HEARTBEAT_TIMEOUT_DURATION: Duration = Duration::from_secs(15);
 const DEFAULT_INITIAL_BACKOFF_DURATION: Duration = Duration::from_secs(1);
 const DEFAULT_MAX_BACKOFF_DURATION: Duration = Duration::from_secs(60);
 const DEFAULT_BACKOFF_MULTIPLIER: f64 = 2.0;
+const DEFAULT_SUBSCRIBER_BUFFER: usize = 1024;
+const DEFAULT_MAX_MARKET_CONNECTIONS: usize = 8;
 
 /// Configuration for WebSocket client behavior.
 #[non_exhaustive]
@@ -23,6 +27,24 @@ pub struct Config {
     pub heartbeat_timeout: Duration,
     /// Reconnection strategy configuration
     pub reconnect: ReconnectConfig,
+    /// Number of incoming messages buffered for each subscriber. A subscriber that falls further
+    /// behind skips the oldest messages and is told how many it missed.
+    pub subscriber_buffer: usize,
+    /// Whether a market subscriber that fell behind re-sends its subscription, so that the server
+    /// pushes fresh `book` snapshots for its assets. Each asset is re-sent at most once a second,
+    /// however many of its subscribers lag.
+    pub resync_after_lag: bool,
+    /// Maximum number of assets carried by each market channel connection. Assets beyond it are
+    /// spread over additional connections. `None` keeps every asset on a single connection.
+    pub max_assets_per_connection: Option<usize>,
+    /// Maximum number of connections the market channel opens when
+    /// [`max_assets_per_connection`](Self::max_assets_per_connection) is set. Subscribing to more
+    /// assets than they can carry fails.
+    pub max_market_connections: usize,
+    /// Records every frame received by the connections, see [`Recorder`].
+    pub recorder: Option<Recorder>,
+    /// Plays a recording back instead of connecting, see [`Replay`].
+    pub replay: Option<Replay>,
 }
 
 impl Default for Config {
@@ -31,6 +53,12 @@ impl Default for Config {
             heartbeat_interval: DEFAULT_HEARTBEAT_INTERVAL_DURATION,
             heartbeat_timeout: DEFAULT_HEARTBEAT_TIMEOUT_DURATION,
             reconnect: ReconnectConfig::default(),
+            subscriber_buffer: DEFAULT_SUBSCRIBER_BUFFER,
+            resync_after_lag: true,
+            max_assets_per_connection: None,
+            max_market_connections: DEFAULT_MAX_MARKET_CONNECTIONS,
+            recorder: None,
+            replay: None,
         }
     }
 }
@@ -113,4 +141,11 @@ mod tests {
         let config = Config::default();
         assert_eq!(config.heartbeat_interval, Duration::from_secs(5));
     }
+
+    #[test]
+    fn default_subscriber_buffer_resyncs_after_lag() {
+        let config = Config::default();
+        assert_eq!(config.subscriber_buffer, 1024);
+        assert!(config.resync_after_lag);
+    }
 }
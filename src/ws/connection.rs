@@ -5,19 +5,24 @@
 
 use std::fmt::Debug;
 use std::marker::PhantomData;
-use std::time::Instant;
+use std::pin::pin;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::{Duration, Instant};
 
 use backoff::backoff::Backoff as _;
 use futures::{SinkExt as _, StreamExt as _};
 use serde::Serialize;
 use serde::de::DeserializeOwned;
 use tokio::net::TcpStream;
-use tokio::sync::{broadcast, mpsc, watch};
+use tokio::sync::broadcast::error::RecvError;
+use tokio::sync::{Notify, broadcast, mpsc, watch};
 use tokio::time::{interval, sleep, timeout};
 use tokio_tungstenite::{MaybeTlsStream, WebSocketStream, connect_async, tungstenite::Message};
 
 use super::config::Config;
 use super::error::WsError;
+use super::record::Replay;
 use super::traits::MessageParser;
 use crate::auth::Credentials;
 use crate::error::Kind;
@@ -26,9 +31,6 @@ use crate::{Result, error::Error};
 
 type WsStream = WebSocketStream<MaybeTlsStream<TcpStream>>;
 
-/// Broadcast channel capacity for incoming messages.
-const BROADCAST_CAPACITY: usize = 1024;
-
 /// Connection state tracking.
 #[non_exhaustive]
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
@@ -57,6 +59,56 @@ impl ConnectionState {
     }
 }
 
+/// Lifecycle event of a connection, see [`ConnectionManager::events`].
+#[non_exhaustive]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ConnectionEvent {
+    /// A connection was established.
+    Connected,
+    /// An established connection was lost.
+    Disconnected {
+        /// Why the connection was lost.
+        reason: String,
+    },
+    /// Waiting before the next connection attempt.
+    Reconnecting {
+        /// Number of failed attempts since the last established connection.
+        attempt: u32,
+    },
+    /// Subscriptions were re-sent over a re-established connection.
+    Resubscribed,
+}
+
+/// Receiver of the messages of a connection, see [`ConnectionManager::subscribe`].
+#[derive(Debug)]
+pub struct Receiver<M> {
+    inner: broadcast::Receiver<M>,
+    /// Wakes a replay waiting for its receivers to catch up
+    received: Option<Arc<Notify>>,
+}
+
+impl<M: Clone> Receiver<M> {
+    /// Receives the next message.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`RecvError::Lagged`] when the receiver fell behind and skipped messages, and
+    /// [`RecvError::Closed`] once the connection has stopped for good.
+    pub async fn recv(&mut self) -> std::result::Result<M, RecvError> {
+        let message = self.inner.recv().await;
+        if let Some(received) = &self.received {
+            received.notify_waiters();
+        }
+        message
+    }
+}
+
+/// Broadcast channel capacity for connection events.
+const EVENTS_CAPACITY: usize = 64;
+
+/// Longest a replay holds a message back for subscribers to catch up before sending it anyway.
+const REPLAY_BACKPRESSURE_TIMEOUT: Duration = Duration::from_secs(1);
+
 /// Manages WebSocket connection lifecycle, reconnection, and heartbeat.
 ///
 /// This generic connection manager handles all WebSocket connection concerns:
@@ -98,8 +150,15 @@ where
     state_rx: watch::Receiver<ConnectionState>,
     /// Sender channel for outgoing messages
     sender_tx: mpsc::UnboundedSender<String>,
-    /// Broadcast sender for incoming messages
-    broadcast_tx: broadcast::Sender<M>,
+    /// Broadcast sender for incoming messages, held by the connection task so that receivers
+    /// are closed once it stops
+    broadcast_tx: broadcast::WeakSender<M>,
+    /// Number of connections established so far (enables telling reconnections apart)
+    connections: Arc<AtomicU64>,
+    /// Broadcast sender for connection lifecycle events
+    events_tx: broadcast::Sender<ConnectionEvent>,
+    /// Notified whenever a receiver takes a message while a replay is playing back
+    received: Option<Arc<Notify>>,
     /// Phantom data for unused type parameters
     _phantom: PhantomData<P>,
 }
@@ -114,9 +173,12 @@ where
     /// The `parser` is used to deserialize incoming WebSocket messages.
     /// The connection loop runs in a background task and automatically
     /// handles reconnection according to the config's `ReconnectConfig`.
+    ///
+    /// With [`Config::replay`] set, the recorded frames of `endpoint` are played back instead
+    /// of connecting.
     pub fn new(endpoint: String, config: Config, parser: P) -> Result<Self> {
         let (sender_tx, sender_rx) = mpsc::unbounded_channel();
-        let (broadcast_tx, _) = broadcast::channel(BROADCAST_CAPACITY);
+        let (broadcast_tx, _) = broadcast::channel(config.subscriber_buffer.max(1));
         let (state_tx, state_rx) = watch::channel(ConnectionState::Disconnected);
 
         // Spawn connection task
@@ -124,29 +186,60 @@ where
         let connection_endpoint = endpoint;
         let broadcast_tx_clone = broadcast_tx.clone();
         let state_tx_clone = state_tx.clone();
+        let connections = Arc::new(AtomicU64::new(0));
+        let connections_clone = Arc::clone(&connections);
+        let (events_tx, _) = broadcast::channel(EVENTS_CAPACITY);
+        let events_tx_clone = events_tx.clone();
+        let received = connection_config.replay.is_some().then(Arc::default);
+        let received_clone = received.clone();
 
         tokio::spawn(async move {
-            Self::connection_loop(
-                connection_endpoint,
-                connection_config,
-                sender_rx,
-                broadcast_tx_clone,
-                parser,
-                state_tx_clone,
-            )
-            .await;
+            if let Some(replay) = connection_config.replay.clone() {
+                Self::replay_loop(
+                    connection_endpoint,
+                    connection_config,
+                    replay,
+                    sender_rx,
+                    broadcast_tx_clone,
+                    received_clone.unwrap_or_default(),
+                    parser,
+                    state_tx_clone,
+                    connections_clone,
+                    events_tx_clone,
+                )
+                .await;
+            } else {
+                Self::connection_loop(
+                    connection_endpoint,
+                    connection_config,
+                    sender_rx,
+                    broadcast_tx_clone,
+                    parser,
+                    state_tx_clone,
+                    connections_clone,
+                    events_tx_clone,
+                )
+                .await;
+            }
         });
 
         Ok(Self {
             state_tx,
             state_rx,
             sender_tx,
-            broadcast_tx,
+            broadcast_tx: broadcast_tx.downgrade(),
+            connections,
+            events_tx,
+            received,
             _phantom: PhantomData,
         })
     }
 
     /// Main connection loop with automatic reconnection.
+    #[expect(
+        clippy::too_many_arguments,
+        reason = "The loop owns every channel shared with the manager, grouping them gains nothing"
+    )]
     async fn connection_loop(
         endpoint: String,
         config: Config,
@@ -154,6 +247,8 @@ where
         broadcast_tx: broadcast::Sender<M>,
         parser: P,
         state_tx: watch::Sender<ConnectionState>,
+        connections: Arc<AtomicU64>,
+        events_tx: broadcast::Sender<ConnectionEvent>,
     ) {
         let mut attempt = 0_u32;
         let mut backoff: backoff::ExponentialBackoff = config.reconnect.clone().into();
@@ -176,12 +271,16 @@ where
                 Ok((ws_stream, _)) => {
                     attempt = 0;
                     backoff.reset();
+                    connections.fetch_add(1, Ordering::Relaxed);
+                    // Announce the connection before the state change triggers resubscription
+                    _ = events_tx.send(ConnectionEvent::Connected);
                     _ = state_tx.send(ConnectionState::Connected {
                         since: Instant::now(),
                     });
 
                     // Handle connection
-                    if let Err(e) = Self::handle_connection(
+                    let reason = match Self::handle_connection(
+                        &endpoint,
                         ws_stream,
                         &mut sender_rx,
                         &broadcast_tx,
@@ -191,11 +290,14 @@ where
                     )
                     .await
                     {
-                        #[cfg(feature = "tracing")]
-                        tracing::error!("Error handling connection: {e:?}");
-                        #[cfg(not(feature = "tracing"))]
-                        let _: &_ = &e;
-                    }
+                        Ok(()) => WsError::ConnectionClosed.to_string(),
+                        Err(e) => {
+                            #[cfg(feature = "tracing")]
+                            tracing::error!("Error handling connection: {e:?}");
+                            e.to_string()
+                        }
+                    };
+                    _ = events_tx.send(ConnectionEvent::Disconnected { reason });
                 }
                 Err(e) => {
                     let error = Error::with_source(Kind::WebSocket, WsError::Connection(e));
@@ -217,6 +319,7 @@ where
 
             // Update state and wait with exponential backoff
             _ = state_tx.send(ConnectionState::Reconnecting { attempt });
+            _ = events_tx.send(ConnectionEvent::Reconnecting { attempt });
 
             if let Some(duration) = backoff.next_backoff() {
                 sleep(duration).await;
@@ -224,8 +327,109 @@ where
         }
     }
 
+    /// Play the frames recorded from `endpoint` back in place of a connection.
+    ///
+    /// Playback waits for subscribers that fall [`Config::subscriber_buffer`] messages behind
+    /// rather than letting them lag, for up to [`REPLAY_BACKPRESSURE_TIMEOUT`] per message. A
+    /// subscriber that does not catch up in time, such as a stream that is never polled, is left
+    /// to lag instead, and playback runs without waiting until every subscriber has caught up.
+    #[expect(
+        clippy::too_many_arguments,
+        reason = "The loop owns every channel shared with the manager, grouping them gains nothing"
+    )]
+    async fn replay_loop(
+        endpoint: String,
+        config: Config,
+        replay: Replay,
+        mut sender_rx: mpsc::UnboundedReceiver<String>,
+        broadcast_tx: broadcast::Sender<M>,
+        received: Arc<Notify>,
+        parser: P,
+        state_tx: watch::Sender<ConnectionState>,
+        connections: Arc<AtomicU64>,
+        events_tx: broadcast::Sender<ConnectionEvent>,
+    ) {
+        connections.fetch_add(1, Ordering::Relaxed);
+        _ = events_tx.send(ConnectionEvent::Connected);
+        _ = state_tx.send(ConnectionState::Connected {
+            since: Instant::now(),
+        });
+
+        // Wait for the first subscription request, or for an explicit start
+        let mut started = replay.started();
+        let start = async {
+            if replay.is_manual_start() {
+                started.wait_for(|started| *started).await.is_ok()
+            } else {
+                tokio::select! {
+                    request = sender_rx.recv() => request.is_some(),
+                    started = started.wait_for(|started| *started) => started.is_ok(),
+                }
+            }
+        };
+
+        if start.await {
+            let capacity = config.subscriber_buffer.max(1);
+            let mut stalled = false;
+            let mut previous = None;
+            for frame in replay
+                .frames()
+                .iter()
+                .filter(|frame| frame.endpoint == endpoint)
+            {
+                if let Some(delay) = previous
+                    .and_then(|previous| replay.delay(frame.received_at.saturating_sub(previous)))
+                {
+                    sleep(delay).await;
+                }
+                previous = Some(frame.received_at);
+
+                // Subscription requests have nowhere to go, but tell whether anyone is left
+                while sender_rx.try_recv().is_ok() {}
+                if sender_rx.is_closed() {
+                    break;
+                }
+
+                match parser.parse(frame.frame.as_bytes()) {
+                    Ok(messages) => {
+                        for message in messages {
+                            stalled &= broadcast_tx.len() >= capacity;
+
+                            let waiting_since = Instant::now();
+                            while !stalled {
+                                // Registered before checking, so that no receiver goes unnoticed
+                                let mut caught_up = pin!(received.notified());
+                                caught_up.as_mut().enable();
+                                if broadcast_tx.len() < capacity {
+                                    break;
+                                }
+
+                                let remaining = REPLAY_BACKPRESSURE_TIMEOUT
+                                    .saturating_sub(waiting_since.elapsed());
+                                stalled = timeout(remaining, caught_up).await.is_err();
+                            }
+                            _ = broadcast_tx.send(message);
+                        }
+                    }
+                    Err(e) => {
+                        #[cfg(feature = "tracing")]
+                        tracing::warn!(frame = %frame.frame, error = %e, "Failed to parse replayed message");
+                        #[cfg(not(feature = "tracing"))]
+                        let _: &_ = &e;
+                    }
+                }
+            }
+        }
+
+        _ = events_tx.send(ConnectionEvent::Disconnected {
+            reason: "replay finished".to_owned(),
+        });
+        _ = state_tx.send(ConnectionState::Disconnected);
+    }
+
     /// Handle an active WebSocket connection.
     async fn handle_connection(
+        endpoint: &str,
         ws_stream: WsStream,
         sender_rx: &mut mpsc::UnboundedReceiver<String>,
         broadcast_tx: &broadcast::Sender<M>,
@@ -234,6 +438,7 @@ where
         parser: &P,
     ) -> Result<()> {
         let (mut write, mut read) = ws_stream.split();
+        let recorder = config.recorder.clone();
 
         // Channel to notify heartbeat loop when PONG is received
         let (pong_tx, pong_rx) = watch::channel(Instant::now());
@@ -255,6 +460,10 @@ where
                             #[cfg(feature = "tracing")]
                             tracing::trace!(%text, "Received WebSocket text message");
 
+                            if let Some(recorder) = &recorder {
+                                recorder.record(endpoint, &text);
+                            }
+
                             // Parse messages using the provided parser
                             match parser.parse(text.as_bytes()) {
                                 Ok(messages) => {
@@ -406,13 +615,47 @@ where
         *self.state_rx.borrow()
     }
 
+    /// Get the number of connections established so far, counting the first one and every
+    /// reconnection.
+    #[must_use]
+    pub fn connection_count(&self) -> u64 {
+        self.connections.load(Ordering::Relaxed)
+    }
+
     /// Subscribe to incoming messages.
     ///
     /// Each call returns a new independent receiver. Multiple subscribers can
-    /// receive messages concurrently without blocking each other.
+    /// receive messages concurrently without blocking each other. A receiver buffers up to
+    /// [`Config::subscriber_buffer`] messages, after which it lags and skips the oldest ones.
+    /// Receivers are closed once the connection stops for good, after the last reconnection
+    /// attempt or at the end of a replay.
     #[must_use]
-    pub fn subscribe(&self) -> broadcast::Receiver<M> {
-        self.broadcast_tx.subscribe()
+    pub fn subscribe(&self) -> Receiver<M> {
+        let inner = match self.broadcast_tx.upgrade() {
+            Some(broadcast_tx) => broadcast_tx.subscribe(),
+            // The connection task has stopped, so the receiver is closed right away
+            None => broadcast::channel(1).1,
+        };
+
+        Receiver {
+            inner,
+            received: self.received.clone(),
+        }
+    }
+
+    /// Subscribe to connection lifecycle events.
+    ///
+    /// Unlike [`Self::state_receiver`], which only holds the latest state, every event is
+    /// delivered in order, along with the reason a connection was lost.
+    #[must_use]
+    pub fn events(&self) -> broadcast::Receiver<ConnectionEvent> {
+        self.events_tx.subscribe()
+    }
+
+    /// Publish a lifecycle event to the receivers of [`Self::events`].
+    #[cfg(any(feature = "clob", feature = "rtds"))]
+    pub(crate) fn emit(&self, event: ConnectionEvent) {
+        _ = self.events_tx.send(event);
     }
 
     /// Subscribe to connection state changes.
@@ -7,6 +7,8 @@
 //!
 //! - [`ConnectionManager`]: Generic WebSocket connection handler with heartbeat and reconnection
 //! - [`MessageParser`]: Trait for parsing incoming WebSocket messages
+//! - [`Recorder`](record::Recorder) and [`Replay`](record::Replay): Capture of received frames
+//!   and offline playback in place of a connection
 //!
 //! # Example
 //!
@@ -22,6 +24,7 @@
 pub mod config;
 pub mod connection;
 pub mod error;
+pub mod record;
 pub mod traits;
 
 pub use connection::ConnectionManager;
@@ -0,0 +1,364 @@
+//! Recording and replay of WebSocket feeds.
+//!
+//! A [`Recorder`] set as [`Config::recorder`] writes every text frame received by a connection
+//! as one JSON line holding the time it was received, the endpoint it came from and the raw
+//! frame. Any [`Write`] can be recorded to, so a feed can be compressed by wrapping the file in
+//! a compressing encoder.
+//!
+//! A [`Replay`] set as [`Config::replay`] plays such a recording back instead of connecting:
+//! every connection feeds the frames recorded from its endpoint to its parser, at the pace they
+//! were received or faster, see [`Speed`]. Everything built on the connection, such as the
+//! subscriptions of [`clob::ws::Client`](crate::clob::ws::Client), works unchanged, without any
+//! network access. Subscription requests are accepted and ignored, so streams only see the
+//! frames that were recorded, and they end once the recording is exhausted.
+//!
+//! # Example
+//!
+//! ```ignore
+//! // Record a live session
+//! let mut config = Config::default();
+//! config.recorder = Some(Recorder::create("feed.jsonl")?);
+//! let client = Client::new("wss://ws-subscriptions-clob.kuest.com", config)?;
+//!
+//! // Replay it later, ten times faster than it was recorded
+//! let mut config = Config::default();
+//! config.replay = Some(Replay::open("feed.jsonl")?.speed(Speed::Multiplier(10)));
+//! let client = Client::new("wss://ws-subscriptions-clob.kuest.com", config)?;
+//! let books = client.subscribe_orderbook(asset_ids)?;
+//! ```
+//!
+//! [`Config::recorder`]: super::config::Config::recorder
+//! [`Config::replay`]: super::config::Config::replay
+
+use std::error::Error as StdError;
+use std::fmt;
+use std::fs::File;
+use std::io::{self, BufRead, BufReader, BufWriter, Write};
+use std::path::Path;
+use std::sync::{Arc, mpsc};
+use std::thread;
+use std::time::Duration;
+
+use chrono::Utc;
+use serde::{Deserialize, Serialize};
+use tokio::sync::watch;
+
+use crate::Result;
+use crate::error::{Error, Kind};
+
+/// A text frame received by a connection.
+#[non_exhaustive]
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Frame {
+    /// Unix timestamp in milliseconds at which the frame was received
+    pub received_at: i64,
+    /// Endpoint of the connection that received the frame
+    pub endpoint: String,
+    /// The frame as received
+    pub frame: String,
+}
+
+impl Frame {
+    /// Creates a frame received from `endpoint` at `received_at`, in Unix milliseconds.
+    #[must_use]
+    pub fn new<E: Into<String>, F: Into<String>>(received_at: i64, endpoint: E, frame: F) -> Self {
+        Self {
+            received_at,
+            endpoint: endpoint.into(),
+            frame: frame.into(),
+        }
+    }
+}
+
+/// Writes the frames received by connections as JSON lines.
+///
+/// Frames are handed over to a dedicated writer thread as they arrive, so that connections never
+/// wait on the writer, which should still be buffered. Clones share the same writer, so one
+/// recorder can capture every connection of a client. The writer is flushed and dropped once
+/// every clone is dropped.
+#[derive(Clone)]
+pub struct Recorder {
+    commands: mpsc::Sender<Command>,
+}
+
+/// What the writer thread of a [`Recorder`] is asked to do.
+enum Command {
+    /// Write a line holding a [`Frame`].
+    Line(String),
+    /// Flush the writer and report the result.
+    Flush(mpsc::SyncSender<io::Result<()>>),
+}
+
+impl Recorder {
+    /// Creates a recorder writing to `writer`.
+    pub fn new<W: Write + Send + 'static>(writer: W) -> Self {
+        let (commands, received) = mpsc::channel();
+        thread::spawn(move || write_frames(writer, &received));
+
+        Self { commands }
+    }
+
+    /// Creates a recorder writing to a new file at `path`, truncating any existing one.
+    pub fn create<P: AsRef<Path>>(path: P) -> Result<Self> {
+        let file = File::create(path).map_err(|e| Error::with_source(Kind::Internal, e))?;
+
+        Ok(Self::new(BufWriter::new(file)))
+    }
+
+    /// Flushes the writer once every frame recorded so far is written, blocking until it is.
+    pub fn flush(&self) -> Result<()> {
+        let (reply, flushed) = mpsc::sync_channel(1);
+        self.commands
+            .send(Command::Flush(reply))
+            .map_err(|_e| Error::with_source(Kind::Internal, WriterGone))?;
+
+        flushed
+            .recv()
+            .map_err(|_e| Error::with_source(Kind::Internal, WriterGone))?
+            .map_err(|e| Error::with_source(Kind::Internal, e))
+    }
+
+    /// Records `frame` as received now from `endpoint`.
+    ///
+    /// A frame that cannot be written is skipped rather than interrupting the connection.
+    pub(crate) fn record(&self, endpoint: &str, frame: &str) {
+        let frame = Frame::new(Utc::now().timestamp_millis(), endpoint, frame);
+
+        let result = serde_json::to_string(&frame)
+            .map_err(Error::from)
+            .and_then(|line| {
+                self.commands
+                    .send(Command::Line(line))
+                    .map_err(|_e| Error::with_source(Kind::Internal, WriterGone))
+            });
+
+        if let Err(e) = result {
+            warn_unrecorded(&e);
+        }
+    }
+}
+
+/// Writes the lines received from `commands` to `writer` until every [`Recorder`] is dropped.
+fn write_frames<W: Write>(mut writer: W, commands: &mpsc::Receiver<Command>) {
+    for command in commands {
+        match command {
+            Command::Line(line) => {
+                if let Err(e) = writeln!(writer, "{line}") {
+                    warn_unrecorded(&e);
+                }
+            }
+            Command::Flush(reply) => _ = reply.send(writer.flush()),
+        }
+    }
+
+    if let Err(e) = writer.flush() {
+        warn_unrecorded(&e);
+    }
+}
+
+/// Reports a frame that could not be recorded.
+fn warn_unrecorded<E: fmt::Display>(error: &E) {
+    #[cfg(feature = "tracing")]
+    tracing::warn!(error = %error, "Failed to record WebSocket frame");
+    #[cfg(not(feature = "tracing"))]
+    let _: &_ = error;
+}
+
+/// Error returned when the writer thread of a [`Recorder`] has stopped.
+#[derive(Debug)]
+struct WriterGone;
+
+impl fmt::Display for WriterGone {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("recorder writer thread has stopped")
+    }
+}
+
+impl StdError for WriterGone {}
+
+impl fmt::Debug for Recorder {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Recorder").finish_non_exhaustive()
+    }
+}
+
+/// Pace at which a [`Replay`] plays frames back.
+#[non_exhaustive]
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Speed {
+    /// Keep the delays between frames as they were recorded.
+    #[default]
+    RealTime,
+    /// Divide the delays between frames by the given factor, a factor of zero meaning no delay.
+    Multiplier(u32),
+    /// Play frames back without any delay.
+    Max,
+}
+
+impl Speed {
+    /// Returns how long to wait between frames received `elapsed` milliseconds apart.
+    fn delay(self, elapsed: i64) -> Option<Duration> {
+        let elapsed = Duration::from_millis(u64::try_from(elapsed).ok()?);
+
+        match self {
+            Speed::RealTime => Some(elapsed),
+            Speed::Multiplier(factor) => elapsed.checked_div(factor),
+            Speed::Max => None,
+        }
+    }
+}
+
+/// A recording of frames played back in place of live connections.
+///
+/// Playback starts on each connection with its first subscription request, or once
+/// [`Replay::start`] is called when created with [`Replay::manual_start`]. Clones share the
+/// same frames and start signal.
+#[derive(Clone)]
+pub struct Replay {
+    frames: Arc<[Frame]>,
+    speed: Speed,
+    manual_start: bool,
+    started: Arc<watch::Sender<bool>>,
+}
+
+impl Replay {
+    /// Creates a replay of `frames`, which are played back in order.
+    #[must_use]
+    pub fn from_frames(frames: Vec<Frame>) -> Self {
+        Self {
+            frames: frames.into(),
+            speed: Speed::default(),
+            manual_start: false,
+            started: Arc::new(watch::Sender::new(false)),
+        }
+    }
+
+    /// Creates a replay of the JSON lines read from `reader`, as written by a [`Recorder`].
+    ///
+    /// Empty lines are skipped. A compressed recording can be read by wrapping it in a
+    /// decoder.
+    pub fn from_reader<R: BufRead>(reader: R) -> Result<Self> {
+        let mut frames = Vec::new();
+        for line in reader.lines() {
+            let line = line.map_err(|e| Error::with_source(Kind::Internal, e))?;
+            if !line.trim().is_empty() {
+                frames.push(serde_json::from_str(&line)?);
+            }
+        }
+
+        Ok(Self::from_frames(frames))
+    }
+
+    /// Creates a replay of the recording in the file at `path`.
+    pub fn open<P: AsRef<Path>>(path: P) -> Result<Self> {
+        let file = File::open(path).map_err(|e| Error::with_source(Kind::Internal, e))?;
+
+        Self::from_reader(BufReader::new(file))
+    }
+
+    /// Sets the pace of playback, [`Speed::RealTime`] by default.
+    #[must_use]
+    pub fn speed(mut self, speed: Speed) -> Self {
+        self.speed = speed;
+        self
+    }
+
+    /// Holds playback back until [`Replay::start`] is called, so that several subscriptions can
+    /// be made before the first frame is played.
+    #[must_use]
+    pub fn manual_start(mut self) -> Self {
+        self.manual_start = true;
+        self
+    }
+
+    /// Starts playback on every connection replaying this recording.
+    pub fn start(&self) {
+        self.started.send_replace(true);
+    }
+
+    /// Returns the recorded frames.
+    #[must_use]
+    pub fn frames(&self) -> &[Frame] {
+        &self.frames
+    }
+
+    /// Returns `true` if playback waits for [`Replay::start`].
+    pub(crate) const fn is_manual_start(&self) -> bool {
+        self.manual_start
+    }
+
+    /// Returns a receiver that turns `true` once [`Replay::start`] is called.
+    pub(crate) fn started(&self) -> watch::Receiver<bool> {
+        self.started.subscribe()
+    }
+
+    /// Returns how long to wait before a frame received `elapsed` milliseconds after the
+    /// previous one.
+    pub(crate) fn delay(&self, elapsed: i64) -> Option<Duration> {
+        self.speed.delay(elapsed)
+    }
+}
+
+impl fmt::Debug for Replay {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Replay")
+            .field("frames", &self.frames.len())
+            .field("speed", &self.speed)
+            .field("manual_start", &self.manual_start)
+            .finish_non_exhaustive()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Mutex;
+
+    use super::*;
+
+    #[derive(Clone, Default)]
+    struct Shared(Arc<Mutex<Vec<u8>>>);
+
+    impl Write for Shared {
+        fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+            self.0.lock().unwrap().write(buf)
+        }
+
+        fn flush(&mut self) -> std::io::Result<()> {
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn recorded_frames_should_replay_in_order() {
+        let buffer = Shared::default();
+        let recorder = Recorder::new(buffer.clone());
+        recorder.record("wss://example.com/ws/market", r#"{"event_type":"book"}"#);
+        recorder.record("wss://example.com/ws/user", "[]");
+        recorder.flush().unwrap();
+
+        let bytes = buffer.0.lock().unwrap().clone();
+        let replay = Replay::from_reader(bytes.as_slice()).unwrap();
+
+        let frames = replay.frames();
+        assert_eq!(frames.len(), 2);
+        assert_eq!(frames[0].endpoint, "wss://example.com/ws/market");
+        assert_eq!(frames[0].frame, r#"{"event_type":"book"}"#);
+        assert_eq!(frames[1].frame, "[]");
+        assert!(frames[0].received_at <= frames[1].received_at);
+    }
+
+    #[test]
+    fn speed_should_scale_delays() {
+        assert_eq!(
+            Speed::RealTime.delay(1500),
+            Some(Duration::from_millis(1500))
+        );
+        assert_eq!(
+            Speed::Multiplier(10).delay(1500),
+            Some(Duration::from_millis(150))
+        );
+        assert_eq!(Speed::Max.delay(1500), None);
+        // Clock adjustments between frames never make playback wait
+        assert_eq!(Speed::RealTime.delay(-5), None);
+    }
+}
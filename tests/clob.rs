@@ -622,6 +622,38 @@ mod unauthenticated {
         Ok(())
     }
 
+    #[test]
+    fn order_book_compute_hash_should_digest_canonical_payload() -> anyhow::Result<()> {
+        // SHA-1 of the canonical payload, written out by hand rather than built by the crate:
+        // {"market":"0x00000000000000000000000000000000000000000000000000000000aabbcc00",
+        // "asset_id":"15871154585880608648532107628464183779895785213830018178010423617714102767076",
+        // "timestamp":"123456789","hash":"","bids":[{"price":"0.3","size":"100"},
+        // {"price":"0.4","size":"100"}],"asks":[{"price":"0.6","size":"100"},
+        // {"price":"0.7","size":"100"}]}
+        let mut book: OrderBookSummaryResponse = serde_json::from_value(json!({
+            "market": "0x00000000000000000000000000000000000000000000000000000000aabbcc00",
+            "asset_id": token_1(),
+            "tick_size": "0.01",
+            "min_order_size": "100",
+            "neg_risk": false,
+            "timestamp": "123456789",
+            "hash": "172607c1dbcd73533dd80c06b6ace8e909722c4f",
+            "bids": [{ "price": "0.30", "size": "100" }, { "price": "0.4", "size": "100.0" }],
+            "asks": [{ "price": "0.6", "size": "100" }, { "price": "0.7", "size": "100" }]
+        }))?;
+
+        assert_eq!(
+            book.compute_hash(),
+            "172607c1dbcd73533dd80c06b6ace8e909722c4f"
+        );
+        assert!(book.verify_hash());
+
+        book.asks[0].size = dec!(99);
+        assert!(!book.verify_hash());
+
+        Ok(())
+    }
+
     #[tokio::test]
     async fn order_books_should_succeed() -> anyhow::Result<()> {
         let server = MockServer::start();
@@ -1389,20 +1421,23 @@ mod authenticated {
     use alloy::signers::local::LocalSigner;
     use chrono::NaiveDate;
     use httpmock::Method::{DELETE, GET, POST};
+    use kuest_client_sdk::clob::order_builder::{
+        MarketParameters, OfflineOrderBuilder, sign_order,
+    };
     use kuest_client_sdk::clob::types::request::{
         BalanceAllowanceRequest, CancelMarketOrderRequest, DeleteNotificationsRequest,
         OrdersRequest, TradesRequest, UserRewardsEarningRequest,
     };
     use kuest_client_sdk::clob::types::response::{
         ApiKeysResponse, BalanceAllowanceResponse, BanStatusResponse, CancelOrdersResponse,
-        CurrentRewardResponse, Earning, HeartbeatResponse, MakerOrder, MarketRewardResponse,
-        MarketRewardsConfig, NotificationPayload, NotificationResponse, OpenOrderResponse,
-        OrderScoringResponse, Page, PostOrderResponse, RewardsConfig, Token,
+        CancelOutcome, CurrentRewardResponse, Earning, HeartbeatResponse, MakerOrder,
+        MarketRewardResponse, MarketRewardsConfig, NotificationPayload, NotificationResponse,
+        OpenOrderResponse, OrderScoringResponse, Page, PostOrderResponse, RewardsConfig, Token,
         TotalUserEarningResponse, TradeResponse, UserEarningResponse, UserRewardsEarningResponse,
     };
     use kuest_client_sdk::clob::types::{
-        AssetType, OrderStatusType, OrderType, Side, SignableOrder, SignedOrder, TickSize,
-        TradeStatusType, TraderSide,
+        ApiError, AssetType, OrderStatusType, OrderType, Side, SignableOrder, SignedOrder,
+        TickSize, TradeStatusType, TraderSide,
     };
     #[cfg(feature = "heartbeats")]
     use kuest_client_sdk::error::Synchronization;
@@ -1410,7 +1445,7 @@ mod authenticated {
 
     use super::*;
     use crate::common::{
-        API_KEY, PASSPHRASE, KUEST_NONCE, KUEST_SIGNATURE, KUEST_TIMESTAMP, SECRET, SIGNATURE,
+        API_KEY, KUEST_NONCE, KUEST_SIGNATURE, KUEST_TIMESTAMP, PASSPHRASE, SECRET, SIGNATURE,
         TIMESTAMP,
     };
 
@@ -1664,6 +1699,192 @@ mod authenticated {
         Ok(())
     }
 
+    #[tokio::test]
+    async fn post_order_rejection_should_decode_api_error() -> anyhow::Result<()> {
+        let server = MockServer::start();
+        let client = create_authenticated(&server).await?;
+
+        ensure_requirements(&server, token_1(), TickSize::Hundredth);
+
+        let mock = server.mock(|when, then| {
+            when.method(POST).path("/order");
+            then.status(StatusCode::BAD_REQUEST)
+                .json_body(json!({ "error": "not enough balance / allowance" }));
+        });
+
+        let signer = LocalSigner::from_str(PRIVATE_KEY)?.with_chain_id(Some(POLYGON));
+        let signed_order = client.sign(&signer, SignableOrder::default()).await?;
+        let err = client.post_order(signed_order).await.unwrap_err();
+
+        assert_eq!(err.api_error(), Some(ApiError::InsufficientBalance));
+        mock.assert();
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn post_order_response_should_decode_api_error() -> anyhow::Result<()> {
+        let server = MockServer::start();
+        let client = create_authenticated(&server).await?;
+
+        ensure_requirements(&server, token_1(), TickSize::Hundredth);
+
+        let mock = server.mock(|when, then| {
+            when.method(POST).path("/order");
+            then.status(StatusCode::OK).json_body(json!({
+                "errorMsg": "invalid post-only order: order crosses book",
+                "makingAmount": "",
+                "orderID": "",
+                "status": "unmatched",
+                "success": false,
+                "takingAmount": ""
+            }));
+        });
+
+        let signer = LocalSigner::from_str(PRIVATE_KEY)?.with_chain_id(Some(POLYGON));
+        let signed_order = client.sign(&signer, SignableOrder::default()).await?;
+        let response = client.post_order(signed_order).await?;
+
+        assert_eq!(response.api_error(), Some(ApiError::CrossesBook));
+        mock.assert();
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn replace_order_should_cancel_and_post() -> anyhow::Result<()> {
+        let server = MockServer::start();
+        let client = create_authenticated(&server).await?;
+
+        ensure_requirements(&server, token_1(), TickSize::Hundredth);
+
+        let cancel = server.mock(|when, then| {
+            when.method(DELETE)
+                .path("/order")
+                .json_body(json!({ "orderId": "0x01" }));
+            then.status(StatusCode::OK)
+                .json_body(json!({ "canceled": ["0x01"], "not_canceled": {} }));
+        });
+        let post = server.mock(|when, then| {
+            when.method(POST).path("/order");
+            then.status(StatusCode::OK).json_body(json!({
+                "makingAmount": "",
+                "orderID": "0x02",
+                "status": "live",
+                "success": true,
+                "takingAmount": ""
+            }));
+        });
+
+        let signer = LocalSigner::from_str(PRIVATE_KEY)?.with_chain_id(Some(POLYGON));
+        let response = client
+            .replace_order(&signer, "0x01", SignableOrder::default())
+            .await?;
+
+        assert!(response.is_replaced());
+        assert_eq!(response.post?.order_id, "0x02");
+        cancel.assert();
+        post.assert();
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn offline_signed_order_should_match_online_and_post() -> anyhow::Result<()> {
+        let server = MockServer::start();
+        let client = create_authenticated(&server).await?;
+
+        ensure_requirements(&server, token_1(), TickSize::Hundredth);
+
+        let signer = LocalSigner::from_str(PRIVATE_KEY)?.with_chain_id(Some(POLYGON));
+        let online = client
+            .limit_order()
+            .token_id(token_1())
+            .side(Side::Buy)
+            .price(dec!(0.45))
+            .size(dec!(20))
+            .build()
+            .await?;
+
+        let market = MarketParameters::builder()
+            .tick_size(TickSize::Hundredth)
+            .build();
+        let mut offline = OfflineOrderBuilder::limit(signer.address(), market)
+            .token_id(token_1())
+            .side(Side::Buy)
+            .price(dec!(0.45))
+            .size(dec!(20))
+            .build()?;
+        offline.order.salt = online.order.salt;
+        assert_eq!(offline, online);
+
+        let offline = sign_order(&signer, offline, POLYGON, false, API_KEY).await?;
+        let online = client.sign(&signer, online).await?;
+        assert_eq!(offline, online);
+
+        let json = serde_json::to_string(&offline)?;
+        let restored: SignedOrder = serde_json::from_str(&json)?;
+
+        let mock = server.mock(|when, then| {
+            when.method(POST)
+                .path("/order")
+                .json_body(serde_json::to_value(&online).unwrap());
+            then.status(StatusCode::OK).json_body(json!({
+                "makingAmount": "",
+                "orderID": "0x01",
+                "status": "live",
+                "success": true,
+                "takingAmount": ""
+            }));
+        });
+
+        let response = client.post_order(restored).await?;
+
+        assert_eq!(response.order_id, "0x01");
+        mock.assert();
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn replace_order_should_report_filled_order() -> anyhow::Result<()> {
+        let server = MockServer::start();
+        let client = create_authenticated(&server).await?;
+
+        ensure_requirements(&server, token_1(), TickSize::Hundredth);
+
+        server.mock(|when, then| {
+            when.method(DELETE).path("/order");
+            then.status(StatusCode::OK).json_body(json!({
+                "canceled": [],
+                "not_canceled": { "0x01": "order can't be found - already canceled or matched" }
+            }));
+        });
+        server.mock(|when, then| {
+            when.method(POST).path("/order");
+            then.status(StatusCode::BAD_REQUEST)
+                .json_body(json!({ "error": "not enough balance / allowance" }));
+        });
+
+        let signer = LocalSigner::from_str(PRIVATE_KEY)?.with_chain_id(Some(POLYGON));
+        let response = client
+            .replace_order(&signer, "0x01", SignableOrder::default())
+            .await?;
+
+        assert!(!response.is_canceled());
+        assert!(!response.is_placed());
+        assert!(matches!(
+            response.cancel,
+            CancelOutcome::NotCanceled(ApiError::OrderNotFound)
+        ));
+        assert_eq!(
+            response.post.unwrap_err().api_error(),
+            Some(ApiError::InsufficientBalance)
+        );
+
+        Ok(())
+    }
+
     #[tokio::test]
     async fn order_should_succeed() -> anyhow::Result<()> {
         let server = MockServer::start();
@@ -2919,9 +3140,9 @@ mod builder_authenticated {
 
     use super::*;
     use crate::common::{
-        API_KEY, BUILDER_API_KEY, BUILDER_PASSPHRASE, PASSPHRASE, KUEST_BUILDER_API_KEY,
+        API_KEY, BUILDER_API_KEY, BUILDER_PASSPHRASE, KUEST_BUILDER_API_KEY,
         KUEST_BUILDER_PASSPHRASE, KUEST_BUILDER_SIGNATURE, KUEST_BUILDER_TIMESTAMP, KUEST_NONCE,
-        KUEST_SIGNATURE, KUEST_TIMESTAMP, SECRET, SIGNATURE, TIMESTAMP,
+        KUEST_SIGNATURE, KUEST_TIMESTAMP, PASSPHRASE, SECRET, SIGNATURE, TIMESTAMP,
     };
 
     #[tokio::test]
@@ -3230,3 +3451,1000 @@ mod builder_authenticated {
         Ok(())
     }
 }
+
+#[cfg(feature = "retry")]
+mod retry {
+    use std::time::Duration;
+
+    use alloy::signers::Signer as _;
+    use alloy::signers::local::LocalSigner;
+    use httpmock::Method::{GET, POST};
+    use kuest_client_sdk::clob::types::{SignableOrder, TickSize};
+    use kuest_client_sdk::error::{Kind, Status};
+    use kuest_client_sdk::retry::RetryPolicy;
+
+    use super::*;
+    use crate::common::create_authenticated_with_config;
+
+    fn policy() -> RetryPolicy {
+        RetryPolicy::builder()
+            .max_retries(2)
+            .initial_backoff(Duration::from_millis(1))
+            .build()
+    }
+
+    #[tokio::test]
+    async fn get_should_be_retried_until_exhausted() -> anyhow::Result<()> {
+        let server = MockServer::start();
+        let config = Config::builder().retry_policy(policy()).build();
+        let client = Client::new(&server.base_url(), config)?;
+
+        let mock = server.mock(|when, then| {
+            when.method(GET).path("/");
+            then.status(StatusCode::SERVICE_UNAVAILABLE)
+                .body("unavailable");
+        });
+
+        let err = client.ok().await.unwrap_err();
+        let status = err.downcast_ref::<Status>().unwrap();
+
+        assert_eq!(err.kind(), Kind::Status);
+        assert_eq!(status.status_code, StatusCode::SERVICE_UNAVAILABLE);
+        mock.assert_calls(3);
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn retry_after_should_be_honoured() -> anyhow::Result<()> {
+        let server = MockServer::start();
+        let config = Config::builder()
+            .retry_policy(
+                RetryPolicy::builder()
+                    .max_retries(1)
+                    .initial_backoff(Duration::from_secs(60))
+                    .build(),
+            )
+            .build();
+        let client = Client::new(&server.base_url(), config)?;
+
+        let mock = server.mock(|when, then| {
+            when.method(GET).path("/");
+            then.status(StatusCode::TOO_MANY_REQUESTS)
+                .header("Retry-After", "0");
+        });
+
+        let result = tokio::time::timeout(Duration::from_secs(5), client.ok()).await?;
+
+        result.unwrap_err();
+        mock.assert_calls(2);
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn retry_after_should_be_capped_by_max_backoff() -> anyhow::Result<()> {
+        let server = MockServer::start();
+        let config = Config::builder()
+            .retry_policy(
+                RetryPolicy::builder()
+                    .max_retries(1)
+                    .max_backoff(Duration::from_millis(10))
+                    .build(),
+            )
+            .build();
+        let client = Client::new(&server.base_url(), config)?;
+
+        let mock = server.mock(|when, then| {
+            when.method(GET).path("/");
+            then.status(StatusCode::SERVICE_UNAVAILABLE)
+                .header("Retry-After", "3600");
+        });
+
+        let result = tokio::time::timeout(Duration::from_secs(5), client.ok()).await?;
+
+        result.unwrap_err();
+        mock.assert_calls(2);
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn client_errors_should_not_be_retried() -> anyhow::Result<()> {
+        let server = MockServer::start();
+        let config = Config::builder().retry_policy(policy()).build();
+        let client = Client::new(&server.base_url(), config)?;
+
+        let mock = server.mock(|when, then| {
+            when.method(GET).path("/");
+            then.status(StatusCode::BAD_REQUEST);
+        });
+
+        client.ok().await.unwrap_err();
+        mock.assert();
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn post_order_should_not_be_retried_by_default() -> anyhow::Result<()> {
+        let server = MockServer::start();
+        let config = Config::builder()
+            .use_server_time(true)
+            .retry_policy(policy())
+            .build();
+        let client = create_authenticated_with_config(&server, config).await?;
+        ensure_requirements(&server, token_1(), TickSize::Hundredth);
+
+        let mock = server.mock(|when, then| {
+            when.method(POST).path("/order");
+            then.status(StatusCode::BAD_GATEWAY);
+        });
+
+        let signer = LocalSigner::from_str(PRIVATE_KEY)?.with_chain_id(Some(POLYGON));
+        let signed_order = client.sign(&signer, SignableOrder::default()).await?;
+        client.post_order(signed_order).await.unwrap_err();
+
+        mock.assert();
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn post_order_should_be_retried_when_opted_in() -> anyhow::Result<()> {
+        let server = MockServer::start();
+        let config = Config::builder()
+            .use_server_time(true)
+            .retry_policy(
+                RetryPolicy::builder()
+                    .max_retries(2)
+                    .initial_backoff(Duration::from_millis(1))
+                    .retry_post_order(true)
+                    .build(),
+            )
+            .build();
+        let client = create_authenticated_with_config(&server, config).await?;
+        ensure_requirements(&server, token_1(), TickSize::Hundredth);
+
+        let mock = server.mock(|when, then| {
+            when.method(POST).path("/order");
+            then.status(StatusCode::BAD_GATEWAY);
+        });
+
+        let signer = LocalSigner::from_str(PRIVATE_KEY)?.with_chain_id(Some(POLYGON));
+        let signed_order = client.sign(&signer, SignableOrder::default()).await?;
+        client.post_order(signed_order).await.unwrap_err();
+
+        mock.assert_calls(3);
+
+        Ok(())
+    }
+}
+
+mod rate_limit {
+    use std::time::{Duration, Instant};
+
+    use httpmock::Method::GET;
+    use kuest_client_sdk::clob::rate_limit::{
+        EndpointGroup, Limit, Mode, RateLimitExceeded, RateLimits,
+    };
+    use kuest_client_sdk::clob::types::request::MidpointRequest;
+    use kuest_client_sdk::error::Kind;
+
+    use super::*;
+
+    #[tokio::test]
+    async fn fail_fast_should_reject_requests_over_quota() -> anyhow::Result<()> {
+        let server = MockServer::start();
+        let limits = RateLimits::builder()
+            .book(Limit::new(1, Duration::from_secs(60)))
+            .mode(Mode::FailFast)
+            .build();
+        let config = Config::builder().rate_limits(limits).build();
+        let client = Client::new(&server.base_url(), config)?;
+
+        let mock = server.mock(|when, then| {
+            when.method(GET).path("/midpoint");
+            then.status(StatusCode::OK)
+                .json_body(json!({ "mid": "0.5" }));
+        });
+
+        let request = MidpointRequest::builder().token_id(token_1()).build();
+        client.midpoint(&request).await?;
+
+        let err = client.midpoint(&request).await.unwrap_err();
+        let exceeded = err.downcast_ref::<RateLimitExceeded>().unwrap();
+
+        assert_eq!(err.kind(), Kind::RateLimited);
+        assert_eq!(exceeded.group, EndpointGroup::Book);
+        mock.assert_calls(1);
+
+        // Endpoints outside the exhausted group are unaffected
+        let ok = server.mock(|when, then| {
+            when.method(GET).path("/");
+            then.status(StatusCode::OK).body("\"OK\"");
+        });
+        client.ok().await?;
+        ok.assert();
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn wait_should_delay_requests_over_quota() -> anyhow::Result<()> {
+        let server = MockServer::start();
+        let limits = RateLimits::builder()
+            .book(Limit::new(1, Duration::from_millis(100)))
+            .build();
+        let config = Config::builder().rate_limits(limits).build();
+        let client = Client::new(&server.base_url(), config)?;
+
+        let mock = server.mock(|when, then| {
+            when.method(GET).path("/midpoint");
+            then.status(StatusCode::OK)
+                .json_body(json!({ "mid": "0.5" }));
+        });
+
+        let request = MidpointRequest::builder().token_id(token_1()).build();
+        let start = Instant::now();
+        client.midpoint(&request).await?;
+        client.midpoint(&request).await?;
+
+        assert!(start.elapsed() >= Duration::from_millis(100));
+        mock.assert_calls(2);
+
+        Ok(())
+    }
+}
+
+mod risk {
+    use alloy::signers::Signer as _;
+    use alloy::signers::local::LocalSigner;
+    use httpmock::Method::{DELETE, GET, POST};
+    use kuest_client_sdk::clob::risk::{RiskGuard, RiskLimits, RiskViolation};
+    use kuest_client_sdk::clob::types::{Side, TickSize};
+    use kuest_client_sdk::error::Kind;
+
+    use super::*;
+    use crate::common::create_authenticated_with_config;
+
+    #[tokio::test]
+    async fn guard_should_reject_orders_before_sending() -> anyhow::Result<()> {
+        let server = MockServer::start();
+        let guard = RiskGuard::new(
+            RiskLimits::builder()
+                .max_order_notional(dec!(10))
+                .max_token_exposure(dec!(15))
+                .price_band(dec!(0.1))
+                .build(),
+        );
+        let config = Config::builder()
+            .use_server_time(true)
+            .risk_guard(guard.clone())
+            .build();
+        let client = create_authenticated_with_config(&server, config).await?;
+        let signer = LocalSigner::from_str(PRIVATE_KEY)?.with_chain_id(Some(POLYGON));
+
+        ensure_requirements(&server, token_1(), TickSize::Hundredth);
+        let midpoint = server.mock(|when, then| {
+            when.method(GET).path("/midpoint");
+            then.status(StatusCode::OK)
+                .json_body(json!({ "mid": "0.5" }));
+        });
+        let post = server.mock(|when, then| {
+            when.method(POST).path("/order");
+            then.status(StatusCode::OK).json_body(json!({
+                "makingAmount": "",
+                "orderID": "0x01",
+                "status": "live",
+                "success": true,
+                "takingAmount": ""
+            }));
+        });
+        let cancel = server.mock(|when, then| {
+            when.method(DELETE).path("/order");
+            then.status(StatusCode::OK)
+                .json_body(json!({ "canceled": ["0x01"], "not_canceled": {} }));
+        });
+
+        let order = |price: Decimal, size: Decimal| {
+            client
+                .limit_order()
+                .token_id(token_1())
+                .side(Side::Buy)
+                .price(price)
+                .size(size)
+                .build()
+        };
+
+        let signed = client
+            .sign(&signer, order(dec!(0.5), dec!(30)).await?)
+            .await?;
+        let err = client.post_order(signed).await.unwrap_err();
+        assert_eq!(err.kind(), Kind::Validation);
+        assert!(matches!(
+            err.downcast_ref::<RiskViolation>(),
+            Some(RiskViolation::OrderNotional { .. })
+        ));
+
+        let signed = client
+            .sign(&signer, order(dec!(0.7), dec!(10)).await?)
+            .await?;
+        let err = client.post_order(signed).await.unwrap_err();
+        assert!(matches!(
+            err.downcast_ref::<RiskViolation>(),
+            Some(RiskViolation::PriceBand { .. })
+        ));
+
+        let signed = client
+            .sign(&signer, order(dec!(0.5), dec!(20)).await?)
+            .await?;
+        client.post_order(signed).await?;
+        assert_eq!(
+            guard.state().token_exposure.get(&token_1()),
+            Some(&dec!(10))
+        );
+
+        let signed = client
+            .sign(&signer, order(dec!(0.5), dec!(20)).await?)
+            .await?;
+        let err = client.post_order(signed).await.unwrap_err();
+        assert!(matches!(
+            err.downcast_ref::<RiskViolation>(),
+            Some(RiskViolation::TokenExposure { .. })
+        ));
+
+        client.cancel_order("0x01").await?;
+        assert!(guard.state().token_exposure.is_empty());
+
+        midpoint.assert_calls(4);
+        post.assert_calls(1);
+        cancel.assert();
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn kill_switch_should_cancel_orders_and_block_new_ones() -> anyhow::Result<()> {
+        let server = MockServer::start();
+        let config = Config::builder()
+            .use_server_time(true)
+            .risk_guard(RiskGuard::new(RiskLimits::default()))
+            .build();
+        let client = create_authenticated_with_config(&server, config).await?;
+        let signer = LocalSigner::from_str(PRIVATE_KEY)?.with_chain_id(Some(POLYGON));
+
+        ensure_requirements(&server, token_1(), TickSize::Hundredth);
+        let cancel_all = server.mock(|when, then| {
+            when.method(DELETE).path("/cancel-all");
+            then.status(StatusCode::OK)
+                .json_body(json!({ "canceled": [], "not_canceled": {} }));
+        });
+        let post = server.mock(|when, then| {
+            when.method(POST).path("/order");
+            then.status(StatusCode::OK).body("{}");
+        });
+
+        client.kill_switch().await?;
+        assert!(client.risk_guard().unwrap().is_halted());
+
+        let order = client
+            .limit_order()
+            .token_id(token_1())
+            .side(Side::Buy)
+            .price(dec!(0.5))
+            .size(Decimal::TEN)
+            .build()
+            .await?;
+        let signed = client.sign(&signer, order).await?;
+        let err = client.post_order(signed).await.unwrap_err();
+
+        assert!(matches!(
+            err.downcast_ref::<RiskViolation>(),
+            Some(RiskViolation::Halted)
+        ));
+        cancel_all.assert();
+        post.assert_calls(0);
+
+        Ok(())
+    }
+}
+
+mod ladder {
+    use alloy::signers::Signer as _;
+    use alloy::signers::local::LocalSigner;
+    use httpmock::Method::{GET, POST};
+    use kuest_client_sdk::clob::ladder::SizeCurve;
+    use kuest_client_sdk::clob::types::{Side, TickSize};
+    use kuest_client_sdk::error::Validation;
+
+    use super::*;
+
+    fn ensure_book(server: &MockServer) {
+        server.mock(|when, then| {
+            when.method(GET).path("/book");
+            then.status(StatusCode::OK).json_body(json!({
+                "market": "0xbd31dc8a20211944f6b70f31557f1001557b59905b7738480ca09bd4532f84af",
+                "asset_id": token_1(),
+                "timestamp": "1000",
+                "bids": [],
+                "asks": [],
+                "min_order_size": "5",
+                "neg_risk": false,
+                "tick_size": "0.01",
+            }));
+        });
+    }
+
+    #[tokio::test]
+    async fn ladder_should_respect_tick_and_minimum_size() -> anyhow::Result<()> {
+        let server = MockServer::start();
+        let client = create_authenticated(&server).await?;
+
+        ensure_requirements(&server, token_1(), TickSize::Hundredth);
+        ensure_book(&server);
+
+        let orders = client
+            .ladder()
+            .token_id(token_1())
+            .centre(dec!(0.505))
+            .spacing(2)
+            .levels(10)
+            .sizes(SizeCurve::Linear {
+                base: dec!(20.555),
+                step: dec!(-2),
+            })
+            .build()
+            .await?;
+
+        // Sizes below the minimum of 5 leave out the last two levels on each side
+        assert_eq!(orders.len(), 16);
+
+        // 0.505 - 0.02 rounds down to 0.48 and 0.505 + 0.02 rounds up to 0.53
+        let bid = &orders[0].order;
+        assert_eq!(bid.side, Side::Buy as u8);
+        assert_eq!(bid.takerAmount, U256::from(20_550_000));
+        assert_eq!(bid.makerAmount, U256::from(9_864_000));
+
+        let ask = &orders[1].order;
+        assert_eq!(ask.side, Side::Sell as u8);
+        assert_eq!(ask.makerAmount, U256::from(20_550_000));
+        assert_eq!(ask.takerAmount, U256::from(10_891_500));
+
+        let err = client
+            .ladder()
+            .token_id(token_1())
+            .centre(dec!(0.5))
+            .levels(3)
+            .sizes(SizeCurve::Flat(dec!(1)))
+            .build()
+            .await
+            .unwrap_err();
+        let msg = &err.downcast_ref::<Validation>().unwrap().reason;
+        assert!(msg.contains("has a valid price and a size of at least 5"));
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn ladder_should_stop_at_the_edge_of_the_price_range() -> anyhow::Result<()> {
+        let server = MockServer::start();
+        let client = create_authenticated(&server).await?;
+
+        ensure_requirements(&server, token_1(), TickSize::Hundredth);
+        ensure_book(&server);
+
+        // Doubling sizes overflow long before the 120th level, which is never reached since
+        // prices run out after 49 levels on each side
+        let orders = client
+            .ladder()
+            .token_id(token_1())
+            .centre(dec!(0.5))
+            .levels(120)
+            .sizes(SizeCurve::Geometric {
+                base: dec!(10),
+                ratio: dec!(2),
+            })
+            .build()
+            .await?;
+
+        assert_eq!(orders.len(), 98);
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn ladder_should_post_in_batches() -> anyhow::Result<()> {
+        let server = MockServer::start();
+        let client = create_authenticated(&server).await?;
+
+        ensure_requirements(&server, token_1(), TickSize::Hundredth);
+        ensure_book(&server);
+
+        let mock = server.mock(|when, then| {
+            when.method(POST).path("/orders");
+            then.status(StatusCode::OK).json_body(json!([{
+                "makingAmount": "",
+                "orderID": "0x01",
+                "status": "live",
+                "success": true,
+                "takingAmount": ""
+            }]));
+        });
+
+        let signer = LocalSigner::from_str(PRIVATE_KEY)?.with_chain_id(Some(POLYGON));
+        let responses = client
+            .ladder()
+            .token_id(token_1())
+            .side(Side::Buy)
+            .centre(dec!(0.5))
+            .levels(20)
+            .sizes(SizeCurve::Flat(dec!(10)))
+            .post(&signer)
+            .await?;
+
+        // 20 bids are posted as a batch of 15 and a batch of 5
+        mock.assert_calls(2);
+        assert_eq!(responses.len(), 2);
+
+        Ok(())
+    }
+}
+
+#[cfg(feature = "refresh")]
+mod refresh {
+    use std::time::Duration;
+
+    use alloy::signers::Signer as _;
+    use alloy::signers::local::LocalSigner;
+    use httpmock::Method::{DELETE, POST};
+    use kuest_client_sdk::clob::refresh::{RefreshOrder, RefreshSchedule};
+    use kuest_client_sdk::clob::types::{Side, TickSize};
+    use kuest_client_sdk::error::Validation;
+
+    use super::*;
+
+    #[tokio::test]
+    async fn refresh_gtd_should_cancel_and_repost_until_stopped() -> anyhow::Result<()> {
+        let server = MockServer::start();
+        let client = create_authenticated(&server).await?;
+
+        ensure_requirements(&server, token_1(), TickSize::Hundredth);
+        let post = server.mock(|when, then| {
+            when.method(POST).path("/orders");
+            then.status(StatusCode::OK).json_body(json!([{
+                "makingAmount": "",
+                "orderID": "0x01",
+                "status": "live",
+                "success": true,
+                "takingAmount": ""
+            }]));
+        });
+        let cancel = server.mock(|when, then| {
+            when.method(DELETE)
+                .path("/orders")
+                .json_body(json!(["0x01"]));
+            then.status(StatusCode::OK)
+                .json_body(json!({ "canceled": ["0x01"], "notCanceled": {} }));
+        });
+
+        let signer = LocalSigner::from_str(PRIVATE_KEY)?.with_chain_id(Some(POLYGON));
+        let order = RefreshOrder::builder()
+            .token_id(token_1())
+            .side(Side::Buy)
+            .price(dec!(0.5))
+            .size(dec!(10))
+            .build();
+        // Rounds start 50ms after each other, the default 10s before the orders leave the book
+        let schedule = RefreshSchedule::builder()
+            .lifetime(Duration::from_millis(10_050))
+            .build();
+
+        let handle = client.refresh_gtd(signer, vec![order], schedule)?;
+        while cancel.calls() < 2 {
+            tokio::time::sleep(Duration::from_millis(10)).await;
+        }
+        assert!(handle.is_active());
+
+        let order_ids = handle.stop().await;
+        assert_eq!(order_ids, vec!["0x01".to_owned()]);
+        assert_eq!(post.calls(), cancel.calls() + 1);
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn refresh_gtd_should_require_lead_shorter_than_lifetime() -> anyhow::Result<()> {
+        let server = MockServer::start();
+        let client = create_authenticated(&server).await?;
+
+        let signer = LocalSigner::from_str(PRIVATE_KEY)?.with_chain_id(Some(POLYGON));
+        let order = RefreshOrder::builder()
+            .token_id(token_1())
+            .side(Side::Buy)
+            .price(dec!(0.5))
+            .size(dec!(10))
+            .build();
+        let schedule = RefreshSchedule::builder()
+            .lifetime(Duration::from_secs(10))
+            .build();
+
+        let err = client
+            .refresh_gtd(signer, vec![order], schedule)
+            .unwrap_err();
+        let msg = &err.downcast_ref::<Validation>().unwrap().reason;
+        assert!(msg.contains("must be shorter than the lifetime"), "{msg}");
+
+        Ok(())
+    }
+}
+
+mod quote {
+    use httpmock::Method::GET;
+    use kuest_client_sdk::clob::quote::{BinaryQuote, Holdings, Outcome, QuoteLevel};
+    use kuest_client_sdk::clob::types::{Side, TickSize};
+
+    use super::*;
+
+    #[tokio::test]
+    async fn binary_quote_should_prefer_inventory() -> anyhow::Result<()> {
+        let server = MockServer::start();
+        let client = create_authenticated(&server).await?;
+
+        ensure_requirements(&server, token_1(), TickSize::Hundredth);
+        server.mock(|when, then| {
+            when.method(GET).path("/markets/0x01");
+            then.status(StatusCode::OK).json_body(json!({
+                "enable_order_book": true,
+                "active": true,
+                "closed": false,
+                "archived": false,
+                "accepting_orders": true,
+                "accepting_order_timestamp": null,
+                "minimum_order_size": "5",
+                "minimum_tick_size": "0.01",
+                "condition_id": "0x0000000000000000000000000000000000000000000000000000000000000001",
+                "question_id": "",
+                "question": "Will BTC close above $50k today?",
+                "description": "",
+                "market_slug": "btc-close-above-50k",
+                "end_date_iso": null,
+                "game_start_time": null,
+                "seconds_delay": 0,
+                "fpmm": "",
+                "maker_base_fee": "0",
+                "taker_base_fee": "0",
+                "notifications_enabled": false,
+                "neg_risk": false,
+                "neg_risk_market_id": "",
+                "neg_risk_request_id": "",
+                "icon": "",
+                "image": "",
+                "rewards": { "rates": null, "min_size": "0", "max_spread": "0" },
+                "is_50_50_outcome": false,
+                "tokens": [
+                    { "token_id": token_2(), "outcome": "No", "price": "0.55" },
+                    { "token_id": token_1(), "outcome": "Yes", "price": "0.45" }
+                ],
+                "tags": []
+            }));
+        });
+
+        let quote = BinaryQuote::builder()
+            .bid(QuoteLevel::new(dec!(0.40), dec!(100)))
+            .ask(QuoteLevel::new(dec!(0.45), dec!(20)))
+            .build();
+        let holdings = Holdings::builder().no(dec!(50)).build();
+
+        let legs = client.binary_quote("0x01", &quote, &holdings).await?;
+
+        let summary: Vec<_> = legs
+            .iter()
+            .map(|leg| (leg.quote_side, leg.outcome, leg.side, leg.price, leg.size))
+            .collect();
+        assert_eq!(
+            summary,
+            vec![
+                (Side::Buy, Outcome::No, Side::Sell, dec!(0.60), dec!(50)),
+                (Side::Buy, Outcome::Yes, Side::Buy, dec!(0.40), dec!(50)),
+                (Side::Sell, Outcome::No, Side::Buy, dec!(0.55), dec!(20)),
+            ]
+        );
+
+        assert_eq!(legs[0].token_id, token_2());
+        assert_eq!(legs[0].order.order.tokenId, token_2());
+        assert_eq!(legs[1].order.order.tokenId, token_1());
+        // Buying 20 NO at 0.55 commits 11 USDC
+        assert_eq!(legs[2].order.order.makerAmount, U256::from(11_000_000));
+
+        Ok(())
+    }
+}
+
+mod funds {
+    use alloy::signers::Signer as _;
+    use alloy::signers::local::LocalSigner;
+    use httpmock::Method::{GET, POST};
+    use kuest_client_sdk::clob::funds::{BalanceCheck, Funds, InsufficientFunds};
+    use kuest_client_sdk::clob::types::{Side, TickSize};
+    use kuest_client_sdk::error::Kind;
+
+    use super::*;
+    use crate::common::create_authenticated_with_config;
+
+    #[tokio::test]
+    async fn post_order_should_fail_on_insufficient_funds() -> anyhow::Result<()> {
+        let server = MockServer::start();
+        let config = Config::builder()
+            .use_server_time(true)
+            .balance_check(BalanceCheck::Refresh)
+            .build();
+        let client = create_authenticated_with_config(&server, config).await?;
+        let signer = LocalSigner::from_str(PRIVATE_KEY)?.with_chain_id(Some(POLYGON));
+
+        ensure_requirements(&server, token_1(), TickSize::Hundredth);
+        let orders = server.mock(|when, then| {
+            when.method(GET).path("/data/orders");
+            then.status(StatusCode::OK).json_body(json!({
+                "data": [
+                    {
+                        "id": "1",
+                        "status": "LIVE",
+                        "owner": "ffffffff-ffff-ffff-ffff-ffffffffffff",
+                        "maker_address": "0x2222222222222222222222222222222222222222",
+                        "market": "0x000000000000000000000000000000000000000000000000006d61726b657461",
+                        "asset_id": token_1(),
+                        "side": "buy",
+                        "original_size": "10.0",
+                        "size_matched": "2.5",
+                        "price": "0.45",
+                        "associate_trades": [],
+                        "outcome": "YES",
+                        "created_at": 1_705_322_096,
+                        "expiration": "0",
+                        "order_type": "GTC"
+                    }
+                ],
+                "limit": 1,
+                "count": 1,
+                "next_cursor": "LTE="
+            }));
+        });
+        let balance = server.mock(|when, then| {
+            when.method(GET)
+                .path("/balance-allowance")
+                .query_param("asset_type", "COLLATERAL");
+            then.status(StatusCode::OK).json_body(json!({
+                "balance": "12000000",
+                "allowances": {}
+            }));
+        });
+        let update = server.mock(|when, then| {
+            when.method(GET)
+                .path("/balance-allowance/update")
+                .query_param("asset_type", "COLLATERAL");
+            then.status(StatusCode::OK).json_body(json!(null));
+        });
+        let post = server.mock(|when, then| {
+            when.method(POST).path("/order");
+            then.status(StatusCode::OK).body("{}");
+        });
+
+        let order = client
+            .limit_order()
+            .token_id(token_1())
+            .side(Side::Buy)
+            .price(dec!(0.5))
+            .size(dec!(20))
+            .build()
+            .await?;
+        let signed = client.sign(&signer, order).await?;
+        let err = client.post_order(signed).await.unwrap_err();
+
+        assert_eq!(err.kind(), Kind::Validation);
+        let insufficient = err.downcast_ref::<InsufficientFunds>().unwrap();
+        let [shortfall] = insufficient.shortfalls.as_slice() else {
+            panic!("expected a single shortfall, found {insufficient}");
+        };
+        assert_eq!(shortfall.token_id, None);
+        assert_eq!(shortfall.funds, Funds::Balance);
+        assert_eq!(shortfall.required, dec!(10));
+        // 7.5 unmatched shares of the open order at 0.45
+        assert_eq!(shortfall.committed, dec!(3.375));
+        assert_eq!(shortfall.missing(), dec!(1.375));
+
+        orders.assert();
+        balance.assert_calls(2);
+        update.assert();
+        post.assert_calls(0);
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn check_funds_should_list_sells_per_token_and_reuse_buy_commitment() -> anyhow::Result<()>
+    {
+        let server = MockServer::start();
+        let config = Config::builder().use_server_time(true).build();
+        let client = create_authenticated_with_config(&server, config).await?;
+        let signer = LocalSigner::from_str(PRIVATE_KEY)?.with_chain_id(Some(POLYGON));
+
+        let open_order = |side: &str| {
+            json!({
+                "data": [
+                    {
+                        "id": "1",
+                        "status": "LIVE",
+                        "owner": "ffffffff-ffff-ffff-ffff-ffffffffffff",
+                        "maker_address": "0x2222222222222222222222222222222222222222",
+                        "market": "0x000000000000000000000000000000000000000000000000006d61726b657461",
+                        "asset_id": token_1(),
+                        "side": side,
+                        "original_size": "10.0",
+                        "size_matched": "2.5",
+                        "price": "0.45",
+                        "associate_trades": [],
+                        "outcome": "YES",
+                        "created_at": 1_705_322_096,
+                        "expiration": "0",
+                        "order_type": "GTC"
+                    }
+                ],
+                "limit": 1,
+                "count": 1,
+                "next_cursor": "LTE="
+            })
+        };
+
+        ensure_requirements(&server, token_1(), TickSize::Hundredth);
+        let all_orders = server.mock(|when, then| {
+            when.method(GET)
+                .path("/data/orders")
+                .query_param_missing("asset_id");
+            then.status(StatusCode::OK).json_body(open_order("buy"));
+        });
+        let token_orders = server.mock(|when, then| {
+            when.method(GET)
+                .path("/data/orders")
+                .query_param("asset_id", token_1().to_string());
+            then.status(StatusCode::OK).json_body(open_order("sell"));
+        });
+        server.mock(|when, then| {
+            when.method(GET)
+                .path("/balance-allowance")
+                .query_param("asset_type", "COLLATERAL");
+            then.status(StatusCode::OK).json_body(json!({
+                "balance": "30000000",
+                "allowances": {}
+            }));
+        });
+        server.mock(|when, then| {
+            when.method(GET)
+                .path("/balance-allowance")
+                .query_param("asset_type", "CONDITIONAL");
+            then.status(StatusCode::OK).json_body(json!({
+                "balance": "100000000",
+                "allowances": {}
+            }));
+        });
+
+        let mut orders = Vec::new();
+        for side in [Side::Buy, Side::Sell] {
+            let order = client
+                .limit_order()
+                .token_id(token_1())
+                .side(side)
+                .price(dec!(0.5))
+                .size(dec!(20))
+                .build()
+                .await?;
+            orders.push(client.sign(&signer, order).await?);
+        }
+
+        // 3.375 committed to the open buy, then 10 more for each buy checked
+        client.check_funds(&orders, BalanceCheck::Strict).await?;
+        client.check_funds(&orders, BalanceCheck::Strict).await?;
+        all_orders.assert_calls(1);
+
+        // 33.375 would exceed the balance of 30, so the open buys are listed again
+        client.check_funds(&orders, BalanceCheck::Strict).await?;
+        all_orders.assert_calls(2);
+        token_orders.assert_calls(3);
+
+        Ok(())
+    }
+}
+
+mod batch {
+    use alloy::signers::Signer as _;
+    use alloy::signers::local::LocalSigner;
+    use httpmock::Method::POST;
+    use kuest_client_sdk::clob::batch::OrderOutcome;
+    use kuest_client_sdk::clob::types::{ApiError, Side, TickSize};
+
+    use super::*;
+
+    fn batch_len(req: &httpmock::HttpMockRequest) -> usize {
+        serde_json::from_slice::<Vec<serde_json::Value>>(req.body_ref())
+            .map_or(0, |orders| orders.len())
+    }
+
+    #[tokio::test]
+    async fn post_batch_should_report_each_order() -> anyhow::Result<()> {
+        let server = MockServer::start();
+        let client = create_authenticated(&server).await?;
+        let signer = LocalSigner::from_str(PRIVATE_KEY)?.with_chain_id(Some(POLYGON));
+
+        ensure_requirements(&server, token_1(), TickSize::Hundredth);
+        let responses: Vec<_> = (1..=15)
+            .map(|i| {
+                if i == 15 {
+                    json!({
+                        "errorMsg": "order crosses book",
+                        "makingAmount": "",
+                        "orderID": "",
+                        "status": "unmatched",
+                        "success": false,
+                        "takingAmount": ""
+                    })
+                } else {
+                    json!({
+                        "errorMsg": "",
+                        "makingAmount": "",
+                        "orderID": format!("0x{i:02x}"),
+                        "status": "live",
+                        "success": true,
+                        "takingAmount": ""
+                    })
+                }
+            })
+            .collect();
+        let full = server.mock(|when, then| {
+            when.method(POST)
+                .path("/orders")
+                .is_true(|req| batch_len(req) == 15);
+            then.status(StatusCode::OK).json_body(json!(responses));
+        });
+        let rest = server.mock(|when, then| {
+            when.method(POST)
+                .path("/orders")
+                .is_true(|req| batch_len(req) == 1);
+            then.status(StatusCode::INTERNAL_SERVER_ERROR)
+                .body("internal error");
+        });
+
+        let mut orders = Vec::new();
+        for i in 1..=16 {
+            let order = client
+                .limit_order()
+                .token_id(token_1())
+                .side(Side::Buy)
+                .price(dec!(0.5))
+                .size(Decimal::from(i))
+                .build()
+                .await?;
+            orders.push(client.sign(&signer, order).await?);
+        }
+
+        let outcomes = client.post_batch(orders, 2).await;
+
+        assert_eq!(outcomes.len(), 16);
+        assert_eq!(outcomes[0].order_id(), Some("0x01"));
+        assert_eq!(outcomes[13].order_id(), Some("0x0e"));
+        assert!(outcomes[..14].iter().all(OrderOutcome::is_posted));
+        assert!(matches!(
+            &outcomes[14],
+            OrderOutcome::Rejected {
+                reason: ApiError::CrossesBook,
+                response: Some(_),
+            }
+        ));
+        assert!(!outcomes[14].is_retryable());
+        assert!(outcomes[15].is_retryable());
+
+        full.assert();
+        rest.assert();
+
+        Ok(())
+    }
+}
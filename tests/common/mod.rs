@@ -68,6 +68,14 @@ pub fn token_2() -> U256 {
 }
 
 pub async fn create_authenticated(server: &MockServer) -> anyhow::Result<TestClient> {
+    let config = Config::builder().use_server_time(true).build();
+    create_authenticated_with_config(server, config).await
+}
+
+pub async fn create_authenticated_with_config(
+    server: &MockServer,
+    config: Config,
+) -> anyhow::Result<TestClient> {
     let signer = LocalSigner::from_str(PRIVATE_KEY)?.with_chain_id(Some(POLYGON));
 
     let mock = server.mock(|when, then| {
@@ -89,7 +97,6 @@ pub async fn create_authenticated(server: &MockServer) -> anyhow::Result<TestCli
             .json_body(TIMESTAMP.parse::<i64>().unwrap());
     });
 
-    let config = Config::builder().use_server_time(true).build();
     let client = Client::new(&server.base_url(), config)?
         .authentication_builder(&signer)
         .authenticate()
@@ -15,8 +15,13 @@ fn test_condition_id() -> B256 {
 }
 
 mod health {
+    #[cfg(feature = "retry")]
+    use std::time::Duration;
+
     use httpmock::{Method::GET, MockServer};
     use kuest_client_sdk::data::Client;
+    #[cfg(feature = "retry")]
+    use kuest_client_sdk::retry::RetryPolicy;
     use reqwest::StatusCode;
     use serde_json::json;
 
@@ -39,6 +44,27 @@ mod health {
 
         Ok(())
     }
+
+    #[cfg(feature = "retry")]
+    #[tokio::test]
+    async fn health_should_retry_server_errors() -> anyhow::Result<()> {
+        let server = MockServer::start();
+        let policy = RetryPolicy::builder()
+            .max_retries(1)
+            .initial_backoff(Duration::from_millis(1))
+            .build();
+        let client = Client::new(&server.base_url())?.with_retry_policy(policy);
+
+        let mock = server.mock(|when, then| {
+            when.method(GET).path("/");
+            then.status(StatusCode::INTERNAL_SERVER_ERROR);
+        });
+
+        client.health().await.unwrap_err();
+        mock.assert_calls(2);
+
+        Ok(())
+    }
 }
 
 mod positions {
@@ -795,9 +821,7 @@ mod builder_volume {
 
     use chrono::{DateTime, Utc};
     use httpmock::{Method::GET, MockServer};
-    use kuest_client_sdk::data::{
-        Client, types::TimePeriod, types::request::BuilderVolumeRequest,
-    };
+    use kuest_client_sdk::data::{Client, types::TimePeriod, types::request::BuilderVolumeRequest};
     use reqwest::StatusCode;
     use rust_decimal_macros::dec;
     use serde_json::json;
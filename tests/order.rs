@@ -7,6 +7,7 @@
 mod common;
 
 use std::str::FromStr as _;
+use std::time::Duration;
 
 use alloy::primitives::U256;
 use chrono::{DateTime, Utc};
@@ -595,6 +596,77 @@ mod limit {
         Ok(())
     }
 
+    #[tokio::test]
+    async fn expires_in_should_add_security_buffer_to_server_time() -> anyhow::Result<()> {
+        let server = MockServer::start();
+        let client = create_authenticated(&server).await?;
+
+        // The server time of 100,000 is mocked by `create_authenticated`
+        ensure_requirements(&server, token_1(), TickSize::Tenth);
+
+        let signable_order = client
+            .limit_order()
+            .token_id(token_1())
+            .price(dec!(0.5))
+            .size(dec!(21.04))
+            .side(Side::Buy)
+            .expires_in(Duration::from_secs(90))
+            .build()
+            .await?;
+
+        assert_eq!(signable_order.order_type, OrderType::GTD);
+        assert_eq!(
+            signable_order.order.expiration,
+            U256::from(100_000 + 60 + 90)
+        );
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn expires_in_should_fail_with_expiration_or_non_gtd() -> anyhow::Result<()> {
+        let server = MockServer::start();
+        let client = create_authenticated(&server).await?;
+
+        ensure_requirements(&server, token_1(), TickSize::Tenth);
+
+        let err = client
+            .limit_order()
+            .token_id(token_1())
+            .price(dec!(0.5))
+            .size(dec!(21.04))
+            .side(Side::Buy)
+            .expiration(DateTime::<Utc>::from_str("1970-01-01T13:53:20Z").unwrap())
+            .expires_in(Duration::from_secs(90))
+            .build()
+            .await
+            .unwrap_err();
+        let msg = &err.downcast_ref::<Validation>().unwrap().reason;
+        assert_eq!(
+            msg,
+            "Unable to build Order: `expires_in` and `expiration` cannot both be set"
+        );
+
+        let err = client
+            .limit_order()
+            .token_id(token_1())
+            .price(dec!(0.5))
+            .size(dec!(21.04))
+            .side(Side::Buy)
+            .order_type(OrderType::FOK)
+            .expires_in(Duration::from_secs(90))
+            .build()
+            .await
+            .unwrap_err();
+        let msg = &err.downcast_ref::<Validation>().unwrap().reason;
+        assert_eq!(
+            msg,
+            "Only GTD orders may have a non-zero expiration, found FOK"
+        );
+
+        Ok(())
+    }
+
     #[tokio::test]
     async fn should_fail_on_post_only_for_non_gtc_gtd() -> anyhow::Result<()> {
         let server = MockServer::start();
@@ -1338,6 +1410,9 @@ mod limit {
 }
 
 mod market {
+    use kuest_client_sdk::clob::order_builder::SlippageAction;
+    use kuest_client_sdk::clob::simulation::simulate_market_order;
+    use kuest_client_sdk::clob::types::request::OrderBookSummaryRequest;
     use kuest_client_sdk::error::Validation;
     use serde_json::json;
 
@@ -3175,4 +3250,176 @@ mod market {
         assert_eq!(msg, "Sell Orders must specify their `amount`s in shares");
         Ok(())
     }
+
+    #[tokio::test]
+    async fn simulation_should_agree_with_built_order() -> anyhow::Result<()> {
+        let server = MockServer::start();
+        let client = create_authenticated(&server).await?;
+
+        ensure_requirements_for_market_price(
+            &server,
+            token_1(),
+            &[OrderSummary::builder()
+                .price(dec!(0.2))
+                .size(Decimal::ONE_HUNDRED)
+                .build()],
+            &[
+                OrderSummary::builder()
+                    .price(dec!(0.5))
+                    .size(Decimal::ONE_HUNDRED)
+                    .build(),
+                OrderSummary::builder()
+                    .price(dec!(0.4))
+                    .size(Decimal::ONE_HUNDRED)
+                    .build(),
+                OrderSummary::builder()
+                    .price(dec!(0.3))
+                    .size(Decimal::ONE_HUNDRED)
+                    .build(),
+            ],
+        );
+
+        let book = client
+            .order_book(
+                &OrderBookSummaryRequest::builder()
+                    .token_id(token_1())
+                    .build(),
+            )
+            .await?;
+        let simulation =
+            simulate_market_order(&book, Side::Buy, Amount::usdc(Decimal::ONE_HUNDRED)?, 0)?;
+
+        assert_eq!(simulation.fills.len(), 3);
+        assert_eq!(simulation.worst_price, Some(dec!(0.5)));
+        assert_eq!(simulation.midpoint, Some(dec!(0.25)));
+        assert!(simulation.is_fully_filled());
+
+        let signable_order = client
+            .market_order()
+            .token_id(token_1())
+            .amount(Amount::usdc(Decimal::ONE_HUNDRED)?)
+            .side(Side::Buy)
+            .build()
+            .await?;
+
+        // 100 USDC at the simulated worst price of 0.5
+        assert_eq!(signable_order.order.makerAmount, U256::from(100_000_000));
+        assert_eq!(signable_order.order.takerAmount, U256::from(200_000_000));
+
+        Ok(())
+    }
+
+    fn thin_book() -> (Vec<OrderSummary>, Vec<OrderSummary>) {
+        let level = |price| {
+            OrderSummary::builder()
+                .price(price)
+                .size(Decimal::ONE_HUNDRED)
+                .build()
+        };
+
+        (
+            vec![level(dec!(0.2)), level(dec!(0.3)), level(dec!(0.4))],
+            vec![level(dec!(0.7)), level(dec!(0.6)), level(dec!(0.5))],
+        )
+    }
+
+    #[tokio::test]
+    async fn should_reject_on_max_slippage() -> anyhow::Result<()> {
+        let server = MockServer::start();
+        let client = create_authenticated(&server).await?;
+
+        let (bids, asks) = thin_book();
+        ensure_requirements_for_market_price(&server, token_1(), &bids, &asks);
+
+        // 50 USDC at 0.5 and 60 USDC at 0.6 reach 0.6, more than 10% above the best ask
+        let err = client
+            .market_order()
+            .token_id(token_1())
+            .amount(Amount::usdc(Decimal::ONE_HUNDRED)?)
+            .side(Side::Buy)
+            .max_slippage_bps(1_000)
+            .build()
+            .await
+            .unwrap_err();
+        let msg = &err.downcast_ref::<Validation>().unwrap().reason;
+
+        assert!(msg.starts_with("Market price 0.6 for"));
+        assert!(msg.contains("breaches the slippage bound 0.55"));
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn should_clamp_buy_to_worst_price() -> anyhow::Result<()> {
+        let server = MockServer::start();
+        let client = create_authenticated(&server).await?;
+
+        let (bids, asks) = thin_book();
+        ensure_requirements_for_market_price(&server, token_1(), &bids, &asks);
+
+        let signable_order = client
+            .market_order()
+            .token_id(token_1())
+            .amount(Amount::usdc(Decimal::ONE_HUNDRED)?)
+            .side(Side::Buy)
+            .worst_price(dec!(0.58))
+            .max_slippage_bps(5_000)
+            .on_slippage(SlippageAction::Clamp)
+            .build()
+            .await?;
+
+        // Clamped to 0.58 and rounded down to the 0.1 tick
+        assert_eq!(signable_order.order.makerAmount, U256::from(100_000_000));
+        assert_eq!(signable_order.order.takerAmount, U256::from(200_000_000));
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn should_clamp_sell_to_worst_price() -> anyhow::Result<()> {
+        let server = MockServer::start();
+        let client = create_authenticated(&server).await?;
+
+        let (bids, asks) = thin_book();
+        ensure_requirements_for_market_price(&server, token_1(), &bids, &asks);
+
+        let signable_order = client
+            .market_order()
+            .token_id(token_1())
+            .amount(Amount::shares(dec!(150))?)
+            .side(Side::Sell)
+            .worst_price(dec!(0.35))
+            .on_slippage(SlippageAction::Clamp)
+            .build()
+            .await?;
+
+        // Clamped to 0.35 and rounded up to the 0.1 tick
+        assert_eq!(signable_order.order.makerAmount, U256::from(150_000_000));
+        assert_eq!(signable_order.order.takerAmount, U256::from(60_000_000));
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn slippage_bound_should_not_apply_to_explicit_price() -> anyhow::Result<()> {
+        let server = MockServer::start();
+        let client = create_authenticated(&server).await?;
+
+        let (bids, asks) = thin_book();
+        ensure_requirements_for_market_price(&server, token_1(), &bids, &asks);
+
+        let signable_order = client
+            .market_order()
+            .token_id(token_1())
+            .amount(Amount::shares(Decimal::ONE_HUNDRED)?)
+            .side(Side::Buy)
+            .price(dec!(0.6))
+            .worst_price(dec!(0.5))
+            .build()
+            .await?;
+
+        assert_eq!(signable_order.order.makerAmount, U256::from(60_000_000));
+
+        Ok(())
+    }
 }
@@ -12,7 +12,7 @@ use std::sync::Arc;
 use std::time::Duration;
 
 use futures_util::{SinkExt as _, StreamExt as _};
-use kuest_client_sdk::clob::ws::{Client, WsMessage};
+use kuest_client_sdk::clob::ws::{Client, WsEvent, WsMessage};
 use kuest_client_sdk::types::{Address, U256, b256};
 use kuest_client_sdk::ws::config::Config;
 use serde_json::json;
@@ -732,6 +732,9 @@ mod user_channel {
 mod reconnection {
     use std::sync::atomic::{AtomicBool, Ordering};
 
+    use kuest_client_sdk::clob::ws::ChannelType;
+    use kuest_client_sdk::ws::connection::ConnectionEvent;
+
     use super::*;
 
     /// Mock WebSocket server that can simulate disconnections and send messages.
@@ -1035,6 +1038,320 @@ mod reconnection {
             "Should receive best_bid_ask message after reconnection - this was the bug in issue #185"
         );
     }
+
+    #[tokio::test]
+    async fn order_tracker_backfills_after_reconnect() {
+        use httpmock::MockServer;
+        use kuest_client_sdk::auth::Credentials;
+        use kuest_client_sdk::clob::types::response::PostOrderResponse;
+        use kuest_client_sdk::clob::types::{OrderStatusType, TradeStatusType};
+        use kuest_client_sdk::clob::ws::OrderTracker;
+        use kuest_client_sdk::clob::ws::tracker::OrderState;
+        use kuest_client_sdk::types::Decimal;
+        use reqwest::StatusCode;
+        use rust_decimal_macros::dec;
+
+        use crate::common::{API_KEY, PASSPHRASE, SECRET, create_authenticated};
+
+        const ORDER_ID: &str = "0xff354cd7ca7539dfa9c28d90943ab5779a4eac34b9b37a757d7b32bdfb11790b";
+        const TRADE_ID: &str = "28c4d2eb-bbea-40e7-a9f0-b2fdb56b2c2e";
+
+        let http = MockServer::start();
+        let rest = create_authenticated(&http).await.unwrap();
+        let order_mock = http.mock(|when, then| {
+            when.method(httpmock::Method::GET)
+                .path(format!("/data/order/{ORDER_ID}"));
+            then.status(StatusCode::OK).json_body(json!({
+                "id": ORDER_ID,
+                "status": "MATCHED",
+                "owner": "9180014b-33c8-9240-a14b-bdca11c0a465",
+                "maker_address": "0x2222222222222222222222222222222222222222",
+                "market": payloads::MARKET_STR,
+                "asset_id": "52114319501245915516055106046884209969926127482827954674443846427813813222426",
+                "side": "SELL",
+                "original_size": "10",
+                "size_matched": "10",
+                "price": "0.57",
+                "associate_trades": [TRADE_ID],
+                "outcome": "YES",
+                "created_at": 1_672_290_687,
+                "expiration": "0",
+                "order_type": "GTC"
+            }));
+        });
+        let trades_mock = http.mock(|when, then| {
+            when.method(httpmock::Method::GET)
+                .path("/data/trades")
+                .query_param("id", TRADE_ID);
+            then.status(StatusCode::OK).json_body(json!({
+                "data": [{
+                    "id": TRADE_ID,
+                    "taker_order_id": "0x06bc63e346ed4ceddce9efd6b3af37c8f8f440c92fe7da6b2d0f9e4ccbc50c42",
+                    "market": payloads::MARKET_STR,
+                    "asset_id": "52114319501245915516055106046884209969926127482827954674443846427813813222426",
+                    "side": "BUY",
+                    "size": "10",
+                    "fee_rate_bps": "0",
+                    "price": "0.57",
+                    "status": "CONFIRMED",
+                    "match_time": "1672290701",
+                    "last_update": "1672290701",
+                    "outcome": "YES",
+                    "bucket_index": 0,
+                    "owner": "9180014b-33c8-9240-a14b-bdca11c0a465",
+                    "maker_address": "0x2222222222222222222222222222222222222222",
+                    "maker_orders": [{
+                        "order_id": ORDER_ID,
+                        "owner": "9180014b-33c8-9240-a14b-bdca11c0a465",
+                        "maker_address": "0x2222222222222222222222222222222222222222",
+                        "matched_amount": "10",
+                        "price": "0.57",
+                        "fee_rate_bps": "0",
+                        "asset_id": "52114319501245915516055106046884209969926127482827954674443846427813813222426",
+                        "outcome": "YES",
+                        "side": "SELL"
+                    }],
+                    "transaction_hash": "0xabcdefabcdefabcdefabcdefabcdefabcdefabcdefabcdefabcdefabcdefabcd",
+                    "trader_side": "MAKER"
+                }],
+                "limit": 1,
+                "count": 1,
+                "next_cursor": "LTE="
+            }));
+        });
+
+        let mut server = ReconnectableMockServer::start().await;
+        let credentials = Credentials::new(API_KEY, SECRET.to_owned(), PASSPHRASE.to_owned());
+        let client = Client::new(&server.ws_url("/ws/user"), config())
+            .unwrap()
+            .authenticate(credentials, Address::ZERO)
+            .unwrap();
+
+        let tracker = OrderTracker::new();
+        let stream = client.track_orders(&tracker, vec![], rest).unwrap();
+        let mut stream = Box::pin(stream);
+        let _: Option<String> = server.recv_subscription().await;
+
+        let response = PostOrderResponse::builder()
+            .making_amount(Decimal::ZERO)
+            .taking_amount(Decimal::ZERO)
+            .order_id(ORDER_ID)
+            .status(OrderStatusType::Live)
+            .success(true)
+            .build();
+        assert!(tracker.register(&response));
+
+        // The order is filled while the user channel is down
+        server.disconnect_all();
+        tokio::time::sleep(Duration::from_millis(100)).await;
+        server.allow_reconnect();
+        assert!(server.recv_subscription().await.is_some());
+
+        let mut transitions = Vec::new();
+        for _ in 0..2 {
+            let transition = timeout(Duration::from_secs(2), stream.next())
+                .await
+                .unwrap()
+                .unwrap()
+                .unwrap();
+            transitions.push(transition);
+        }
+
+        let order = tracker.order(ORDER_ID).unwrap();
+        assert_eq!(order.state, OrderState::Filled, "{transitions:?}");
+        assert_eq!(order.filled_size(), dec!(10));
+        assert_eq!(order.average_fill_price(), Some(dec!(0.57)));
+        assert_eq!(order.fills[0].status, TradeStatusType::Confirmed);
+        order_mock.assert();
+        trades_mock.assert();
+    }
+
+    #[tokio::test]
+    async fn order_tracker_keeps_tracking_after_failed_backfill() {
+        use httpmock::MockServer;
+        use kuest_client_sdk::auth::Credentials;
+        use kuest_client_sdk::clob::types::OrderStatusType;
+        use kuest_client_sdk::clob::types::response::PostOrderResponse;
+        use kuest_client_sdk::clob::ws::OrderTracker;
+        use kuest_client_sdk::clob::ws::tracker::{OrderState, Transition};
+        use kuest_client_sdk::types::Decimal;
+        use reqwest::StatusCode;
+
+        use crate::common::{API_KEY, PASSPHRASE, SECRET, create_authenticated};
+
+        const ORDER_ID: &str = "0xff354cd7ca7539dfa9c28d90943ab5779a4eac34b9b37a757d7b32bdfb11790b";
+        const CANCELED_ID: &str =
+            "0x06bc63e346ed4ceddce9efd6b3af37c8f8f440c92fe7da6b2d0f9e4ccbc50c42";
+
+        let http = MockServer::start();
+        let rest = create_authenticated(&http).await.unwrap();
+        let order_mock = http.mock(|when, then| {
+            when.method(httpmock::Method::GET)
+                .path(format!("/data/order/{ORDER_ID}"));
+            then.status(StatusCode::INTERNAL_SERVER_ERROR);
+        });
+        let canceled_mock = http.mock(|when, then| {
+            when.method(httpmock::Method::GET)
+                .path(format!("/data/order/{CANCELED_ID}"));
+            then.status(StatusCode::OK).json_body(json!({
+                "id": CANCELED_ID,
+                "status": "CANCELED",
+                "owner": "9180014b-33c8-9240-a14b-bdca11c0a465",
+                "maker_address": "0x2222222222222222222222222222222222222222",
+                "market": payloads::MARKET_STR,
+                "asset_id": "52114319501245915516055106046884209969926127482827954674443846427813813222426",
+                "side": "SELL",
+                "original_size": "10",
+                "size_matched": "0",
+                "price": "0.57",
+                "associate_trades": [],
+                "outcome": "YES",
+                "created_at": 1_672_290_687,
+                "expiration": "0",
+                "order_type": "GTC"
+            }));
+        });
+
+        let mut server = ReconnectableMockServer::start().await;
+        let credentials = Credentials::new(API_KEY, SECRET.to_owned(), PASSPHRASE.to_owned());
+        let client = Client::new(&server.ws_url("/ws/user"), config())
+            .unwrap()
+            .authenticate(credentials, Address::ZERO)
+            .unwrap();
+
+        let tracker = OrderTracker::new();
+        let stream = client.track_orders(&tracker, vec![], rest).unwrap();
+        let mut stream = Box::pin(stream);
+        let _: Option<String> = server.recv_subscription().await;
+
+        let response = PostOrderResponse::builder()
+            .making_amount(Decimal::ZERO)
+            .taking_amount(Decimal::ZERO)
+            .order_id(ORDER_ID)
+            .status(OrderStatusType::Live)
+            .success(true)
+            .build();
+        assert!(tracker.register(&response));
+        let mut canceled = response.clone();
+        canceled.order_id = CANCELED_ID.to_owned();
+        assert!(tracker.register(&canceled));
+
+        server.disconnect_all();
+        tokio::time::sleep(Duration::from_millis(100)).await;
+        server.allow_reconnect();
+        assert!(server.recv_subscription().await.is_some());
+
+        // One order is still backfilled, and the other one's failure is reported without ending
+        // the stream
+        let transition = timeout(Duration::from_secs(2), stream.next())
+            .await
+            .unwrap()
+            .unwrap()
+            .unwrap();
+        assert!(matches!(
+            &transition,
+            Transition::Order { order_id, to: OrderState::Canceled, .. } if order_id == CANCELED_ID
+        ));
+        let next = timeout(Duration::from_secs(2), stream.next())
+            .await
+            .unwrap();
+        assert!(matches!(next, Some(Err(_))));
+
+        let mut cancellation = payloads::order();
+        cancellation["type"] = json!("CANCELLATION");
+        server.send(&cancellation.to_string());
+
+        let transition = timeout(Duration::from_secs(2), stream.next())
+            .await
+            .unwrap()
+            .unwrap()
+            .unwrap();
+        assert!(matches!(
+            transition,
+            Transition::Order {
+                to: OrderState::Canceled,
+                ..
+            }
+        ));
+        order_mock.assert();
+        canceled_mock.assert();
+    }
+
+    #[tokio::test]
+    async fn connection_events_follow_reconnect() {
+        let mut server = ReconnectableMockServer::start().await;
+        let client = Client::new(&server.ws_url("/ws/market"), config()).unwrap();
+
+        let events = client.connection_events(ChannelType::Market).unwrap();
+        let mut events = Box::pin(events);
+        let _stream = client
+            .subscribe_orderbook(vec![payloads::asset_id()])
+            .unwrap();
+        let _: Option<String> = server.recv_subscription().await;
+
+        let mut next = async || {
+            timeout(Duration::from_secs(2), events.next())
+                .await
+                .unwrap()
+                .unwrap()
+        };
+        assert_eq!(next().await, ConnectionEvent::Connected);
+
+        server.disconnect_all();
+        tokio::time::sleep(Duration::from_millis(100)).await;
+        server.allow_reconnect();
+
+        // Connections may be dropped again until the server allows them back
+        let mut seen = Vec::new();
+        while seen.last() != Some(&ConnectionEvent::Resubscribed) {
+            seen.push(next().await);
+        }
+        assert!(
+            matches!(
+                seen.as_slice(),
+                [
+                    ConnectionEvent::Disconnected { .. },
+                    ConnectionEvent::Reconnecting { attempt: 0 },
+                    ..,
+                    ConnectionEvent::Connected,
+                    ConnectionEvent::Resubscribed,
+                ]
+            ),
+            "{seen:?}"
+        );
+        assert!(server.recv_subscription().await.is_some());
+    }
+
+    #[tokio::test]
+    async fn market_events_report_reconnect() {
+        let mut server = ReconnectableMockServer::start().await;
+        let client = Client::new(&server.ws_url("/ws/market"), config()).unwrap();
+
+        let stream = client
+            .subscribe_market_events(vec![payloads::asset_id()])
+            .unwrap();
+        let mut stream = Box::pin(stream);
+        let _: Option<String> = server.recv_subscription().await;
+
+        server.disconnect_all();
+        tokio::time::sleep(Duration::from_millis(100)).await;
+        server.allow_reconnect();
+
+        let event = timeout(Duration::from_secs(2), stream.next())
+            .await
+            .unwrap()
+            .unwrap();
+        assert!(matches!(event, WsEvent::Reconnected), "{event:?}");
+
+        // The re-sent subscription brings a fresh snapshot
+        assert!(server.recv_subscription().await.is_some());
+        server.send(&payloads::book().to_string());
+        let event = timeout(Duration::from_secs(2), stream.next())
+            .await
+            .unwrap()
+            .unwrap();
+        assert!(matches!(event, WsEvent::Message(WsMessage::Book(_))));
+    }
 }
 
 mod unsubscribe {
@@ -1766,3 +2083,554 @@ mod message_parsing {
         assert_eq!(ltp.timestamp, 1_750_428_146_322);
     }
 }
+
+mod local_orderbook {
+    use httpmock::MockServer;
+    use kuest_client_sdk::clob::Client as RestClient;
+    use kuest_client_sdk::clob::Config as RestConfig;
+    use reqwest::StatusCode;
+    use rust_decimal_macros::dec;
+
+    use super::*;
+
+    fn price_change(size: &str, best_bid: &str) -> serde_json::Value {
+        json!({
+            "event_type": "price_change",
+            "market": payloads::MARKET_STR,
+            "timestamp": "123456789500",
+            "price_changes": [
+                {
+                    "asset_id": payloads::ASSET_ID_STR,
+                    "price": "0.51",
+                    "size": size,
+                    "side": "BUY",
+                    "best_bid": best_bid,
+                    "best_ask": "0.52"
+                }
+            ]
+        })
+    }
+
+    #[tokio::test]
+    async fn applies_price_changes_to_book_snapshot() {
+        let mut server = MockWsServer::start().await;
+        let client = Client::new(&server.ws_url("/ws/market"), Config::default()).unwrap();
+        let rest = RestClient::new("http://localhost", RestConfig::default()).unwrap();
+
+        let stream = client
+            .subscribe_local_orderbook(vec![payloads::asset_id()], rest)
+            .unwrap();
+        let mut stream = Box::pin(stream);
+        let _: Option<String> = server.recv_subscription().await;
+
+        server.send(&payloads::book().to_string());
+        let book = timeout(Duration::from_secs(2), stream.next())
+            .await
+            .unwrap()
+            .unwrap()
+            .unwrap();
+        assert_eq!(book.best_bid(), Some(dec!(0.50)));
+        assert_eq!(book.best_ask(), Some(dec!(0.52)));
+
+        server.send(&price_change("40", "0.51").to_string());
+        let book = timeout(Duration::from_secs(2), stream.next())
+            .await
+            .unwrap()
+            .unwrap()
+            .unwrap();
+        assert_eq!(book.best_bid(), Some(dec!(0.51)));
+        assert_eq!(book.bids().next().unwrap().size, dec!(40));
+        assert_eq!(book.bids().count(), 4);
+        assert_eq!(book.timestamp(), 123_456_789_500);
+    }
+
+    #[tokio::test]
+    async fn fetches_snapshot_when_out_of_sync() {
+        let mut server = MockWsServer::start().await;
+        let client = Client::new(&server.ws_url("/ws/market"), Config::default()).unwrap();
+
+        let http = MockServer::start();
+        let mock = http.mock(|when, then| {
+            when.method(httpmock::Method::GET)
+                .path("/book")
+                .query_param("token_id", payloads::ASSET_ID_STR);
+            then.status(StatusCode::OK).json_body(json!({
+                "market": payloads::MARKET_STR,
+                "asset_id": payloads::ASSET_ID_STR,
+                "tick_size": "0.01",
+                "min_order_size": "5",
+                "neg_risk": false,
+                "timestamp": "123456789600",
+                "bids": [{ "price": "0.45", "size": "10" }],
+                "asks": [{ "price": "0.55", "size": "10" }]
+            }));
+        });
+        let rest = RestClient::new(&http.base_url(), RestConfig::default()).unwrap();
+
+        let stream = client
+            .subscribe_local_orderbook(vec![payloads::asset_id()], rest)
+            .unwrap();
+        let mut stream = Box::pin(stream);
+        let _: Option<String> = server.recv_subscription().await;
+
+        // A delta without a prior snapshot cannot be applied
+        server.send(&price_change("40", "0.51").to_string());
+        let book = timeout(Duration::from_secs(2), stream.next())
+            .await
+            .unwrap()
+            .unwrap()
+            .unwrap();
+        assert_eq!(book.best_bid(), Some(dec!(0.45)));
+        assert_eq!(book.best_ask(), Some(dec!(0.55)));
+        assert_eq!(book.timestamp(), 123_456_789_600);
+
+        // The delta disagrees with the local best bid, so the book is re-seeded again
+        server.send(&price_change("40", "0.60").to_string());
+        let book = timeout(Duration::from_secs(2), stream.next())
+            .await
+            .unwrap()
+            .unwrap()
+            .unwrap();
+        assert_eq!(book.best_bid(), Some(dec!(0.45)));
+
+        mock.assert_calls(2);
+    }
+
+    #[tokio::test]
+    async fn recovers_from_failed_snapshot_request() {
+        let mut server = MockWsServer::start().await;
+        let mut config = Config::default();
+        config.reconnect.initial_backoff = Duration::from_millis(50);
+        let client = Client::new(&server.ws_url("/ws/market"), config).unwrap();
+
+        let http = MockServer::start();
+        let mut failing = http.mock(|when, then| {
+            when.method(httpmock::Method::GET).path("/book");
+            then.status(StatusCode::INTERNAL_SERVER_ERROR)
+                .json_body(json!({ "error": "internal error" }));
+        });
+        let rest = RestClient::new(&http.base_url(), RestConfig::default()).unwrap();
+
+        let stream = client
+            .subscribe_local_orderbook(vec![payloads::asset_id()], rest)
+            .unwrap();
+        let mut stream = Box::pin(stream);
+        let _: Option<String> = server.recv_subscription().await;
+
+        server.send(&price_change("40", "0.51").to_string());
+        let result = timeout(Duration::from_secs(2), stream.next())
+            .await
+            .unwrap()
+            .unwrap();
+        result.unwrap_err();
+        failing.assert_calls(1);
+        failing.delete();
+
+        let mock = http.mock(|when, then| {
+            when.method(httpmock::Method::GET).path("/book");
+            then.status(StatusCode::OK).json_body(json!({
+                "market": payloads::MARKET_STR,
+                "asset_id": payloads::ASSET_ID_STR,
+                "tick_size": "0.01",
+                "min_order_size": "5",
+                "neg_risk": false,
+                "timestamp": "123456789600",
+                "bids": [{ "price": "0.45", "size": "10" }],
+                "asks": [{ "price": "0.52", "size": "10" }]
+            }));
+        });
+
+        // The snapshot is retried after the backoff without waiting for another message
+        let book = timeout(Duration::from_secs(2), stream.next())
+            .await
+            .unwrap()
+            .unwrap()
+            .unwrap();
+        assert_eq!(book.best_bid(), Some(dec!(0.45)));
+        mock.assert_calls(1);
+
+        // Deltas apply to the recovered book
+        server.send(&price_change("40", "0.51").to_string());
+        let book = timeout(Duration::from_secs(2), stream.next())
+            .await
+            .unwrap()
+            .unwrap()
+            .unwrap();
+        assert_eq!(book.bids().count(), 2);
+        mock.assert_calls(1);
+    }
+}
+
+mod lag {
+    use kuest_client_sdk::ws::WsError;
+
+    use super::*;
+
+    fn config(resync_after_lag: bool) -> Config {
+        let mut config = Config::default();
+        config.subscriber_buffer = 2;
+        config.resync_after_lag = resync_after_lag;
+        config
+    }
+
+    #[tokio::test]
+    async fn market_events_report_lag_and_resync() {
+        let mut server = MockWsServer::start().await;
+        let client = Client::new(&server.ws_url("/ws/market"), config(true)).unwrap();
+
+        let stream = client
+            .subscribe_market_events(vec![payloads::asset_id()])
+            .unwrap();
+        let mut stream = Box::pin(stream);
+        let _: Option<String> = server.recv_subscription().await;
+
+        for _ in 0..5 {
+            server.send(&payloads::book().to_string());
+        }
+        tokio::time::sleep(Duration::from_millis(200)).await;
+
+        let event = timeout(Duration::from_secs(2), stream.next())
+            .await
+            .unwrap()
+            .unwrap();
+        assert!(matches!(event, WsEvent::Lagged { skipped: 3 }), "{event:?}");
+
+        let resync = server.recv_subscription().await.unwrap();
+        assert!(resync.contains(&payloads::asset_id().to_string()));
+
+        // The two buffered messages are still delivered
+        for _ in 0..2 {
+            let event = timeout(Duration::from_secs(2), stream.next())
+                .await
+                .unwrap()
+                .unwrap();
+            assert!(matches!(event, WsEvent::Message(WsMessage::Book(_))));
+        }
+    }
+
+    #[tokio::test]
+    async fn lagging_streams_share_one_resync() {
+        let mut server = MockWsServer::start().await;
+        let client = Client::new(&server.ws_url("/ws/market"), config(true)).unwrap();
+
+        let mut streams = Vec::new();
+        for _ in 0..3 {
+            let stream = client
+                .subscribe_market_events(vec![payloads::asset_id()])
+                .unwrap();
+            streams.push(Box::pin(stream));
+        }
+        let _: Option<String> = server.recv_subscription().await;
+
+        for _ in 0..5 {
+            server.send(&payloads::book().to_string());
+        }
+        tokio::time::sleep(Duration::from_millis(200)).await;
+
+        for stream in &mut streams {
+            let event = timeout(Duration::from_secs(2), stream.next())
+                .await
+                .unwrap()
+                .unwrap();
+            assert!(matches!(event, WsEvent::Lagged { skipped: 3 }), "{event:?}");
+        }
+
+        let resync = server.recv_subscription().await.unwrap();
+        assert!(resync.contains(&payloads::asset_id().to_string()));
+        assert!(server.recv_subscription().await.is_none());
+    }
+
+    #[tokio::test]
+    async fn typed_streams_continue_after_lag() {
+        let mut server = MockWsServer::start().await;
+        let client = Client::new(&server.ws_url("/ws/market"), config(false)).unwrap();
+
+        let stream = client
+            .subscribe_orderbook(vec![payloads::asset_id()])
+            .unwrap();
+        let mut stream = Box::pin(stream);
+        let _: Option<String> = server.recv_subscription().await;
+
+        for _ in 0..5 {
+            server.send(&payloads::book().to_string());
+        }
+        tokio::time::sleep(Duration::from_millis(200)).await;
+
+        let lagged = timeout(Duration::from_secs(2), stream.next())
+            .await
+            .unwrap()
+            .unwrap()
+            .unwrap_err();
+        assert!(matches!(
+            lagged.downcast_ref::<WsError>(),
+            Some(WsError::Lagged { count: 3 })
+        ));
+
+        let book = timeout(Duration::from_secs(2), stream.next())
+            .await
+            .unwrap()
+            .unwrap()
+            .unwrap();
+        assert_eq!(book.asset_id, payloads::asset_id());
+        assert!(server.recv_subscription().await.is_none());
+    }
+}
+
+mod sharding {
+    use std::str::FromStr as _;
+
+    use kuest_client_sdk::clob::ws::ChannelType;
+
+    use super::*;
+    use crate::payloads::{ASSET_ID_STR, OTHER_ASSET_ID_STR};
+
+    fn config() -> Config {
+        let mut config = Config::default();
+        config.max_assets_per_connection = Some(1);
+        config.max_market_connections = 2;
+        config
+    }
+
+    #[tokio::test]
+    async fn market_assets_are_spread_over_connections() {
+        let mut server = MockWsServer::start().await;
+        let client = Client::new(&server.ws_url("/ws/market"), config()).unwrap();
+
+        let stream = client
+            .subscribe_last_trade_price(vec![payloads::asset_id(), payloads::other_asset_id()])
+            .unwrap();
+        let mut stream = Box::pin(stream);
+
+        // One subscription request per connection, each carrying a single asset
+        let mut requests = [
+            server.recv_subscription().await.unwrap(),
+            server.recv_subscription().await.unwrap(),
+        ];
+        requests.sort_by_key(|request| request.contains(OTHER_ASSET_ID_STR));
+        assert!(requests[0].contains(ASSET_ID_STR) && !requests[0].contains(OTHER_ASSET_ID_STR));
+        assert!(requests[1].contains(OTHER_ASSET_ID_STR) && !requests[1].contains(ASSET_ID_STR));
+
+        // Both connections feed the merged stream
+        server.send(&payloads::last_trade_price(ASSET_ID_STR).to_string());
+        server.send(&payloads::last_trade_price(OTHER_ASSET_ID_STR).to_string());
+
+        let mut received = Vec::new();
+        for _ in 0..2 {
+            let price = timeout(Duration::from_secs(2), stream.next())
+                .await
+                .unwrap()
+                .unwrap()
+                .unwrap();
+            received.push(price.asset_id);
+        }
+        received.sort();
+        let mut expected = vec![payloads::asset_id(), payloads::other_asset_id()];
+        expected.sort();
+        assert_eq!(received, expected);
+
+        // The pool is full
+        let third = U256::from_str("12345").unwrap();
+        let Err(error) = client.subscribe_last_trade_price(vec![third]) else {
+            panic!("subscribing beyond the pool should fail");
+        };
+        assert!(
+            error.to_string().contains("market channel is full"),
+            "{error}"
+        );
+
+        // Unsubscribing closes the emptied connection, making room for the next asset
+        client
+            .unsubscribe_orderbook(&[payloads::asset_id()])
+            .unwrap();
+        let _: Option<String> = server.recv_subscription().await;
+        let _stream = client.subscribe_last_trade_price(vec![third]).unwrap();
+
+        let request = server.recv_subscription().await.unwrap();
+        assert!(request.contains("12345"));
+        assert!(client.is_connected(ChannelType::Market));
+    }
+
+    #[tokio::test]
+    async fn unsubscribing_drains_partly_filled_connection() {
+        let mut server = MockWsServer::start().await;
+        let mut config = config();
+        config.max_assets_per_connection = Some(2);
+        let client = Client::new(&server.ws_url("/ws/market"), config).unwrap();
+
+        // Two assets fill the first connection and the third one opens another
+        let third = U256::from_str("12345").unwrap();
+        let stream = client
+            .subscribe_market_events(vec![
+                payloads::other_asset_id(),
+                third,
+                payloads::asset_id(),
+            ])
+            .unwrap();
+        let mut stream = Box::pin(stream);
+        for _ in 0..2 {
+            let _: Option<String> = server.recv_subscription().await;
+        }
+        assert_eq!(client.subscription_count(), 2);
+
+        // Once one asset is gone, the second connection's asset moves to the first one
+        client.unsubscribe_orderbook(&[third]).unwrap();
+        let unsubscribe = server.recv_subscription().await.unwrap();
+        assert!(unsubscribe.contains("12345"), "{unsubscribe}");
+        let subscribe = server.recv_subscription().await.unwrap();
+        assert!(subscribe.contains(ASSET_ID_STR), "{subscribe}");
+        assert!(!subscribe.contains("unsubscribe"), "{subscribe}");
+
+        // Both connections send the snapshot, which is only yielded once
+        server.send(&payloads::book().to_string());
+        let event = timeout(Duration::from_secs(2), stream.next())
+            .await
+            .unwrap()
+            .unwrap();
+        assert!(
+            matches!(event, WsEvent::Message(WsMessage::Book(_))),
+            "{event:?}"
+        );
+
+        // The snapshot completes the handover, so the drained connection lets go of the asset
+        let unsubscribe = server.recv_subscription().await.unwrap();
+        assert!(unsubscribe.contains(ASSET_ID_STR), "{unsubscribe}");
+        assert!(unsubscribe.contains("unsubscribe"), "{unsubscribe}");
+        tokio::time::sleep(Duration::from_millis(100)).await;
+        assert_eq!(client.subscription_count(), 2);
+
+        server.send(&payloads::last_trade_price(ASSET_ID_STR).to_string());
+        let event = timeout(Duration::from_secs(2), stream.next())
+            .await
+            .unwrap()
+            .unwrap();
+        assert!(
+            matches!(event, WsEvent::Message(WsMessage::LastTradePrice(_))),
+            "{event:?}"
+        );
+        assert!(
+            timeout(Duration::from_millis(200), stream.next())
+                .await
+                .is_err(),
+            "messages should not be repeated after the move"
+        );
+    }
+}
+
+mod replay {
+    use std::io::Write;
+    use std::sync::Mutex;
+
+    use kuest_client_sdk::ws::record::{Frame, Recorder, Replay, Speed};
+
+    use super::*;
+    use crate::payloads::ASSET_ID_STR;
+
+    /// Writer whose bytes can be read back while a recorder holds it.
+    #[derive(Clone, Default)]
+    struct SharedBuffer(Arc<Mutex<Vec<u8>>>);
+
+    impl Write for SharedBuffer {
+        fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+            self.0.lock().unwrap().write(buf)
+        }
+
+        fn flush(&mut self) -> std::io::Result<()> {
+            Ok(())
+        }
+    }
+
+    #[tokio::test]
+    async fn recorded_feed_replays_without_network() {
+        let mut server = MockWsServer::start().await;
+        let endpoint = server.ws_url("/ws/market");
+
+        // Record a live session
+        let buffer = SharedBuffer::default();
+        let recorder = Recorder::new(buffer.clone());
+        let mut config = Config::default();
+        config.recorder = Some(recorder.clone());
+        let client = Client::new(&endpoint, config).unwrap();
+
+        let stream = client
+            .subscribe_orderbook(vec![payloads::asset_id()])
+            .unwrap();
+        let mut stream = Box::pin(stream);
+        let _: Option<String> = server.recv_subscription().await;
+
+        server.send(&payloads::book().to_string());
+        server.send(&payloads::last_trade_price(ASSET_ID_STR).to_string());
+        timeout(Duration::from_secs(2), stream.next())
+            .await
+            .unwrap()
+            .unwrap()
+            .unwrap();
+        tokio::time::sleep(Duration::from_millis(50)).await;
+        drop(stream);
+        drop(client);
+        drop(server);
+        recorder.flush().unwrap();
+
+        let recording = buffer.0.lock().unwrap().clone();
+        let replay = Replay::from_reader(recording.as_slice())
+            .unwrap()
+            .speed(Speed::Max)
+            .manual_start();
+        assert_eq!(replay.frames().len(), 2);
+
+        // Replay it with the server gone, subscribing to both streams before playback starts
+        let mut config = Config::default();
+        config.replay = Some(replay.clone());
+        let client = Client::new(&endpoint, config).unwrap();
+
+        let books = client
+            .subscribe_orderbook(vec![payloads::asset_id()])
+            .unwrap();
+        let prices = client
+            .subscribe_last_trade_price(vec![payloads::asset_id()])
+            .unwrap();
+        replay.start();
+
+        let books: Vec<_> = timeout(Duration::from_secs(2), books.collect())
+            .await
+            .unwrap();
+        assert_eq!(books.len(), 1);
+        assert_eq!(books[0].as_ref().unwrap().asset_id, payloads::asset_id());
+
+        let prices: Vec<_> = timeout(Duration::from_secs(2), prices.collect())
+            .await
+            .unwrap();
+        assert_eq!(prices.len(), 1);
+        assert_eq!(
+            prices[0].as_ref().unwrap().price,
+            rust_decimal_macros::dec!(0.456)
+        );
+    }
+
+    #[tokio::test]
+    async fn replay_goes_on_past_stream_that_is_never_polled() {
+        let endpoint = "ws://127.0.0.1:1/ws/market";
+        let frames = (0..5)
+            .map(|i| Frame::new(i, endpoint, payloads::book().to_string()))
+            .collect();
+        let replay = Replay::from_frames(frames).speed(Speed::Max).manual_start();
+
+        let mut config = Config::default();
+        config.subscriber_buffer = 1;
+        config.replay = Some(replay.clone());
+        let client = Client::new(endpoint, config).unwrap();
+
+        let books = client
+            .subscribe_orderbook(vec![payloads::asset_id()])
+            .unwrap();
+        // Held for the whole replay without ever being polled
+        let _idle = client
+            .subscribe_orderbook(vec![payloads::asset_id()])
+            .unwrap();
+        replay.start();
+
+        let books: Vec<_> = timeout(Duration::from_secs(10), books.collect())
+            .await
+            .expect("replay should not wait for the idle stream forever");
+        assert!(!books.is_empty());
+    }
+}